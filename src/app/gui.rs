@@ -4,7 +4,7 @@
 //  Created:
 //    11 Apr 2024, 17:56:50
 //  Last edited:
-//    01 May 2024, 19:44:01
+//    16 Oct 2026, 11:57:05
 //  Auto updated?
 //    Yes
 //
@@ -12,19 +12,1119 @@
 //!   Implements the actual user interface renderer.
 //
 
-use egui::Context;
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use egui::{Context, Key, Modifiers, RichText, Ui};
 use egui_winit::egui;
+use enum_debug::EnumDebug as _;
+use error_trace::ErrorTrace as _;
+use log::{debug, info, warn};
+use parking_lot::Mutex;
+
+use crate::clock::Clock;
+use crate::event::{Event, Severity};
+use crate::i18n::{Lang, Text};
+use crate::poller::{build_client, poll_once};
+use crate::state::{ActivityBucket, AppState, ConfigFile, GuiLayout, HttpCache, MutableAppState, MuteState, ServerConfig, SortKey};
+
+
+/***** CONSTANTS *****/
+/// The choices offered when snoozing an event title, as a label and a duration ([`None`] for until the next restart).
+const SNOOZE_CHOICES: [(Text, Option<Duration>); 3] =
+    [(Text::SnoozeHour, Some(Duration::from_secs(60 * 60))), (Text::SnoozeDay, Some(Duration::from_secs(24 * 60 * 60))), (Text::SnoozeRestart, None)];
+/// The number of most recent events shown in the [mini layout](GuiLayout::Mini).
+const MINI_EVENTS: usize = 5;
+/// The width of a single minute in the activity sparkline, in points.
+const SPARKLINE_BAR_WIDTH: f32 = 2.0;
+/// The height of the activity sparkline, in points.
+const SPARKLINE_HEIGHT: f32 = 16.0;
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Decides the color of the dot shown in front of events that have no icon.
+///
+/// # Arguments
+/// - `ui`: The [`Ui`] whose visuals to follow.
+/// - `severity`: The [`Severity`] of the event.
+///
+/// # Returns
+/// The [`Color32`](egui::Color32) of the dot.
+fn severity_color(ui: &Ui, severity: Severity) -> egui::Color32 {
+    match severity {
+        Severity::Info => ui.visuals().weak_text_color(),
+        Severity::Warning => ui.visuals().warn_fg_color,
+        Severity::Error => ui.visuals().error_fg_color,
+        Severity::Critical => egui::Color32::RED,
+    }
+}
+
+/// Describes how long ago something happened in a compact, human-friendly way.
+///
+/// # Arguments
+/// - `elapsed`: The time that has passed since.
+/// - `lang`: The [`Lang`] to describe it in.
+///
+/// # Returns
+/// A string like `"12s ago"`, `"5m ago"` or `"3h ago"`.
+fn ago(elapsed: chrono::Duration, lang: Lang) -> String {
+    let secs: i64 = elapsed.num_seconds().max(0);
+    let (text, n): (Text, i64) = if secs < 60 {
+        (Text::AgoSeconds, secs)
+    } else if secs < 3600 {
+        (Text::AgoMinutes, secs / 60)
+    } else {
+        (Text::AgoHours, secs / 3600)
+    };
+    lang.tr(text).replace("{n}", &n.to_string())
+}
+
+/// Draws the choices for how long to snooze an event title.
+///
+/// # Arguments
+/// - `ui`: The [`Ui`] to draw in, typically a menu.
+/// - `now`: The current time, to compute the end of timed snoozes from.
+/// - `lang`: The [`Lang`] to label the choices in.
+///
+/// # Returns
+/// The [`MuteState`] that the user picked, if they picked any.
+fn snooze_choices(ui: &mut Ui, now: DateTime<Local>, lang: Lang) -> Option<MuteState> {
+    let mut choice: Option<MuteState> = None;
+    for (text, duration) in SNOOZE_CHOICES {
+        if ui.button(lang.tr(text)).clicked() {
+            choice = Some(duration.map(|duration| MuteState::lasting(now, duration)).unwrap_or(MuteState::NextBoot));
+            ui.close_menu();
+        }
+    }
+    choice
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Describes what the user wants to do with an imported config file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ImportChoice {
+    /// Only add what we don't have yet.
+    Merge,
+    /// Overwrite the current config.
+    Replace,
+    /// Don't import at all.
+    Cancel,
+}
+
+
+
+/// Describes something that the user can do with all selected events at once.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum BulkAction {
+    /// Marks the events as read.
+    MarkRead,
+    /// Removes the events from the list.
+    Delete,
+    /// Copies the events as JSON to the clipboard.
+    Export,
+}
+
+
+
+/// The outcome of a connection test, once it's done: either the number of events the server reported, or why it failed.
+type TestResult = Result<usize, String>;
+
+
+
 
 
 /***** LIBRARY *****/
-pub struct Gui;
+pub struct Gui {
+    /// The state of the app that we visualize.
+    state: AppState,
+    /// A config file that was dropped onto the window and that awaits the user's decision.
+    pending_import: Option<(PathBuf, ConfigFile)>,
+    /// An error to show to the user until they dismiss it.
+    toast: Option<String>,
+    /// The [keys](Event::key()) of the events that are currently selected in the list.
+    selected: HashSet<String>,
+    /// The key of the event from which shift-clicks select a range.
+    anchor: Option<String>,
+    /// The key of the event that was last clicked or moved to with the arrow keys.
+    cursor: Option<String>,
+    /// The key of the event whose details are shown, if any.
+    detail: Option<String>,
+    /// The key of the event to scroll to the next time the list is drawn, if any.
+    reveal: Option<String>,
+    /// The tab of the event list that is shown, where `0` is the one with all events and `i` the one with the events of the `i - 1`th
+    /// server in [`MutableAppState::servers`].
+    selected_server: usize,
+    /// The text that events must contain (in their title, message or source) to be listed. Matched case-insensitively.
+    query: String,
+    /// The least severe events that are listed.
+    min_severity: Severity,
+    /// Whether this Gui is drawn in the popover near the tray icon, which always uses the compact layout.
+    popover: bool,
+    /// Whether the panel with the servers is open.
+    show_servers: bool,
+    /// Whether the panel with the snoozed event titles is open.
+    show_snoozed: bool,
+    /// The connection tests that were started per server, which are [`None`] while still running.
+    tests: HashMap<String, Arc<Mutex<Option<TestResult>>>>,
+}
 impl Gui {
+    /// Constructor for the Gui.
+    ///
+    /// # Arguments
+    /// - `state`: The [`AppState`] that we visualize.
+    ///
+    /// # Returns
+    /// A new Gui.
+    #[inline]
+    pub fn new(state: AppState) -> Self {
+        Self {
+            state,
+            pending_import: None,
+            toast: None,
+            selected: HashSet::new(),
+            anchor: None,
+            cursor: None,
+            detail: None,
+            reveal: None,
+            selected_server: 0,
+            query: String::new(),
+            min_severity: Severity::Info,
+            popover: false,
+            show_servers: false,
+            show_snoozed: false,
+            tests: HashMap::new(),
+        }
+    }
+
+    /// Constructor for a Gui that is drawn in the popover near the tray icon.
+    ///
+    /// # Arguments
+    /// - `state`: The [`AppState`] that we visualize.
+    ///
+    /// # Returns
+    /// A new Gui that always uses the compact layout.
+    #[inline]
+    pub fn popover(state: AppState) -> Self { Self { popover: true, ..Self::new(state) } }
+
+    /// Scrolls the event list to the given event the next time it's drawn, and shows its details.
+    ///
+    /// # Arguments
+    /// - `key`: The [key](Event::key()) of the event to show.
+    pub fn reveal(&mut self, key: String) {
+        // The event may be from any server, so make sure its tab is the one shown
+        self.selected_server = 0;
+        self.detail = Some(key.clone());
+        self.reveal = Some(key);
+    }
+
+    /// Returns whether this Gui is drawn in the popover near the tray icon.
+    ///
+    /// # Returns
+    /// True if it was created with [`Gui::popover()`], false otherwise.
+    #[inline]
+    pub fn is_popover(&self) -> bool { self.popover }
+
+    /// Returns the state that the Gui visualizes.
+    ///
+    /// # Returns
+    /// A reference to the [`AppState`].
+    #[inline]
+    pub fn state(&self) -> &AppState { &self.state }
+
+    /// Returns the layout that the Gui is currently in.
+    ///
+    /// # Returns
+    /// The [`GuiLayout`] that the user last chose.
+    #[inline]
+    pub fn layout(&self) -> GuiLayout { self.state.access(|state| state.layout) }
+
+    /// Changes the layout of the Gui, persisting the choice.
+    ///
+    /// # Arguments
+    /// - `layout`: The new [`GuiLayout`] to switch to.
+    fn set_layout(&self, layout: GuiLayout) {
+        debug!("Switching to layout '{}'", layout.variant());
+        let res: Result<(), crate::state::Error> = match self.state.access_mut(|state| -> Result<(), Infallible> {
+            state.layout = layout;
+            state.mark_dirty();
+            Ok(())
+        }) {
+            Ok(res) => res,
+            Err(err) => match err {},
+        };
+        if let Err(err) = res {
+            warn!("{}", err.trace());
+        }
+    }
+
+
+
+    /// Silences or un-silences all notifications, persisting the choice.
+    ///
+    /// # Arguments
+    /// - `muted`: The new [`MuteState`].
+    fn set_muted(&self, muted: MuteState) {
+        info!("Notifications are now {} from the window", if muted.is_muted() { "silenced" } else { "un-silenced" });
+        if let Ok(Err(err)) = self.state.access_mut_from("gui", |state| -> Result<(), Infallible> {
+            state.muted = muted;
+            state.mark_dirty();
+            Ok(())
+        }) {
+            warn!("{}", err.trace());
+        }
+    }
+
+
+
+    /// Changes how the event list is sorted, persisting the choice.
+    ///
+    /// # Arguments
+    /// - `sort`: The new [`SortKey`] to sort by.
+    fn set_sort(&self, sort: SortKey) {
+        debug!("Sorting event list by '{}'", sort.variant());
+        if let Ok(Err(err)) = self.state.access_mut(|state| -> Result<(), Infallible> {
+            state.sort = sort;
+            state.mark_dirty();
+            Ok(())
+        }) {
+            warn!("{}", err.trace());
+        }
+    }
+
+
+
+    /// Pins or unpins an event, such that it's kept around regardless of how many newer events there are.
+    ///
+    /// # Arguments
+    /// - `key`: The [`Event::key()`] of the event.
+    /// - `pinned`: Whether the event should be pinned.
+    fn set_pinned(&self, key: &str, pinned: bool) {
+        debug!("{} event '{key}'", if pinned { "Pinning" } else { "Unpinning" });
+        if let Ok(Err(err)) = self.state.access_mut(|state| -> Result<(), Infallible> {
+            if let Some(event) = state.events.iter_mut().find(|event| event.key() == key) {
+                event.pinned = pinned;
+                state.mark_history_dirty();
+            }
+            Ok(())
+        }) {
+            warn!("{}", err.trace());
+        }
+    }
+
+
+
+    /// Loads a config file that the user wants to import, asking them how to apply it on the next paint.
+    ///
+    /// If the file is invalid, an error is shown instead.
+    ///
+    /// # Arguments
+    /// - `path`: The path to the config file to import.
+    pub fn import_config(&mut self, path: &Path) {
+        match ConfigFile::import(path) {
+            Ok(config) => self.pending_import = Some((path.into(), config)),
+            Err(err) => {
+                warn!("{}", err.trace());
+                self.toast = Some(format!("{}", err.trace()));
+            },
+        }
+    }
+
+    /// Draws any pop-up dialogs that are currently open.
+    ///
+    /// # Arguments
+    /// - `ctx`: The egui [`Context`] that we draw to.
+    /// - `lang`: The [`Lang`] to draw the dialogs in.
+    fn dialogs(&mut self, ctx: &Context, lang: Lang) {
+        // Ask what to do with an imported config file
+        if let Some((path, config)) = &self.pending_import {
+            let mut choice: Option<ImportChoice> = None;
+            egui::Window::new(lang.tr(Text::ImportTitle)).id(egui::Id::new("import")).collapsible(false).resizable(false).show(ctx, |ui| {
+                ui.label(lang.tr(Text::ImportQuestion).replace("{path}", &path.display().to_string()).replace("{n}", &config.servers.len().to_string()));
+                ui.label(lang.tr(Text::ImportHint));
+                ui.horizontal(|ui| {
+                    for (button, text) in
+                        [(ImportChoice::Merge, Text::ImportMerge), (ImportChoice::Replace, Text::ImportReplace), (ImportChoice::Cancel, Text::Cancel)]
+                    {
+                        if ui.button(lang.tr(text)).clicked() {
+                            choice = Some(button);
+                        }
+                    }
+                });
+            });
+
+            // Apply the choice, if any
+            if let Some(choice) = choice {
+                if let Some((path, config)) = self.pending_import.take().filter(|_| choice != ImportChoice::Cancel) {
+                    if let Ok(Err(err)) = self.state.access_mut_from("import", |state| -> Result<(), Infallible> {
+                        if choice == ImportChoice::Replace {
+                            state.replace_config(config);
+                        } else {
+                            state.merge_config(config);
+                        }
+                        Ok(())
+                    }) {
+                        warn!("{}", err.trace());
+                        self.toast = Some(format!("{}", err.trace()));
+                    } else {
+                        info!("Imported config file '{}'", path.display());
+                    }
+                }
+            }
+        }
+
+        // Show errors
+        if let Some(toast) = &self.toast {
+            let mut dismissed: bool = false;
+            egui::Window::new(lang.tr(Text::ErrorTitle)).id(egui::Id::new("error")).collapsible(false).resizable(false).show(ctx, |ui| {
+                ui.label(toast);
+                dismissed = ui.button(lang.tr(Text::Ok)).clicked();
+            });
+            if dismissed {
+                self.toast = None;
+            }
+        }
+    }
+
+
+
+    /// Updates the selection after the user clicked an event in the list (or moved to it with the arrow keys).
+    ///
+    /// A plain click selects only that event, a ctrl-click (cmd on macOS) toggles it and a shift-click selects everything between
+    /// it and the last (non-shift) clicked event. Shift-ctrl-click adds such a range to the selection instead of replacing it.
+    ///
+    /// # Arguments
+    /// - `keys`: The keys of the events in the order they're listed.
+    /// - `index`: The index of the clicked event in `keys`.
+    /// - `modifiers`: The [`Modifiers`] that were held during the click.
+    fn select(&mut self, keys: &[String], index: usize, modifiers: Modifiers) {
+        let key: &String = &keys[index];
+        self.cursor = Some(key.clone());
+        if modifiers.shift {
+            let anchor: usize = self.anchor.as_ref().and_then(|anchor| keys.iter().position(|key| key == anchor)).unwrap_or(index);
+            if !modifiers.command {
+                self.selected.clear();
+            }
+            self.selected.extend(keys[anchor.min(index)..=anchor.max(index)].iter().cloned());
+        } else if modifiers.command {
+            if !self.selected.remove(key) {
+                self.selected.insert(key.clone());
+            }
+            self.anchor = Some(key.clone());
+        } else {
+            self.selected.clear();
+            self.selected.insert(key.clone());
+            self.anchor = Some(key.clone());
+        }
+    }
+
+    /// Applies some action to all selected events.
+    ///
+    /// # Arguments
+    /// - `ctx`: The egui [`Context`] (used to reach the clipboard).
+    /// - `action`: The [`BulkAction`] to apply.
+    /// - `events`: The events as they're listed.
+    /// - `lang`: The [`Lang`] to report failures in.
+    fn bulk_action(&mut self, ctx: &Context, action: BulkAction, events: &[Event], lang: Lang) {
+        debug!("Applying bulk action {action:?} to {} event(s)", self.selected.len());
+        match action {
+            BulkAction::MarkRead | BulkAction::Delete => {
+                let selected: &HashSet<String> = &self.selected;
+                if let Ok(Err(err)) = self.state.access_mut(|state| -> Result<(), Infallible> {
+                    if action == BulkAction::Delete {
+                        state.events.retain(|event| !selected.contains(&event.key()));
+                    } else {
+                        for event in state.events.iter_mut().filter(|event| selected.contains(&event.key())) {
+                            event.read = true;
+                        }
+                    }
+                    state.mark_history_dirty();
+                    Ok(())
+                }) {
+                    warn!("{}", err.trace());
+                    self.toast = Some(format!("{}", err.trace()));
+                }
+                if action == BulkAction::Delete {
+                    self.selected.clear();
+                    self.anchor = None;
+                }
+            },
+
+            BulkAction::Export => {
+                let selected: Vec<&Event> = events.iter().filter(|event| self.selected.contains(&event.key())).collect();
+                match serde_json::to_string_pretty(&selected) {
+                    Ok(json) => {
+                        ctx.output_mut(|output| output.copied_text = json);
+                        info!("Copied {} event(s) to the clipboard", selected.len());
+                    },
+                    Err(err) => self.toast = Some(lang.tr(Text::SerializeFailed).replace("{err}", &err.to_string())),
+                }
+            },
+        }
+    }
+
+    /// Snoozes (or unsnoozes) an event title.
+    ///
+    /// # Arguments
+    /// - `title`: The title of the events to (un)snooze.
+    /// - `snooze`: Until when to snooze them, where [`MuteState::Unmuted`] unsnoozes them.
+    fn snooze(&mut self, title: &str, snooze: MuteState) {
+        debug!("Snoozing event title '{title}': {snooze:?}");
+        if let Ok(Err(err)) = self.state.access_mut(|state| -> Result<(), Infallible> {
+            if snooze.is_unmuted() {
+                state.snoozed.remove(title);
+            } else {
+                state.snoozed.insert(title.into(), snooze);
+            }
+            state.mark_dirty();
+            Ok(())
+        }) {
+            warn!("{}", err.trace());
+            self.toast = Some(format!("{}", err.trace()));
+        }
+    }
+
+    /// Draws the list of events, together with the actions to apply to selected ones.
+    ///
+    /// # Arguments
+    /// - `ui`: The [`Ui`] to draw in.
+    /// - `lang`: The [`Lang`] to draw the actions in.
+    fn event_list(&mut self, ui: &mut Ui, lang: Lang) {
+        let (mut events, sort): (Vec<Event>, SortKey) = self.state.access(|state| (state.events.iter().cloned().collect(), state.sort));
+        if let Some(server) = self.selected_server() {
+            events.retain(|event| event.source == server);
+        }
+        let query: String = self.query.to_lowercase();
+        events.retain(|event| {
+            event.severity >= self.min_severity
+                && (query.is_empty() || [&event.title, &event.message, &event.source].into_iter().any(|text| text.to_lowercase().contains(&query)))
+        });
+        events.sort_by(|lhs, rhs| sort.compare(lhs, rhs));
+        let icons: Vec<Option<String>> = self.state.access(|state| events.iter().map(|event| state.icon(event).map(String::from)).collect());
+        let keys: Vec<String> = events.iter().map(Event::key).collect();
+
+        // Forget about events that are gone
+        let known: HashSet<&String> = keys.iter().collect();
+        self.selected.retain(|key| known.contains(key));
+        let cursor: Option<usize> = self.cursor.as_ref().and_then(|cursor| keys.iter().position(|key| key == cursor));
+        if cursor.is_none() {
+            self.cursor = None;
+        }
+
+        // Move through the list with the keyboard, stopping at either end
+        let mut scroll_to: Option<usize> = self.reveal.take().and_then(|reveal| keys.iter().position(|key| *key == reveal));
+        if !ui.ctx().wants_keyboard_input() && !keys.is_empty() {
+            let (up, down, enter, escape, modifiers): (bool, bool, bool, bool, Modifiers) = ui.input(|input| {
+                (
+                    input.key_pressed(Key::ArrowUp),
+                    input.key_pressed(Key::ArrowDown),
+                    input.key_pressed(Key::Enter),
+                    input.key_pressed(Key::Escape),
+                    input.modifiers,
+                )
+            });
+            if up || down {
+                let next: usize = match cursor {
+                    Some(i) if up => i.saturating_sub(1),
+                    Some(i) => (i + 1).min(keys.len() - 1),
+                    None => 0,
+                };
+                // Ctrl would toggle the event we land on, which isn't what anyone moving through the list wants
+                self.select(&keys, next, Modifiers { command: false, ctrl: false, mac_cmd: false, ..modifiers });
+                scroll_to = Some(next);
+            }
+            if enter {
+                self.detail = self.cursor.clone();
+            }
+            if escape {
+                self.detail = None;
+            }
+        }
+
+        // Draw the actions
+        let mut action: Option<BulkAction> = None;
+        ui.horizontal(|ui| {
+            ui.label(lang.tr(Text::Selected).replace("{n}", &self.selected.len().to_string()));
+            ui.add_enabled_ui(!self.selected.is_empty(), |ui| {
+                for (button, text) in [(BulkAction::MarkRead, Text::MarkRead), (BulkAction::Delete, Text::Delete), (BulkAction::Export, Text::CopyJson)] {
+                    if ui.button(lang.tr(text)).clicked() {
+                        action = Some(button);
+                    }
+                }
+            });
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let mut chosen: SortKey = sort;
+                egui::ComboBox::from_id_source("sort").selected_text(lang.tr(sort.label())).show_ui(ui, |ui| {
+                    for key in SortKey::ALL {
+                        ui.selectable_value(&mut chosen, key, lang.tr(key.label()));
+                    }
+                });
+                if chosen != sort {
+                    self.set_sort(chosen);
+                }
+            });
+        });
+        ui.separator();
+
+        // Draw the events themselves
+        let now: DateTime<Local> = self.state.now();
+        let mut clicked: Option<usize> = None;
+        let mut opened: Option<usize> = None;
+        let mut snoozed: Option<(usize, MuteState)> = None;
+        let mut pinned: Option<usize> = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if events.is_empty() {
+                ui.label(lang.tr(Text::NoEvents));
+            }
+            let mut row = |ui: &mut Ui, i: usize| {
+                let (event, key, icon): (&Event, &String, &Option<String>) = (&events[i], &keys[i], &icons[i]);
+                let text: String =
+                    format!("{}  [{}]  {}: {}", event.timestamp.format("%Y-%m-%d %H:%M:%S"), event.severity.variant(), event.source, event.title);
+                let text: RichText = if event.read { RichText::new(text) } else { RichText::new(text).strong() };
+                ui.horizontal(|ui| {
+                    match icon {
+                        Some(icon) => ui.label(icon),
+                        None => ui.colored_label(severity_color(ui, event.severity), "●"),
+                    };
+                    if ui.selectable_label(event.pinned, "📌").on_hover_text(lang.tr(if event.pinned { Text::Unpin } else { Text::PinHint })).clicked() {
+                        pinned = Some(i);
+                    }
+                    if let Some(flag) = event.timestamp_flag {
+                        ui.weak("⏱").on_hover_text(lang.tr(Text::timestamp_flag(flag)));
+                    }
+                    let response: egui::Response = ui.selectable_label(self.selected.contains(key), text);
+                    if response.clicked() {
+                        clicked = Some(i);
+                    }
+                    if response.double_clicked() {
+                        opened = Some(i);
+                    }
+                    response.context_menu(|ui| {
+                        if ui.button(lang.tr(if event.pinned { Text::Unpin } else { Text::Pin })).clicked() {
+                            pinned = Some(i);
+                            ui.close_menu();
+                        }
+                        ui.menu_button(lang.tr(Text::SnoozeAction), |ui| {
+                            if let Some(snooze) = snooze_choices(ui, now, lang) {
+                                snoozed = Some((i, snooze));
+                            }
+                        });
+                    });
+                    if scroll_to == Some(i) {
+                        response.scroll_to_me(None);
+                    }
+                    if let Some(url) = &event.url {
+                        ui.hyperlink_to("🔗", url.as_str()).on_hover_text(url.as_str());
+                    }
+                });
+            };
+
+            // When sorted by server, the events of every server are next to each other, so show them in groups
+            if sort == SortKey::Server {
+                let mut start: usize = 0;
+                while start < events.len() {
+                    let source: &str = &events[start].source;
+                    let end: usize = events[start..].iter().position(|event| event.source != source).map_or(events.len(), |len| start + len);
+                    let unread: usize = events[start..end].iter().filter(|event| !event.read).count();
+                    let header: String = lang.tr(Text::ServerUnread).replace("{server}", source).replace("{n}", &unread.to_string());
+                    egui::CollapsingHeader::new(header).id_source(("server", source)).default_open(true).show(ui, |ui| {
+                        for i in start..end {
+                            row(ui, i);
+                        }
+                    });
+                    start = end;
+                }
+            } else {
+                for i in 0..events.len() {
+                    row(ui, i);
+                }
+            }
+        });
+
+        // Process the interactions
+        if let Some(i) = clicked {
+            let modifiers: Modifiers = ui.input(|input| input.modifiers);
+            self.select(&keys, i, modifiers);
+        }
+        if let Some(i) = opened {
+            self.detail = Some(keys[i].clone());
+        }
+        if let Some((i, snooze)) = snoozed {
+            self.snooze(&events[i].title, snooze);
+        }
+        if let Some(i) = pinned {
+            self.set_pinned(&keys[i], !events[i].pinned);
+        }
+        if let Some(action) = action {
+            self.bulk_action(ui.ctx(), action, &events, lang);
+        }
+    }
+
+    /// Draws a condensed list of the most recent events, for in the [mini layout](GuiLayout::Mini).
+    ///
+    /// # Arguments
+    /// - `ui`: The [`Ui`] to draw in.
+    /// - `lang`: The [`Lang`] to draw the list in.
+    fn recent_events(&self, ui: &mut Ui, lang: Lang) {
+        let (events, icons): (Vec<Event>, Vec<Option<String>>) = self.state.access(|state| {
+            let events: Vec<Event> = state.events.iter().take(MINI_EVENTS).cloned().collect();
+            let icons: Vec<Option<String>> = events.iter().map(|event| state.icon(event).map(String::from)).collect();
+            (events, icons)
+        });
+        if events.is_empty() {
+            ui.weak(lang.tr(Text::NoEvents));
+            return;
+        }
+        let now: DateTime<Local> = self.state.now();
+        for (event, icon) in events.iter().zip(&icons) {
+            ui.horizontal(|ui| {
+                match icon {
+                    Some(icon) => ui.label(icon),
+                    None => ui.colored_label(severity_color(ui, event.severity), "●"),
+                };
+                let title: RichText = if event.read { RichText::new(&event.title) } else { RichText::new(&event.title).strong() };
+                ui.small(format!("[{}]", event.severity.variant()));
+                ui.label(title).on_hover_text(&event.source);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| ui.weak(ago(now - event.timestamp, lang)));
+            });
+        }
+    }
+
+    /// Draws the search box and the severity filter for the event list.
+    ///
+    /// # Arguments
+    /// - `ui`: The [`Ui`] to draw in.
+    /// - `lang`: The [`Lang`] to label the filters in.
+    fn filter_bar(&mut self, ui: &mut Ui, lang: Lang) {
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.query).hint_text(lang.tr(Text::SearchHint)));
+            if !self.query.is_empty() && ui.small_button("✖").on_hover_text(lang.tr(Text::SearchClear)).clicked() {
+                self.query.clear();
+            }
+            egui::ComboBox::from_id_source("min_severity").selected_text(lang.tr(Text::min_severity(self.min_severity))).show_ui(ui, |ui| {
+                for severity in Severity::ALL {
+                    ui.selectable_value(&mut self.min_severity, severity, lang.tr(Text::min_severity(severity)));
+                }
+            });
+        });
+    }
+
+    /// Draws the tabs that choose which server's events the list shows.
+    ///
+    /// # Arguments
+    /// - `ui`: The [`Ui`] to draw in.
+    /// - `lang`: The [`Lang`] to label the tab with all events in.
+    fn server_tabs(&mut self, ui: &mut Ui, lang: Lang) {
+        let (servers, total): (Vec<(String, usize)>, usize) = self.state.access(|state| {
+            (
+                state.servers.iter().map(|server| (server.name.clone(), state.unread(&server.name))).collect(),
+                state.events.iter().filter(|event| state.is_unread(event)).count(),
+            )
+        });
+        let label = |name: &str, unread: usize| -> String {
+            if unread > 0 {
+                format!("{name} ({unread})")
+            } else {
+                name.into()
+            }
+        };
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.selected_server, 0, label(lang.tr(Text::AllServers), total));
+            for (i, (name, unread)) in servers.iter().enumerate() {
+                ui.selectable_value(&mut self.selected_server, i + 1, label(name, *unread));
+            }
+        });
+    }
+
+    /// Returns the name of the server whose tab is shown.
+    ///
+    /// Falls back to the tab with all events if the server is gone (e.g., because the config was reloaded).
+    ///
+    /// # Returns
+    /// The name of the server, or [`None`] if the tab with all events is shown.
+    fn selected_server(&mut self) -> Option<String> {
+        let i: usize = self.selected_server.checked_sub(1)?;
+        let name: Option<String> = self.state.access(|state| state.servers.get(i).map(|server| server.name.clone()));
+        if name.is_none() {
+            self.selected_server = 0;
+        }
+        name
+    }
+
+
+
+    /// Draws a line with when each server was last polled successfully, and whether it's reachable.
+    ///
+    /// # Arguments
+    /// - `ui`: The [`Ui`] to draw in.
+    /// - `lang`: The [`Lang`] to describe the servers in.
+    fn status_bar(&self, ui: &mut Ui, lang: Lang) {
+        let now: DateTime<Local> = self.state.now();
+        let servers: Vec<(String, bool, bool, Option<DateTime<Local>>)> = self.state.access(|state| {
+            state
+                .servers
+                .iter()
+                .map(|server| {
+                    (
+                        server.name.clone(),
+                        state.offline.contains(&server.name),
+                        state.auth_failed.contains(&server.name),
+                        state.last_poll.get(&server.name).copied(),
+                    )
+                })
+                .collect()
+        });
+        ui.horizontal_wrapped(|ui| {
+            for (i, (name, offline, auth_failed, last_poll)) in servers.into_iter().enumerate() {
+                if i > 0 {
+                    ui.separator();
+                }
+                let last_poll: String = last_poll.map(|time| ago(now - time, lang)).unwrap_or_else(|| lang.tr(Text::Never).into());
+                let status = |text: Text| -> String { lang.tr(text).replace("{server}", &name).replace("{time}", &last_poll) };
+                if auth_failed {
+                    ui.colored_label(ui.visuals().error_fg_color, status(Text::AuthFailed)).on_hover_text(lang.tr(Text::AuthFailedHint));
+                } else if offline {
+                    ui.colored_label(ui.visuals().warn_fg_color, status(Text::Offline));
+                } else {
+                    ui.label(status(Text::PolledAgo));
+                }
+            }
+        });
+
+        // Keep the times up-to-date
+        ui.ctx().request_repaint_after(Duration::from_secs(1));
+    }
+
+    /// Draws a small bar strip of how many events came in per minute over the last hour.
+    ///
+    /// Every bar is colored by the highest severity that came in during its minute.
+    ///
+    /// # Arguments
+    /// - `ui`: The [`Ui`] to draw in.
+    /// - `lang`: The [`Lang`] to explain the sparkline in.
+    fn sparkline(&self, ui: &mut Ui, lang: Lang) {
+        let now: DateTime<Local> = self.state.now();
+        let buckets: Vec<ActivityBucket> = self.state.access(|state| state.activity.buckets(now));
+        let total: usize = buckets.iter().map(|bucket| bucket.count).sum();
+        let max: usize = buckets.iter().map(|bucket| bucket.count).max().unwrap_or(0);
+
+        let (rect, response): (egui::Rect, egui::Response) =
+            ui.allocate_exact_size(egui::vec2(SPARKLINE_BAR_WIDTH * buckets.len() as f32, SPARKLINE_HEIGHT), egui::Sense::hover());
+        let painter: egui::Painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+        for (i, bucket) in buckets.iter().enumerate() {
+            if let Some(severity) = bucket.severity {
+                let left: f32 = rect.left() + i as f32 * SPARKLINE_BAR_WIDTH;
+                let top: f32 = rect.bottom() - rect.height() * bucket.count as f32 / max.max(1) as f32;
+                painter.rect_filled(
+                    egui::Rect::from_min_max(egui::pos2(left, top), egui::pos2(left + SPARKLINE_BAR_WIDTH, rect.bottom())),
+                    0.0,
+                    severity_color(ui, severity),
+                );
+            }
+        }
+        response.on_hover_text(lang.tr(Text::Activity).replace("{n}", &total.to_string()).replace("{max}", &max.to_string()));
+    }
+
+    /// Draws everything we know about a single event.
+    ///
+    /// # Arguments
+    /// - `ui`: The [`Ui`] to draw in.
+    /// - `event`: The [`Event`] to draw.
+    /// - `lang`: The [`Lang`] to label the event's fields in.
+    fn event_detail(&mut self, ui: &mut Ui, event: &Event, lang: Lang) {
+        ui.horizontal(|ui| {
+            ui.heading(&event.title);
+            if ui.small_button("✖").on_hover_text(lang.tr(Text::CloseDetail)).clicked() {
+                self.detail = None;
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.selectable_label(event.pinned, lang.tr(Text::Pinned)).on_hover_text(lang.tr(Text::PinnedHint)).clicked() {
+                self.set_pinned(&event.key(), !event.pinned);
+            }
+            let now: DateTime<Local> = self.state.now();
+            if self.state.access(|state| state.is_snoozed(&event.title, now)) {
+                if ui.button(lang.tr(Text::Unsnooze)).on_hover_text(lang.tr(Text::UnsnoozeHint)).clicked() {
+                    self.snooze(&event.title, MuteState::Unmuted);
+                }
+            } else {
+                let mut choice: Option<MuteState> = None;
+                ui.menu_button(format!("💤 {}", lang.tr(Text::SnoozeAction)), |ui| choice = snooze_choices(ui, now, lang))
+                    .response
+                    .on_hover_text(lang.tr(Text::SnoozeThisHint));
+                if let Some(snooze) = choice {
+                    self.snooze(&event.title, snooze);
+                }
+            }
+        });
+        egui::Grid::new("detail").num_columns(2).show(ui, |ui| {
+            ui.label(lang.tr(Text::DetailTime));
+            ui.horizontal(|ui| {
+                ui.label(event.timestamp.format("%Y-%m-%d %H:%M:%S").to_string());
+                if let Some(flag) = event.timestamp_flag {
+                    ui.weak(format!("⏱ {}", lang.tr(Text::timestamp_flag(flag))));
+                }
+            });
+            ui.end_row();
+            ui.label(lang.tr(Text::DetailSeverity));
+            ui.label(event.severity.variant().to_string());
+            ui.end_row();
+            ui.label(lang.tr(Text::DetailServer));
+            ui.label(&event.source);
+            ui.end_row();
+            ui.label(lang.tr(Text::DetailId));
+            ui.label(&event.id);
+            ui.end_row();
+            if let Some(url) = &event.url {
+                ui.label(lang.tr(Text::DetailLink));
+                ui.hyperlink(url.as_str());
+                ui.end_row();
+            }
+        });
+        if !event.metadata.is_empty() {
+            egui::CollapsingHeader::new(lang.tr(Text::Metadata).replace("{n}", &event.metadata.len().to_string())).id_source("metadata").show(ui, |ui| {
+                egui::Grid::new("metadata").num_columns(2).striped(true).show(ui, |ui| {
+                    for (key, value) in &event.metadata {
+                        ui.label(key);
+                        ui.label(value);
+                        ui.end_row();
+                    }
+                });
+            });
+        }
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| ui.label(&event.message));
+    }
+
+    /// Polls a server once on a worker thread, to see if we can reach it.
+    ///
+    /// The result is shown in the servers panel when it's done.
+    ///
+    /// # Arguments
+    /// - `ctx`: The egui [`Context`] to repaint when the test is done.
+    /// - `server`: The [`ServerConfig`] to test.
+    fn test_connection(&mut self, ctx: &Context, server: ServerConfig) {
+        debug!("Testing connection to server '{}'...", server.name);
+        let result: Arc<Mutex<Option<TestResult>>> = Arc::new(Mutex::new(None));
+        self.tests.insert(server.name.clone(), result.clone());
+
+        let user_agent: Option<String> = self.state.access(|state| state.user_agent.clone());
+        let clock: Arc<dyn Clock> = self.state.shared_clock();
+        let ctx: Context = ctx.clone();
+        thread::spawn(move || {
+            let res: TestResult = match build_client(&server.effective_tls(), user_agent.as_deref()) {
+                Ok(client) => poll_once(&client, &server, &mut HttpCache::default(), &*clock).map(|events| events.len()),
+                Err(err) => Err(err),
+            }
+            .map_err(|err| format!("{}", err.trace()));
+            *result.lock() = Some(res);
+            ctx.request_repaint();
+        });
+    }
+
+    /// Draws the list of servers, together with a button to test each of them.
+    ///
+    /// # Arguments
+    /// - `ui`: The [`Ui`] to draw in.
+    /// - `lang`: The [`Lang`] to draw the list in.
+    fn server_list(&mut self, ui: &mut Ui, lang: Lang) {
+        ui.heading(lang.tr(Text::Servers));
+        let servers: Vec<ServerConfig> = self.state.access(|state| state.servers.clone());
+        if servers.is_empty() {
+            ui.label(lang.tr(Text::NoServers));
+        }
+        for server in servers {
+            ui.separator();
+            ui.strong(&server.name);
+            ui.label(server.url.as_str());
+
+            // Show the test button or its result
+            let result: Option<Option<TestResult>> = self.tests.get(&server.name).map(|result| result.lock().clone());
+            ui.horizontal(|ui| {
+                let running: bool = matches!(result, Some(None));
+                if ui.add_enabled(!running, egui::Button::new(lang.tr(Text::TestConnection))).clicked() {
+                    self.test_connection(ui.ctx(), server.clone());
+                }
+                match &result {
+                    Some(None) => {
+                        ui.spinner();
+                    },
+                    Some(Some(Ok(count))) => {
+                        ui.colored_label(egui::Color32::GREEN, lang.tr(Text::Reachable).replace("{n}", &count.to_string()));
+                    },
+                    Some(Some(Err(_))) => {
+                        ui.colored_label(ui.visuals().error_fg_color, lang.tr(Text::TestFailed));
+                    },
+                    None => {},
+                }
+            });
+            if let Some(Some(Err(err))) = result {
+                ui.label(RichText::new(err).small());
+            }
+        }
+    }
+
+
+
+    /// Draws the list of snoozed event titles, together with buttons to unsnooze them.
+    ///
+    /// # Arguments
+    /// - `ui`: The [`Ui`] to draw in.
+    /// - `lang`: The [`Lang`] to draw the list in.
+    fn snoozed_list(&mut self, ui: &mut Ui, lang: Lang) {
+        ui.heading(lang.tr(Text::Snoozed));
+        let now: DateTime<Local> = self.state.now();
+        let mut snoozed: Vec<(String, MuteState)> = self.state.access(|state| {
+            state.snoozed.iter().filter(|(title, _)| state.is_snoozed(title, now)).map(|(title, snooze)| (title.clone(), snooze.clone())).collect()
+        });
+        snoozed.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+        if snoozed.is_empty() {
+            ui.label(lang.tr(Text::NoSnoozed));
+            return;
+        }
+        if ui.button(lang.tr(Text::UnsnoozeAll)).clicked() {
+            for (title, _) in &snoozed {
+                self.snooze(title, MuteState::Unmuted);
+            }
+        }
+        for (title, snooze) in snoozed {
+            ui.separator();
+            ui.strong(&title);
+            ui.horizontal(|ui| {
+                ui.label(match snooze {
+                    MuteState::After(deadline) => {
+                        lang.tr(Text::SnoozedUntil).replace("{time}", &deadline.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                    },
+                    MuteState::NextBoot => lang.tr(Text::SnoozedUntilRestart).into(),
+                    _ => lang.tr(Text::SnoozedUntilUnsnoozed).into(),
+                });
+                if ui.small_button(lang.tr(Text::Unsnooze)).clicked() {
+                    self.snooze(&title, MuteState::Unmuted);
+                }
+            });
+        }
+    }
+
+
+
     /// Builds the UI from the group up.
     ///
     /// # Arguments
     /// - `ctx`: The egui [`Context`] that we draw to.
     pub fn ui(&mut self, ctx: &Context) {
-        // Just draw some wacky colour for now
-        egui::CentralPanel::default().show(ctx, |ui| ui.add(egui::Label::new("Hello there!")));
+        let lang: Lang = self.state.access(MutableAppState::lang);
+
+        // Make sure the user knows if their settings aren't saved
+        if !self.state.is_persistent() {
+            egui::TopBottomPanel::top("not_persistent").show(ctx, |ui| {
+                ui.colored_label(ui.visuals().warn_fg_color, lang.tr(Text::NotPersistent).replace("{dir}", &self.state.config_dir().display().to_string()));
+            });
+        } else if let Some(err) = self.state.access(|state| state.sync_error.clone()) {
+            egui::TopBottomPanel::top("sync_error").show(ctx, |ui| {
+                ui.colored_label(ui.visuals().warn_fg_color, lang.tr(Text::SyncError).replace("{err}", &err));
+            });
+        }
+
+        // Make it obvious that nothing will come through while silenced, and how to undo that
+        let muted: MuteState = self.state.access(|state| state.muted.clone());
+        if muted.is_muted() {
+            egui::TopBottomPanel::top("silenced").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let text: String = match &muted {
+                        MuteState::After(deadline) => {
+                            lang.tr(Text::SilencedUntil).replace("{time}", &deadline.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                        },
+                        _ => lang.tr(Text::Silenced).into(),
+                    };
+                    ui.colored_label(ui.visuals().warn_fg_color, RichText::new(text).strong());
+                    if ui.button(lang.tr(Text::Unsilence)).clicked() {
+                        self.set_muted(MuteState::Unmuted);
+                    }
+                });
+            });
+        }
+
+        match if self.popover { GuiLayout::Mini } else { self.layout() } {
+            GuiLayout::Full => {
+                egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        if ui.button(lang.tr(Text::MiniMode)).clicked() {
+                            self.set_layout(GuiLayout::Mini);
+                        }
+                        ui.toggle_value(&mut self.show_servers, lang.tr(Text::Servers));
+                        ui.toggle_value(&mut self.show_snoozed, lang.tr(Text::Snoozed));
+                        if muted.is_unmuted()
+                            && ui
+                                .add(egui::Button::new(RichText::new(lang.tr(Text::SilenceAll)).strong()).fill(ui.visuals().error_fg_color.gamma_multiply(0.3)))
+                                .on_hover_text(lang.tr(Text::SilenceAllHint))
+                                .clicked()
+                        {
+                            self.set_muted(MuteState::Manual);
+                        }
+
+                        // Remind the user of servers that we don't verify
+                        let insecure: Vec<String> = self.state.access(|state| {
+                            state.servers.iter().filter(|server| server.effective_tls().danger_accept_invalid).map(|server| server.name.clone()).collect()
+                        });
+                        if !insecure.is_empty() {
+                            ui.colored_label(ui.visuals().warn_fg_color, lang.tr(Text::Insecure).replace("{servers}", &insecure.join(", ")))
+                                .on_hover_text(lang.tr(Text::InsecureHint));
+                        }
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| self.sparkline(ui, lang));
+                    });
+                });
+
+                if self.show_servers {
+                    egui::SidePanel::right("servers").show(ctx, |ui| egui::ScrollArea::vertical().show(ui, |ui| self.server_list(ui, lang)));
+                }
+                if self.show_snoozed {
+                    egui::SidePanel::right("snoozed").show(ctx, |ui| egui::ScrollArea::vertical().show(ui, |ui| self.snoozed_list(ui, lang)));
+                }
+                egui::TopBottomPanel::top("filter").show(ctx, |ui| self.filter_bar(ui, lang));
+                if self.state.access(|state| state.servers.len() > 1) {
+                    egui::TopBottomPanel::top("server_tabs").show(ctx, |ui| self.server_tabs(ui, lang));
+                }
+                egui::TopBottomPanel::bottom("status").show(ctx, |ui| self.status_bar(ui, lang));
+                let detail: Option<Event> =
+                    self.detail.as_ref().and_then(|key| self.state.access(|state| state.events.iter().find(|event| &event.key() == key).cloned()));
+                match detail {
+                    Some(event) => {
+                        egui::TopBottomPanel::bottom("detail").resizable(true).show(ctx, |ui| self.event_detail(ui, &event, lang));
+                    },
+                    None => self.detail = None,
+                }
+                egui::CentralPanel::default().show(ctx, |ui| self.event_list(ui, lang));
+            },
+
+            GuiLayout::Mini => {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        let muted: bool = self.state.access(|state| state.muted.is_muted());
+                        ui.label(lang.tr(if muted { Text::Muted } else { Text::Unmuted }));
+                        if !muted && self.state.effective_muted() {
+                            ui.label(lang.tr(Text::OsDnd)).on_hover_text(lang.tr(Text::OsDndHint));
+                        }
+                        if !self.popover && ui.small_button("⛶").on_hover_text(lang.tr(Text::FullMode)).clicked() {
+                            self.set_layout(GuiLayout::Full);
+                        }
+                    });
+                    ui.separator();
+                    self.recent_events(ui, lang);
+                });
+            },
+        }
+        self.dialogs(ctx, lang);
     }
 }
@@ -0,0 +1,176 @@
+//  HEADLESS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 09:45:56
+//  Last edited:
+//    16 Oct 2026, 10:00:44
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Runs the app without any windows or tray icon, for machines that
+//!   don't have a display (e.g., a server that forwards its events to a
+//!   webhook).
+//!   
+//!   The [`AppEvent`]s that would otherwise go to the event loop are
+//!   handled on the main thread instead. Notifications go wherever the
+//!   configured notifiers send them.
+//
+
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender};
+use std::time::Instant;
+
+use enum_debug::EnumDebug as _;
+use error_trace::ErrorTrace as _;
+use log::{debug, info, warn};
+
+use super::{
+    apply_tray_item, next_wakeup, poller_config, prune_events, resolve_mute, respawn_poller, snooze, spawn_poller, spawn_services, AppEvent, AppEventLoopProxy,
+    Error, PRUNE_INTERVAL,
+};
+use crate::app::tray::TrayIconMenuItem;
+use crate::instance::InstanceLock;
+use crate::poller::{Poller, Replay};
+use crate::state::AppState;
+
+
+/***** LIBRARY *****/
+/// Runs the [`Poller`] and everything around it, but without a GUI.
+pub struct Headless {
+    /// The state shared between various components.
+    state:     AppState,
+    /// The Poller that fetches events from the servers in the background.
+    poller:    Poller,
+    /// Whether the [`Poller`] replays events from a file, in which case it's never respawned.
+    replaying: bool,
+    /// A proxy to our own loop, to hand to respawned [`Poller`]s.
+    proxy:     AppEventLoopProxy,
+    /// Where the [`AppEvent`]s sent through the `proxy` arrive.
+    events:    Receiver<AppEvent>,
+    /// The lock that proves we're the only instance, which is released when the app exits.
+    _instance: InstanceLock,
+}
+impl Headless {
+    /// Constructor for the Headless app that does what is necessary.
+    ///
+    /// # Arguments
+    /// - `config_dir`: The directory to load persistent app state from.
+    /// - `instance`: The [`InstanceLock`] that proves we're the only instance for this `config_dir`.
+    /// - `replay`: If given, replays the events in this [`Replay`] instead of polling the servers.
+    ///
+    /// # Returns
+    /// A new Headless app, ready to rumble.
+    ///
+    /// # Errors
+    /// This function errors if some part of the initialization failed.
+    pub fn new(config_dir: PathBuf, mut instance: InstanceLock, replay: Option<Replay>) -> Result<Self, Error> {
+        info!("Initializing headless app...");
+
+        // Build an app state
+        let state: AppState = match AppState::new(config_dir) {
+            Ok(state) => state,
+            Err(err) => return Err(Error::AppStateCreate { err }),
+        };
+
+        // Events arrive over a plain channel, since there's no event loop
+        let (sender, events): (Sender<AppEvent>, Receiver<AppEvent>) = std::sync::mpsc::channel();
+        let proxy: AppEventLoopProxy = AppEventLoopProxy::Headless(sender);
+
+        // Start polling the servers
+        let poller: Poller = spawn_poller(&state, &proxy, replay.as_ref())?;
+
+        // Stop cleanly on Ctrl+C, such that the instance lock is released
+        let signal_proxy: AppEventLoopProxy = proxy.clone();
+        if let Err(err) = ctrlc::set_handler(move || {
+            if signal_proxy.send_event(AppEvent::Action(TrayIconMenuItem::Exit)).is_err() {
+                // The loop is gone already, so there's nothing to stop
+                std::process::exit(130);
+            }
+        }) {
+            return Err(Error::SignalHandlerSet { err });
+        }
+
+        // Start the things that talk to us from the outside
+        spawn_services(&state, &mut instance, &proxy);
+
+        // Done; build self
+        info!("Headless app initialization complete");
+        Ok(Self { state, poller, replaying: replay.is_some(), proxy, events, _instance: instance })
+    }
+
+    /// Runs the app on the current thread, handling events until asked to stop (e.g., by Ctrl+C).
+    ///
+    /// # Errors
+    /// This function does not error at the moment, but returns a [`Result`] for parity with [`App::run()`](super::App::run()).
+    pub fn run(mut self) -> Result<(), Error> {
+        info!("Entering headless loop...");
+        let mut next_prune: Instant = Instant::now() + PRUNE_INTERVAL;
+        loop {
+            // Sleep until something happens or until we have scheduled work
+            // (we hold a proxy ourselves, so the channel never disconnects)
+            let event: Option<AppEvent> = match next_wakeup(&self.state, next_prune) {
+                Some(wakeup) => self.events.recv_timeout(wakeup.saturating_duration_since(Instant::now())).ok(),
+                None => self.events.recv().ok(),
+            };
+            let Some(event) = event else {
+                if Instant::now() >= next_prune {
+                    prune_events(&self.state);
+                    next_prune = Instant::now() + PRUNE_INTERVAL;
+                }
+                resolve_mute(&self.state);
+                continue;
+            };
+
+            // Handle what makes sense without a GUI
+            match event {
+                AppEvent::Action(TrayIconMenuItem::Exit) => {
+                    info!("Exiting app...");
+                    return Ok(());
+                },
+                AppEvent::Action(TrayIconMenuItem::Open) => debug!("Ignoring request to open the window, as there is none in headless mode"),
+                AppEvent::Action(item) => {
+                    debug!("Received '{}' action", item.variant());
+                    apply_tray_item(&self.state, None, Some(&self.poller), item);
+                },
+                AppEvent::Menu(_) | AppEvent::Tray(_) => {},
+
+                AppEvent::NewEvents { server, count, silent } => {
+                    if silent {
+                        info!("Seeded {count} existing event(s) from server '{server}'");
+                    } else {
+                        info!("Received {count} new event(s) from server '{server}'");
+                    }
+                },
+                AppEvent::ConnectionChanged { server, status } => info!("Server '{server}' is now {}", status.describe()),
+
+                AppEvent::Snooze { title } => snooze(&self.state, title),
+                AppEvent::Reveal { key } => debug!("Ignoring request to show event '{key}', as there is no window in headless mode"),
+
+                AppEvent::ReloadConfig => {
+                    let before: serde_json::Value = poller_config(&self.state);
+                    if let Err(err) = self.state.reload_config() {
+                        warn!("{}", err.trace());
+                        continue;
+                    }
+                    if !self.replaying && before != poller_config(&self.state) {
+                        respawn_poller(&self.state, &self.proxy, &mut self.poller);
+                    }
+                },
+
+                AppEvent::Woke { slept } => {
+                    info!("Refreshing everything after sleeping for {}s", slept.as_secs());
+                    self.poller.refresh();
+                    resolve_mute(&self.state);
+                    prune_events(&self.state);
+                },
+
+                AppEvent::HealthCheck { reply } => {
+                    // The endpoint may have given up waiting already, which is fine
+                    let _ = reply.send(());
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,424 @@
+//  ICONS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 08:55:56
+//  Last edited:
+//    16 Oct 2026, 10:57:42
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Collects the icons that are embedded in the binary, and decodes
+//!   them once such that the tray icon can cheaply swap between them.
+//!   Rendered icons are kept around as well, such that swapping back to
+//!   one doesn't render it again.
+//!   
+//!   Every icon can be replaced by an image of the user's own in the
+//!   config directory (e.g., to tell machines apart).
+//
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::path::{Path, PathBuf};
+use std::{error, fs};
+
+use enum_debug::EnumDebug;
+use error_trace::ErrorTrace as _;
+use image::{Rgba, RgbaImage};
+use log::{debug, info, warn};
+use tray_icon::Icon;
+
+use crate::state::{MutableAppState, ServerStatus};
+
+
+/***** CONSTANTS *****/
+/// The icon shown when nothing needs the user's attention.
+const NORMAL: &[u8] = include_bytes!("../../assets/icon/example-256x256.png");
+/// The icon shown when there are unread events.
+const EVENTS: &[u8] = include_bytes!("../../assets/icon/events-256x256.png");
+/// The icon shown when a server answers with something we can't use.
+const ERROR: &[u8] = include_bytes!("../../assets/icon/error-256x256.png");
+/// The colour that the normal icon is tinted with to make the icon shown when a server cannot be reached.
+const WARNING_TINT: Rgba<u8> = Rgba([220, 40, 40, 255]);
+
+/// The extensions of custom icons that we look for, in order of preference.
+const CUSTOM_EXTENSIONS: [&str; 2] = ["png", "ico"];
+
+/// The largest count that a badge shows; anything above is shown as this number followed by a `+`.
+const BADGE_MAX: usize = 99;
+/// The colour of the badge itself.
+const BADGE_COLOR: Rgba<u8> = Rgba([220, 40, 40, 255]);
+/// The colour of the count on the badge.
+const BADGE_TEXT_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+/// The characters that can appear on a badge, as bitmaps of 3x5 pixels (one row per entry, leftmost pixel in the highest bit).
+const GLYPHS: [(char, [u8; 5]); 11] = [
+    ('0', [0b111, 0b101, 0b101, 0b101, 0b111]),
+    ('1', [0b010, 0b110, 0b010, 0b010, 0b111]),
+    ('2', [0b111, 0b001, 0b111, 0b100, 0b111]),
+    ('3', [0b111, 0b001, 0b111, 0b001, 0b111]),
+    ('4', [0b101, 0b101, 0b111, 0b001, 0b001]),
+    ('5', [0b111, 0b100, 0b111, 0b001, 0b111]),
+    ('6', [0b111, 0b100, 0b111, 0b101, 0b111]),
+    ('7', [0b111, 0b001, 0b001, 0b001, 0b001]),
+    ('8', [0b111, 0b101, 0b111, 0b101, 0b111]),
+    ('9', [0b111, 0b101, 0b111, 0b001, 0b111]),
+    ('+', [0b000, 0b010, 0b111, 0b010, 0b000]),
+];
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from loading or rendering the [`Icons`].
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to decode an embedded icon.
+    Decode { status: IconStatus, err: image::ImageError },
+    /// Failed to convert a rendered icon to something the tray understands.
+    Create { status: IconStatus, err: tray_icon::BadIcon },
+    /// Failed to read a custom icon from the config directory.
+    CustomRead { path: PathBuf, err: std::io::Error },
+    /// Failed to decode a custom icon from the config directory.
+    CustomDecode { path: PathBuf, err: image::ImageError },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            Decode { status, .. } => write!(f, "Failed to decode embedded {} icon image", status.variant()),
+            Create { status, .. } => write!(f, "Failed to create {} icon from decoded image", status.variant()),
+            CustomRead { path, .. } => write!(f, "Failed to read custom icon '{}'", path.display()),
+            CustomDecode { path, .. } => write!(f, "Failed to decode custom icon image '{}'", path.display()),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            Decode { err, .. } => Some(err),
+            Create { err, .. } => Some(err),
+            CustomRead { err, .. } => Some(err),
+            CustomDecode { err, .. } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Decodes the given raw image file to RGBA8-pixels.
+///
+/// # Arguments
+/// - `raw`: The bytes of some image file (e.g., a PNG).
+///
+/// # Returns
+/// The decoded image.
+///
+/// # Errors
+/// This function errors if we failed to decode the given image.
+#[inline]
+fn convert_image_to_raw(raw: &[u8]) -> Result<RgbaImage, image::ImageError> { Ok(image::load_from_memory(raw)?.into_rgba8()) }
+
+/// Decodes a custom icon from the config directory.
+///
+/// # Arguments
+/// - `path`: The path of the image file.
+///
+/// # Returns
+/// The decoded image.
+///
+/// # Errors
+/// This function errors if we failed to read or decode the image.
+fn load_custom(path: &Path) -> Result<RgbaImage, Error> {
+    let raw: Vec<u8> = match fs::read(path) {
+        Ok(raw) => raw,
+        Err(err) => return Err(Error::CustomRead { path: path.into(), err }),
+    };
+    match convert_image_to_raw(&raw) {
+        Ok(img) => Ok(img),
+        Err(err) => Err(Error::CustomDecode { path: path.into(), err }),
+    }
+}
+
+/// Finds and decodes the custom icon for the given status in the config directory, if the user put one there.
+///
+/// A custom icon that fails to load is skipped with a warning, so that a broken file never keeps the app from starting.
+///
+/// # Arguments
+/// - `config_dir`: The directory to look for custom icons in.
+/// - `status`: The [`IconStatus`] that the icon is shown for.
+///
+/// # Returns
+/// The decoded image, or [`None`] if there is no (usable) custom icon.
+fn find_custom(config_dir: &Path, status: IconStatus) -> Option<RgbaImage> {
+    for name in status.custom_names() {
+        let path: PathBuf = config_dir.join(name);
+        if !path.exists() {
+            continue;
+        }
+        match load_custom(&path) {
+            Ok(img) => {
+                info!("Using custom {} icon '{}'", status.variant(), path.display());
+                return Some(img);
+            },
+            Err(err) => warn!("{} (falling back to the default icon)", err.trace()),
+        }
+    }
+    None
+}
+
+/// Decodes the icon for the given status, preferring a custom one in the config directory over the embedded one.
+///
+/// # Arguments
+/// - `config_dir`: The directory to look for custom icons in.
+/// - `status`: The [`IconStatus`] that the icon is shown for.
+/// - `raw`: The bytes of the embedded image file to fall back to.
+///
+/// # Returns
+/// The decoded image.
+///
+/// # Errors
+/// This function errors if we had to fall back to the embedded image and failed to decode it.
+fn load(config_dir: &Path, status: IconStatus, raw: &[u8]) -> Result<RgbaImage, Error> {
+    if let Some(img) = find_custom(config_dir, status) {
+        return Ok(img);
+    }
+    match convert_image_to_raw(raw) {
+        Ok(img) => Ok(img),
+        Err(err) => Err(Error::Decode { status, err }),
+    }
+}
+
+/// Makes a version of an image that is tinted with the given colour, keeping its transparency.
+///
+/// # Arguments
+/// - `img`: The image to tint.
+/// - `tint`: The colour to blend every pixel halfway towards.
+///
+/// # Returns
+/// A new, tinted image.
+fn tint(img: &RgbaImage, tint: Rgba<u8>) -> RgbaImage {
+    let mut res: RgbaImage = img.clone();
+    for pixel in res.pixels_mut() {
+        for c in 0..3 {
+            pixel[c] = ((pixel[c] as u16 + tint[c] as u16) / 2) as u8;
+        }
+    }
+    res
+}
+
+/// Draws a badge with the given count in the bottom-right corner of an image.
+///
+/// # Arguments
+/// - `img`: The image to draw on.
+/// - `count`: The count to show. Anything above [`BADGE_MAX`] is shown as `99+`.
+fn draw_badge(img: &mut RgbaImage, count: usize) {
+    let text: String = if count > BADGE_MAX { format!("{BADGE_MAX}+") } else { count.to_string() };
+
+    // The badge is a pill that is half as high as the image, with a margin of one glyph pixel around the text
+    let (width, height): (u32, u32) = img.dimensions();
+    let scale: u32 = (width.min(height) / 2 / 7).max(1);
+    let cols: u32 = 4 * text.len() as u32 - 1;
+    let (w, h): (u32, u32) = (((cols + 2) * scale).max(7 * scale).min(width), (7 * scale).min(height));
+    let (x0, y0): (u32, u32) = (width - w, height - h);
+    let radius: f32 = h as f32 / 2.0;
+    let (left, right, middle): (f32, f32, f32) = (x0 as f32 + radius, width as f32 - radius, y0 as f32 + radius);
+    for y in y0..height {
+        for x in x0..width {
+            let (px, py): (f32, f32) = (x as f32 + 0.5, y as f32 + 0.5);
+            let dx: f32 = if px < left {
+                left - px
+            } else if px > right {
+                px - right
+            } else {
+                0.0
+            };
+            let dy: f32 = py - middle;
+            if dx * dx + dy * dy <= radius * radius {
+                img.put_pixel(x, y, BADGE_COLOR);
+            }
+        }
+    }
+
+    // Then the text, centered on it
+    let (tx, ty): (u32, u32) = (x0 + w.saturating_sub(cols * scale) / 2, y0 + scale);
+    for (i, c) in text.chars().enumerate() {
+        let glyph: &[u8; 5] = match GLYPHS.iter().find(|(g, _)| *g == c) {
+            Some((_, glyph)) => glyph,
+            None => continue,
+        };
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..3 {
+                if (bits >> (2 - col)) & 1 == 0 {
+                    continue;
+                }
+                let (gx, gy): (u32, u32) = (tx + (4 * i as u32 + col) * scale, ty + row as u32 * scale);
+                for y in gy..(gy + scale).min(height) {
+                    for x in gx..(gx + scale).min(width) {
+                        img.put_pixel(x, y, BADGE_TEXT_COLOR);
+                    }
+                }
+            }
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines what the tray icon tells the user at a glance.
+#[derive(Clone, Copy, Debug, EnumDebug, Eq, Hash, PartialEq)]
+pub enum IconStatus {
+    /// Nothing needs the user's attention.
+    Normal,
+    /// There are unread events.
+    Events,
+    /// At least one server could not be reached for a few polls in a row (see [`MutableAppState::warn_after_failures`]).
+    Warning,
+    /// At least one server answers with something that we can't use (e.g., because it rejects our credentials).
+    Error,
+}
+impl IconStatus {
+    /// Decides which icon to show for the given state.
+    ///
+    /// Servers that need fixing take precedence over servers that are down, which take precedence over unread events, since those
+    /// may hide events that we don't know about yet. Servers that are down only count once they failed
+    /// [`MutableAppState::warn_after_failures`] polls in a row, such that a single hiccup doesn't make the icon flicker.
+    ///
+    /// # Arguments
+    /// - `state`: The [`MutableAppState`] to summarize.
+    ///
+    /// # Returns
+    /// The matching IconStatus.
+    pub fn of(state: &MutableAppState) -> Self {
+        let failed = |name: &String| -> bool { state.failures.get(name).is_some_and(|failures| *failures >= state.warn_after_failures) };
+        if state.status.values().any(|status| *status == ServerStatus::Degraded) {
+            Self::Error
+        } else if state.status.iter().any(|(name, status)| *status == ServerStatus::Unreachable && failed(name)) {
+            Self::Warning
+        } else if state.events.iter().any(|event| state.is_unread(event)) {
+            Self::Events
+        } else {
+            Self::Normal
+        }
+    }
+
+    /// Returns the names of the files in the config directory that replace the icon for this status.
+    ///
+    /// # Returns
+    /// The file names like `icon.png` or `icon-events.ico`, in order of preference.
+    fn custom_names(&self) -> impl Iterator<Item = String> {
+        let stem: &'static str = match self {
+            Self::Normal => "icon",
+            Self::Events => "icon-events",
+            Self::Warning => "icon-warning",
+            Self::Error => "icon-error",
+        };
+        CUSTOM_EXTENSIONS.into_iter().map(move |ext| format!("{stem}.{ext}"))
+    }
+}
+
+
+
+/// The decoded versions of all icons.
+#[derive(Clone)]
+pub struct Icons {
+    /// The image for [`IconStatus::Normal`].
+    normal:   RgbaImage,
+    /// The image for [`IconStatus::Events`].
+    events:   RgbaImage,
+    /// The image for [`IconStatus::Warning`].
+    warning:  RgbaImage,
+    /// The image for [`IconStatus::Error`].
+    error:    RgbaImage,
+    /// The icon last rendered for every status, together with the badge count on it.
+    rendered: RefCell<HashMap<IconStatus, (usize, Icon)>>,
+}
+impl Icons {
+    /// Decodes all icons.
+    ///
+    /// Any of them can be replaced by putting an image in the config directory: `icon.png` for [`IconStatus::Normal`],
+    /// `icon-events.png` for [`IconStatus::Events`], `icon-warning.png` for [`IconStatus::Warning`] and `icon-error.png` for
+    /// [`IconStatus::Error`] (or `.ico` instead). Custom icons that fail to load are skipped with a warning.
+    ///
+    /// There is no embedded icon for [`IconStatus::Warning`]; unless replaced, it's a red-tinted version of the normal one.
+    ///
+    /// # Arguments
+    /// - `config_dir`: The directory to look for custom icons in.
+    ///
+    /// # Returns
+    /// A new Icons that can render the icon for every [`IconStatus`] without decoding anything again.
+    ///
+    /// # Errors
+    /// This function errors if we failed to decode any of the embedded icons that we needed.
+    pub fn load(config_dir: &Path) -> Result<Self, Error> {
+        debug!("Loading icons...");
+        let normal: RgbaImage = load(config_dir, IconStatus::Normal, NORMAL)?;
+        Ok(Self {
+            events: load(config_dir, IconStatus::Events, EVENTS)?,
+            warning: find_custom(config_dir, IconStatus::Warning).unwrap_or_else(|| tint(&normal, WARNING_TINT)),
+            error: load(config_dir, IconStatus::Error, ERROR)?,
+            normal,
+            rendered: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the base image shown for the given status.
+    ///
+    /// # Arguments
+    /// - `status`: The [`IconStatus`] to show.
+    ///
+    /// # Returns
+    /// A reference to the matching image, without any badge.
+    #[inline]
+    pub fn get(&self, status: IconStatus) -> &RgbaImage {
+        match status {
+            IconStatus::Normal => &self.normal,
+            IconStatus::Events => &self.events,
+            IconStatus::Warning => &self.warning,
+            IconStatus::Error => &self.error,
+        }
+    }
+
+    /// Renders the icon to show for the given status.
+    ///
+    /// The last icon rendered for every status is cached, such that swapping between statuses (with the same badge) only clones it.
+    ///
+    /// # Arguments
+    /// - `status`: The [`IconStatus`] to show.
+    /// - `badge`: A count to show in the corner of the icon, or `0` to show none.
+    ///
+    /// # Returns
+    /// An [`Icon`] that the tray understands.
+    ///
+    /// # Errors
+    /// This function errors if the tray did not accept the rendered image.
+    pub fn render(&self, status: IconStatus, badge: usize) -> Result<Icon, Error> {
+        // Every count above the maximum shows the same badge
+        let badge: usize = badge.min(BADGE_MAX + 1);
+        if let Some((_, icon)) = self.rendered.borrow().get(&status).filter(|(rendered, _)| *rendered == badge) {
+            return Ok(icon.clone());
+        }
+
+        // Otherwise, render it anew
+        let mut img: RgbaImage = self.get(status).clone();
+        if badge > 0 {
+            draw_badge(&mut img, badge);
+        }
+        let (width, height): (u32, u32) = img.dimensions();
+        let icon: Icon = match Icon::from_rgba(img.into_raw(), width, height) {
+            Ok(icon) => icon,
+            Err(err) => return Err(Error::Create { status, err }),
+        };
+        self.rendered.borrow_mut().insert(status, (badge, icon.clone()));
+        Ok(icon)
+    }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    02 Apr 2024, 15:13:02
 //  Last edited:
-//    01 May 2024, 19:51:49
+//    16 Oct 2026, 10:56:58
 //  Auto updated?
 //    Yes
 //
@@ -16,22 +16,135 @@
 
 // Declare submodules
 pub mod gui;
+pub mod headless;
+pub mod icons;
 pub mod pipeline;
+pub mod tray;
 pub mod window;
 
 // Imports
+use std::convert::Infallible;
 use std::error;
 use std::fmt::{Display, Formatter, Result as FResult};
 use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender};
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Local, Utc};
 use egui_winit::winit;
-use log::{debug, info};
+use enum_debug::EnumDebug;
+use error_trace::ErrorTrace as _;
+use log::{debug, info, warn};
 use tray_icon::menu::MenuEvent;
-use winit::event::Event;
-use winit::event_loop::{EventLoop, EventLoopBuilder};
+use tray_icon::{MouseButton, MouseButtonState, TrayIconEvent};
+use url::Url;
+use winit::dpi::PhysicalPosition;
+use winit::event::{Event, StartCause, WindowEvent};
+use winit::event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopClosed, EventLoopProxy, EventLoopWindowTarget};
+
+use crate::app::tray::{TrayIcon, TrayIconHandle, TrayIconMenuItem};
+use crate::app::window::{Window, WindowResponse};
+use crate::i18n::Lang;
+use crate::instance::InstanceLock;
+use crate::notify::{NotifierConfig, Notifiers};
+use crate::poller::{Poller, Replay};
+use crate::state::{AppState, MenuAction, MenuItemSpec, MuteState, ServerConfig, ServerStatus};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::app::tray::MuteDuration;
+    use crate::clock::{Clock as _, MockClock};
+
+    /// Builds an [`AppState`] in the given directory, of which the clock only moves when told to.
+    fn mock_state(dir: &tempfile::TempDir) -> (AppState, Arc<MockClock>) {
+        let clock: Arc<MockClock> = Arc::new(MockClock::new(Local::now()));
+        (AppState::with_clock(dir.path().into(), clock.clone()).unwrap(), clock)
+    }
+
+    #[test]
+    fn mute_item_toggles_mute() {
+        let dir: tempfile::TempDir = tempfile::tempdir().unwrap();
+        let (state, _): (AppState, _) = mock_state(&dir);
+        assert!(!state.access(|state| state.muted.is_muted()));
+
+        apply_tray_item(&state, None, None, TrayIconMenuItem::Mute);
+        assert!(state.access(|state| state.muted.is_muted()));
+        apply_tray_item(&state, None, None, TrayIconMenuItem::Mute);
+        assert!(!state.access(|state| state.muted.is_muted()));
+    }
+
+    #[test]
+    fn mute_for_item_mutes_until_app_clock_deadline() {
+        let dir: tempfile::TempDir = tempfile::tempdir().unwrap();
+        let (state, clock): (AppState, Arc<MockClock>) = mock_state(&dir);
+
+        apply_tray_item(&state, None, None, TrayIconMenuItem::MuteFor(MuteDuration::Hour1));
+        let deadline: DateTime<Utc> = (clock.now() + chrono::Duration::hours(1)).with_timezone(&Utc);
+        assert_eq!(state.access(|state| state.muted.clone()), MuteState::After(deadline));
+
+        // The deadline holds until the clock passes it
+        clock.advance(chrono::Duration::minutes(59));
+        resolve_mute(&state);
+        assert!(state.access(|state| state.muted.is_muted()));
+        clock.advance(chrono::Duration::minutes(2));
+        resolve_mute(&state);
+        assert!(state.access(|state| state.muted.is_unmuted()));
+    }
+
+    #[test]
+    fn snooze_event_snoozes_until_app_clock_deadline() {
+        let dir: tempfile::TempDir = tempfile::tempdir().unwrap();
+        let (state, clock): (AppState, Arc<MockClock>) = mock_state(&dir);
+
+        snooze(&state, "Disk almost full".into());
+        let deadline: DateTime<Utc> = (clock.now() + chrono::Duration::hours(1)).with_timezone(&Utc);
+        assert_eq!(state.access(|state| state.snoozed.get("Disk almost full").cloned()), Some(MuteState::After(deadline)));
+    }
+
+    #[test]
+    #[ignore = "needs a display server"]
+    fn app_events_are_routed_through_event_loop() {
+        let dir: tempfile::TempDir = tempfile::tempdir().unwrap();
+        let (state, _): (AppState, _) = mock_state(&dir);
+
+        // Tests don't run on the main thread, which winit only allows when asked nicely
+        let mut builder: EventLoopBuilder<AppEvent> = EventLoopBuilder::with_user_event();
+        #[cfg(target_os = "linux")]
+        winit::platform::x11::EventLoopBuilderExtX11::with_any_thread(&mut builder, true);
+        let eloop: AppEventLoop = builder.build().unwrap();
+        let proxy: EventLoopProxy<AppEvent> = eloop.create_proxy();
+        for item in [TrayIconMenuItem::Mute, TrayIconMenuItem::Open, TrayIconMenuItem::Exit] {
+            proxy.send_event(AppEvent::Action(item)).unwrap();
+        }
+
+        // Run the loop until the exit we sent ourselves
+        let (mut window, mut popover): (Option<Window>, Option<Window>) = (None, None);
+        eloop
+            .run(|event, eloop| {
+                if let Event::UserEvent(event) = event {
+                    handle_app_event(&state, None, None, &mut window, &mut popover, eloop, event);
+                }
+            })
+            .unwrap();
+        assert!(state.access(|state| state.muted.is_muted()));
+        assert!(window.is_some());
+    }
+}
+
+
+/***** CONSTANTS *****/
+/// The interval at which we check for events that have become too old to keep.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+/// How long an event title is snoozed when the user does so from a notification.
+const SNOOZE_DURATION: Duration = Duration::from_secs(60 * 60);
+
+
 
-use crate::app::window::Window;
-use crate::state::AppState;
 
 
 /***** ERRORS *****/
@@ -44,6 +157,14 @@ pub enum Error {
     EventLoopCreate { err: winit::error::EventLoopError },
     /// Failed to run the backend EventLoop.
     EventLoopRun { err: winit::error::EventLoopError },
+    /// Failed to set up the notification backends.
+    NotifiersCreate { err: crate::notify::Error },
+    /// Failed to spawn the pollers.
+    PollerSpawn { err: crate::poller::Error },
+    /// Failed to catch the signal that asks us to stop.
+    SignalHandlerSet { err: ctrlc::Error },
+    /// Failed to create the tray icon.
+    TrayIconCreate { err: crate::app::tray::Error },
 }
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
@@ -52,6 +173,10 @@ impl Display for Error {
             AppStateCreate { .. } => write!(f, "Failed to load app state"),
             EventLoopCreate { .. } => write!(f, "Failed to create main event loop"),
             EventLoopRun { .. } => write!(f, "Failed to run main event loop"),
+            NotifiersCreate { .. } => write!(f, "Failed to set up notification backends"),
+            PollerSpawn { .. } => write!(f, "Failed to spawn server pollers"),
+            SignalHandlerSet { .. } => write!(f, "Failed to set handler for interrupt signal"),
+            TrayIconCreate { .. } => write!(f, "Failed to create tray icon"),
         }
     }
 }
@@ -62,9 +187,610 @@ impl error::Error for Error {
             AppStateCreate { err } => Some(err),
             EventLoopCreate { err } => Some(err),
             EventLoopRun { err } => Some(err),
+            NotifiersCreate { err } => Some(err),
+            PollerSpawn { err } => Some(err),
+            SignalHandlerSet { err } => Some(err),
+            TrayIconCreate { err } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// The [`EventLoop`] of the app, which dispatches [`AppEvent`]s.
+///
+/// Everything that talks to the event loop should use these aliases instead of naming the user event type themselves, such that
+/// it cannot accidentally end up with a loop that never dispatches its events.
+pub type AppEventLoop = EventLoop<AppEvent>;
+/// The [`AppEventLoop`] as seen from within its event handler.
+pub type AppEventLoopTarget = EventLoopWindowTarget<AppEvent>;
+
+
+
+/// A handle to send [`AppEvent`]s to the main loop from elsewhere.
+///
+/// Usually, that's the [`AppEventLoop`]; but when running [`Headless`](headless::Headless), there is none, and events go over a
+/// channel instead.
+#[derive(Clone, Debug)]
+pub enum AppEventLoopProxy {
+    /// Sends events to an [`AppEventLoop`].
+    EventLoop(EventLoopProxy<AppEvent>),
+    /// Sends events to the loop of a [`Headless`](headless::Headless) app.
+    Headless(Sender<AppEvent>),
+}
+impl AppEventLoopProxy {
+    /// Sends an [`AppEvent`] to the main loop, waking it up if necessary.
+    ///
+    /// # Arguments
+    /// - `event`: The [`AppEvent`] to send.
+    ///
+    /// # Errors
+    /// This function errors if the main loop no longer exists, in which case the event is handed back.
+    pub fn send_event(&self, event: AppEvent) -> Result<(), EventLoopClosed<AppEvent>> {
+        match self {
+            Self::EventLoop(proxy) => proxy.send_event(event),
+            Self::Headless(sender) => sender.send(event).map_err(|err| EventLoopClosed(err.0)),
         }
     }
 }
+impl From<EventLoopProxy<AppEvent>> for AppEventLoopProxy {
+    #[inline]
+    fn from(value: EventLoopProxy<AppEvent>) -> Self { Self::EventLoop(value) }
+}
+
+
+
+/// Defines the user events that are sent to the main [`AppEventLoop`].
+#[derive(Debug, EnumDebug)]
+pub enum AppEvent {
+    /// The user clicked something in the tray icon's menu.
+    Menu(MenuEvent),
+    /// Something else asked to do what one of the tray icon's menu items does (e.g., another instance or the control socket).
+    ///
+    /// Unlike [`AppEvent::Menu`], this works whether the item is in the menu or not.
+    Action(TrayIconMenuItem),
+    /// The user interacted with the tray icon itself.
+    Tray(TrayIconEvent),
+    /// The poller ingested new events from a server.
+    NewEvents {
+        /// The name of the server that sent the events.
+        server: String,
+        /// The number of new events.
+        count:  usize,
+        /// Whether these events were only used as a starting point, and should not be notified about.
+        silent: bool,
+    },
+    /// A server became reachable or unreachable, or started to answer differently.
+    ConnectionChanged {
+        /// The name of the server.
+        server: String,
+        /// How the last poll of the server went.
+        status: ServerStatus,
+    },
+    /// The user asked to not be notified about events with some title for a while (e.g., from a notification).
+    Snooze {
+        /// The title of the events to snooze.
+        title: String,
+    },
+    /// The user clicked the notification of an event, so the window should be opened at that event.
+    Reveal {
+        /// The [key](crate::event::Event::key()) of the event.
+        key: String,
+    },
+    /// The config file changed (e.g., because the user edited it by hand), so its settings should be applied.
+    ReloadConfig,
+    /// The machine woke up from sleep, so everything we know may be outdated.
+    Woke {
+        /// Roughly how long the machine slept.
+        slept: Duration,
+    },
+    /// Someone wants to know whether the event loop still dispatches our events (e.g., the metrics endpoint, or the probe sent at
+    /// startup).
+    HealthCheck {
+        /// Where to answer once the event loop got around to it.
+        reply: Sender<()>,
+    },
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Removes events that have become too old to keep, if the user configured a maximum age.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] to prune the events of.
+fn prune_events(state: &AppState) {
+    if state.access(|state| state.retention_age.is_none()) {
+        return;
+    }
+    let now: DateTime<Local> = state.now();
+    if let Ok(Err(err)) = state.access_mut(|state| -> Result<(), Infallible> {
+        let pruned: usize = state.prune(now);
+        if pruned > 0 {
+            debug!("Pruned {pruned} event(s) that exceeded the retention age");
+        }
+        Ok(())
+    }) {
+        warn!("{}", err.trace());
+    }
+}
+
+/// Unmutes the app if it was muted until some deadline that has now passed, and does the same for snoozed event titles.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] to resolve the mute state of.
+fn resolve_mute(state: &AppState) {
+    let now: DateTime<Local> = state.now();
+    let passed = |mute: &MuteState| -> bool { matches!(mute, MuteState::After(deadline) if *deadline <= now) };
+    if !state.access(|state| passed(&state.muted) || state.snoozed.values().any(passed)) {
+        return;
+    }
+    if let Ok(Err(err)) = state.access_mut(|state| -> Result<(), Infallible> {
+        if state.muted.tick(now) {
+            debug!("Mute deadline has passed; unmuting");
+            state.mark_dirty();
+        }
+        let unsnoozed: usize = state.resolve_snoozes(now);
+        if unsnoozed > 0 {
+            debug!("Snooze deadline of {unsnoozed} event title(s) has passed; unsnoozing");
+        }
+        Ok(())
+    }) {
+        warn!("{}", err.trace());
+    }
+}
+
+/// Stops notifying about events with the given title for [`SNOOZE_DURATION`].
+///
+/// # Arguments
+/// - `state`: The [`AppState`] to snooze the title in.
+/// - `title`: The title of the events to snooze.
+fn snooze(state: &AppState, title: String) {
+    info!("Snoozing event title '{title}' for {}s", SNOOZE_DURATION.as_secs());
+    let snooze: MuteState = MuteState::lasting(state.now(), SNOOZE_DURATION);
+    if let Ok(Err(err)) = state.access_mut(|state| -> Result<(), Infallible> {
+        state.snoozed.insert(title, snooze);
+        state.mark_dirty();
+        Ok(())
+    }) {
+        warn!("{}", err.trace());
+    }
+}
+
+/// Collects the settings that a [`Poller`] only reads when it's spawned.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] to collect the settings from.
+///
+/// # Returns
+/// The settings as JSON, such that two versions can be compared.
+fn poller_config(state: &AppState) -> serde_json::Value {
+    state.access(|state| {
+        serde_json::json!({
+            "servers": state.servers,
+            "user_agent": state.user_agent,
+            "max_concurrent_polls": state.max_concurrent_polls,
+            "notifiers": state.notifiers,
+            "max_notifications_per_minute": state.max_notifications_per_minute,
+            "webhook": state.webhook,
+            "language": state.language,
+        })
+    })
+}
+
+/// Spawns a [`Poller`] for the servers, together with the notification backends it reports to.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] that lists the servers and notifiers.
+/// - `proxy`: An [`AppEventLoopProxy`] for the poller to notify the main loop of new events with.
+/// - `replay`: If given, replays the events in this [`Replay`] instead of polling the servers.
+///
+/// # Returns
+/// A new [`Poller`] that runs in the background.
+///
+/// # Errors
+/// This function errors if we failed to set up the notifiers or to spawn the poller.
+fn spawn_poller(state: &AppState, proxy: &AppEventLoopProxy, replay: Option<&Replay>) -> Result<Poller, Error> {
+    let (configs, max_per_minute, lang, webhook): (Vec<NotifierConfig>, usize, Lang, Option<Url>) =
+        state.access(|state| (state.notifiers.clone(), state.max_notifications_per_minute, state.lang(), state.webhook.clone()));
+    let mut notifiers: Notifiers = match Notifiers::from_config(&configs, max_per_minute, lang, state.shared_clock(), proxy) {
+        Ok(notifiers) => notifiers,
+        Err(err) => return Err(Error::NotifiersCreate { err }),
+    };
+    if let Some(url) = webhook {
+        if let Err(err) = notifiers.set_webhook(url) {
+            return Err(Error::NotifiersCreate { err });
+        }
+    }
+    let poller: Result<Poller, crate::poller::Error> = match replay {
+        Some(replay) => Poller::replay(state.clone(), proxy.clone(), notifiers, replay),
+        None => Poller::spawn(state.clone(), proxy.clone(), notifiers),
+    };
+    match poller {
+        Ok(poller) => Ok(poller),
+        Err(err) => Err(Error::PollerSpawn { err }),
+    }
+}
+
+/// Starts the background threads through which the outside world talks to the main loop.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] that says which of them the user wants.
+/// - `instance`: The [`InstanceLock`] to answer other instances on.
+/// - `proxy`: An [`AppEventLoopProxy`] for the threads to reach the main loop with.
+fn spawn_services(state: &AppState, instance: &mut InstanceLock, proxy: &AppEventLoopProxy) {
+    // Let other instances ask us to show ourselves
+    instance.listen(proxy.clone());
+
+    // Catch up as soon as the machine wakes up from sleep
+    crate::wake::watch(proxy.clone());
+
+    // Apply changes to the config file without a restart
+    crate::reload::watch(state.clone(), proxy.clone());
+
+    // Serve health and metrics, if the user asked for it
+    if let Some(addr) = state.access(|state| state.metrics_addr) {
+        if let Err(err) = crate::metrics::serve(addr, state.clone(), proxy.clone()) {
+            warn!("{}", err.trace());
+        }
+    }
+
+    // Let scripts control us, if the user asked for it
+    if let Some(path) = state.access(|state| state.control_socket.clone()) {
+        #[cfg(unix)]
+        if let Err(err) = crate::control::serve(&path, state.clone(), proxy.clone()) {
+            warn!("{}", err.trace());
+        }
+        #[cfg(not(unix))]
+        warn!("Ignoring control socket '{}' as it's not supported on this OS", path.display());
+    }
+}
+
+/// Replaces a [`Poller`] with a fresh one, such that changed servers and notifiers apply.
+///
+/// If that fails, the old one keeps running.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] that lists the servers.
+/// - `proxy`: An [`AppEventLoopProxy`] for the new poller to notify the main loop of new events with.
+/// - `poller`: The [`Poller`] to replace.
+fn respawn_poller(state: &AppState, proxy: &AppEventLoopProxy, poller: &mut Poller) {
+    info!("Respawning pollers to apply the new config...");
+    match spawn_poller(state, proxy, None) {
+        Ok(new) => *poller = new,
+        Err(err) => warn!("{}", err.trace()),
+    }
+}
+
+/// Decides when the event loop has to wake up next if nothing else happens in the meantime.
+///
+/// Polling happens on separate threads, which wake the event loop themselves when they have something to report. So the only
+/// things to wake up for are pruning old events and the end of a timed mute or snooze (besides windows that want to be repainted).
+///
+/// # Arguments
+/// - `state`: The [`AppState`] to find the scheduled work in.
+/// - `next_prune`: The time at which we want to prune events next.
+///
+/// # Returns
+/// The [`Instant`] to wake up at, or [`None`] if we can sleep until the next event.
+fn next_wakeup(state: &AppState, next_prune: Instant) -> Option<Instant> {
+    let now: DateTime<Utc> = state.now().with_timezone(&Utc);
+    let (prune, deadline): (bool, Option<DateTime<Utc>>) = state.access(|state| {
+        let deadline: Option<DateTime<Utc>> = std::iter::once(&state.muted)
+            .chain(state.snoozed.values())
+            .filter_map(|mute| if let MuteState::After(deadline) = mute { Some(*deadline) } else { None })
+            .min();
+        (state.retention_age.is_some(), deadline)
+    });
+    let unmute: Option<Instant> = deadline.map(|deadline| Instant::now() + (deadline - now).to_std().unwrap_or(Duration::ZERO));
+    match (prune.then_some(next_prune), unmute) {
+        (Some(prune), Some(unmute)) => Some(prune.min(unmute)),
+        (prune, unmute) => prune.or(unmute),
+    }
+}
+
+/// Opens the main window, or focuses it if it's already open.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] that the window visualizes.
+/// - `tray`: A [`TrayIconHandle`] to update the tray icon with, if we have one.
+/// - `window`: The [`Window`] to open (or focus).
+/// - `eloop`: The [`EventLoopWindowTarget`] used to spawn the window.
+fn open_window(state: &AppState, tray: Option<&TrayIconHandle>, window: &mut Option<Window>, eloop: &AppEventLoopTarget) {
+    // The user is about to see all events
+    if let Ok(Err(err)) = state.access_mut(|state| -> Result<(), Infallible> {
+        state.mark_read();
+        Ok(())
+    }) {
+        warn!("{}", err.trace());
+    }
+    if let Some(tray) = tray {
+        tray.refresh(state);
+    }
+
+    // Focus the window if it already exists; else, make a new one
+    if let Some(window) = window {
+        window.focus();
+        return;
+    }
+    match Window::new(eloop, state, "Server Events") {
+        Ok(win) => *window = Some(win),
+        Err(err) => warn!("{}", err.trace()),
+    }
+}
+
+/// Opens the given URL in the user's browser.
+///
+/// # Arguments
+/// - `url`: The [`Url`] to open.
+fn open_url(url: &Url) {
+    debug!("Opening '{url}'...");
+    if let Err(err) = open::that_detached(url.as_str()) {
+        warn!("Failed to open '{url}' in browser: {err}");
+    }
+}
+
+/// Does what one of the items in the tray icon's menu does, for those items that don't depend on the main loop.
+///
+/// That's all of them except [`TrayIconMenuItem::Open`] and [`TrayIconMenuItem::Exit`], which are left to the caller.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] that we may mutate based on the click.
+/// - `tray`: A [`TrayIconHandle`] to update the tray icon with, if we have one.
+/// - `poller`: The [`Poller`] to refresh, if we have one.
+/// - `item`: The [`TrayIconMenuItem`] that was clicked.
+fn apply_tray_item(state: &AppState, tray: Option<&TrayIconHandle>, poller: Option<&Poller>, item: TrayIconMenuItem) {
+    match item {
+        TrayIconMenuItem::Open | TrayIconMenuItem::Exit => warn!("Cannot apply '{}' without the main loop", item.variant()),
+
+        TrayIconMenuItem::Mute => {
+            let res: Result<bool, crate::state::Error> = match state.access_mut_from("tray", |state| -> Result<bool, Infallible> {
+                state.muted.toggle();
+                state.mark_dirty();
+                Ok(state.muted.is_muted())
+            }) {
+                Ok(res) => res,
+                Err(err) => match err {},
+            };
+            match res {
+                Ok(muted) => info!("Notifications are now {}", if muted { "muted" } else { "unmuted" }),
+                Err(err) => warn!("{}", err.trace()),
+            }
+        },
+
+        TrayIconMenuItem::MuteFor(duration) => {
+            let muted: MuteState = duration.mute_state(state.now());
+            if let MuteState::After(deadline) = &muted {
+                info!("Notifications are now muted until {}", deadline.with_timezone(&Local).format("%Y-%m-%d %H:%M"));
+            }
+            if let Ok(Err(err)) = state.access_mut_from("tray", |state| -> Result<(), Infallible> {
+                state.muted = muted;
+                state.mark_dirty();
+                Ok(())
+            }) {
+                warn!("{}", err.trace());
+            }
+        },
+
+        TrayIconMenuItem::MinSeverity(severity) => {
+            info!("Only notifying about events that are {} or worse", severity.variant());
+            if let Ok(Err(err)) = state.access_mut_from("tray", |state| -> Result<(), Infallible> {
+                state.min_severity = severity;
+                state.mark_dirty();
+                Ok(())
+            }) {
+                warn!("{}", err.trace());
+            }
+            if let Some(tray) = tray {
+                tray.refresh(state);
+            }
+        },
+
+        TrayIconMenuItem::Dashboard(i) => {
+            let url: Url = match state.access(|state| state.servers.get(i).map(ServerConfig::dashboard)) {
+                Some(url) => url,
+                None => {
+                    warn!("Received dashboard click for unknown server {i}");
+                    return;
+                },
+            };
+            open_url(&url);
+        },
+
+        TrayIconMenuItem::Refresh => {
+            if let Some(poller) = poller {
+                poller.refresh();
+            }
+        },
+
+        TrayIconMenuItem::AcknowledgeNewest => {
+            let res: Result<Option<String>, crate::state::Error> =
+                match state.access_mut_from("tray", |state| -> Result<Option<String>, Infallible> { Ok(state.acknowledge_newest()) }) {
+                    Ok(res) => res,
+                    Err(err) => match err {},
+                };
+            match res {
+                Ok(Some(key)) => info!("Acknowledged event '{key}'"),
+                Ok(None) => debug!("There is no unread event to acknowledge"),
+                Err(err) => warn!("{}", err.trace()),
+            }
+            if let Some(tray) = tray {
+                tray.refresh(state);
+            }
+        },
+
+        TrayIconMenuItem::Custom(i) => match state.access(|state| state.menu.get(i).cloned()) {
+            Some(MenuItemSpec::Custom { action: MenuAction::OpenUrl(url), .. }) => open_url(&url),
+            Some(MenuItemSpec::Custom { action: MenuAction::Refresh, .. }) => {
+                if let Some(poller) = poller {
+                    poller.refresh();
+                }
+            },
+            _ => warn!("Received click for unknown custom menu item {i}"),
+        },
+    }
+}
+
+/// Does what one of the items in the tray icon's menu does.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] that we may mutate based on the click.
+/// - `tray`: A [`TrayIconHandle`] to update the tray icon with, if we have one.
+/// - `poller`: The [`Poller`] to refresh, if we have one.
+/// - `window`: The [`Window`] that we may open (or close).
+/// - `eloop`: The [`EventLoopWindowTarget`] used to spawn windows or exit.
+/// - `item`: The [`TrayIconMenuItem`] that was clicked.
+fn handle_tray_event(
+    state: &AppState,
+    tray: Option<&TrayIconHandle>,
+    poller: Option<&Poller>,
+    window: &mut Option<Window>,
+    eloop: &AppEventLoopTarget,
+    item: TrayIconMenuItem,
+) {
+    debug!("Received '{}' click", item.variant());
+    match item {
+        TrayIconMenuItem::Open => open_window(state, tray, window, eloop),
+        TrayIconMenuItem::Exit => {
+            info!("Exiting app...");
+            eloop.exit();
+        },
+        item => apply_tray_item(state, tray, poller, item),
+    }
+}
+
+
+
+/// Handles one of our own [`AppEvent`]s.
+///
+/// This is separate from [`App::run()`] such that the routing can be driven without a full app.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] that we may mutate based on the event.
+/// - `tray`: A [`TrayIconHandle`] to update the tray icon with, if we have one.
+/// - `poller`: The [`Poller`] to refresh, if we have one.
+/// - `window`: The [`Window`] that we may open (or close).
+/// - `popover`: The popover [`Window`] near the tray icon that we may open.
+/// - `eloop`: The [`EventLoopWindowTarget`] used to spawn windows or exit.
+/// - `event`: The [`AppEvent`] to handle.
+pub fn handle_app_event(
+    state: &AppState,
+    tray: Option<&TrayIconHandle>,
+    poller: Option<&Poller>,
+    window: &mut Option<Window>,
+    popover: &mut Option<Window>,
+    eloop: &AppEventLoopTarget,
+    event: AppEvent,
+) {
+    match event {
+        // Tray icon events
+        AppEvent::Menu(event) => match tray.and_then(|tray| tray.classify(&event.id)) {
+            Some(item) => handle_tray_event(state, tray, poller, window, eloop, item),
+            None => warn!("Received unknown tray icon menu ID '{}'", event.id.as_ref()),
+        },
+        AppEvent::Action(item) => handle_tray_event(state, tray, poller, window, eloop, item),
+        AppEvent::Tray(TrayIconEvent::Click { position, button: MouseButton::Left, button_state: MouseButtonState::Up, .. }) => {
+            debug!("Received left click on tray icon");
+            if !state.access(|state| state.tray_popover) {
+                open_window(state, tray, window, eloop);
+                return;
+            }
+            // Clicking again closes the popover, like any other
+            if popover.take().is_some() {
+                return;
+            }
+            match Window::popover(eloop, state, PhysicalPosition::new(position.x, position.y)) {
+                Ok(win) => *popover = Some(win),
+                Err(err) => warn!("{}", err.trace()),
+            }
+        },
+        AppEvent::Tray(_) => {},
+
+        // Poller events
+        AppEvent::NewEvents { server, count, silent } => {
+            if silent {
+                info!("Seeded {count} existing event(s) from server '{server}'");
+            } else {
+                info!("Received {count} new event(s) from server '{server}'");
+            }
+            if let Some(tray) = tray {
+                tray.refresh(state);
+            }
+            for window in window.iter().chain(popover.iter()) {
+                window.request_redraw();
+            }
+        },
+        AppEvent::ConnectionChanged { server, status } => {
+            info!("Server '{server}' is now {}", status.describe());
+            if let Some(tray) = tray {
+                tray.refresh(state);
+            }
+            for window in window.iter().chain(popover.iter()) {
+                window.request_redraw();
+            }
+        },
+
+        // Notification events
+        AppEvent::Snooze { title } => {
+            snooze(state, title);
+            if let Some(window) = window {
+                window.request_redraw();
+            }
+        },
+        AppEvent::Reveal { key } => {
+            // Same as the "Open" item, but then with the event in view
+            open_window(state, tray, window, eloop);
+            if let Some(window) = window {
+                window.reveal(key);
+            }
+        },
+
+        // Config events
+        AppEvent::ReloadConfig => {
+            // The poller is respawned by `App::run()`, if needed
+            if let Err(err) = state.reload_config() {
+                warn!("{}", err.trace());
+                return;
+            }
+            // The menu may list other servers (or other items) now, which the indices of its items refer to
+            if let Some(tray) = tray {
+                tray.rebuild_menu(state);
+                tray.refresh(state);
+            }
+            if let Some(window) = window {
+                window.request_redraw();
+            }
+        },
+
+        // System events
+        AppEvent::Woke { slept } => {
+            // Whatever was scheduled during the sleep is due now
+            info!("Refreshing everything after sleeping for {}s", slept.as_secs());
+            if let Some(poller) = poller {
+                poller.refresh();
+            }
+            resolve_mute(state);
+            prune_events(state);
+            if let Some(tray) = tray {
+                tray.refresh(state);
+            }
+            if let Some(window) = window {
+                window.request_redraw();
+            }
+        },
+
+        // Metrics events
+        AppEvent::HealthCheck { reply } => {
+            // The endpoint may have given up waiting already, which is fine
+            let _ = reply.send(());
+        },
+    }
+}
 
 
 
@@ -74,24 +800,40 @@ impl error::Error for Error {
 /// Wraps the other GUI elements and manages them.
 pub struct App {
     /// The state shared between various components.
-    state:  AppState,
+    state:     AppState,
     /// The EventLoop that we use to handle events.
-    eloop:  EventLoop<MenuEvent>,
+    eloop:     AppEventLoop,
+    /// The Poller that fetches events from the servers in the background.
+    poller:    Poller,
+    /// Whether the [`Poller`] replays events from a file, in which case it's never respawned.
+    replaying: bool,
+    /// A proxy to the event loop, to hand to respawned [`Poller`]s.
+    proxy:     AppEventLoopProxy,
+    /// The lock that proves we're the only instance, which is released when the app exits.
+    _instance: InstanceLock,
+    /// A handle to the TrayIcon that lives in the taskbar. On Linux, the icon itself is owned by a separate GTK thread.
+    tray:      Option<TrayIconHandle>,
     /// The Window that, when spawned, provides deeper interaction.
-    window: Option<Window>,
+    window:    Option<Window>,
+    /// The small Window near the tray icon that, when spawned, provides a quick overview.
+    popover:   Option<Window>,
+    /// Answered once the loop dispatched the [`AppEvent::HealthCheck`] sent through the same proxy as the tray's events at startup.
+    probe:     Option<Receiver<()>>,
 }
 impl App {
     /// Constructor for the App that does what is necessary.
     ///
     /// # Arguments
     /// - `config_dir`: The directory to load persistent app state from.
+    /// - `instance`: The [`InstanceLock`] that proves we're the only instance for this `config_dir`.
+    /// - `replay`: If given, replays the events in this [`Replay`] instead of polling the servers.
     ///
     /// # Returns
     /// A new App, ready to rumble.
     ///
     /// # Errors
     /// This function errors if some part of the initialization failed.
-    pub fn new(config_dir: PathBuf) -> Result<Self, Error> {
+    pub fn new(config_dir: PathBuf, mut instance: InstanceLock, replay: Option<Replay>) -> Result<Self, Error> {
         info!("Initializing App...");
 
         // Build an app state
@@ -102,14 +844,73 @@ impl App {
 
         // Build an EventLoop to match
         debug!("Initializing EventLoop...");
-        let eloop: EventLoop<MenuEvent> = match EventLoopBuilder::with_user_event().build() {
+        let eloop: AppEventLoop = match EventLoopBuilder::with_user_event().build() {
             Ok(eloop) => eloop,
             Err(err) => return Err(Error::EventLoopCreate { err }),
         };
 
+        let proxy: AppEventLoopProxy = eloop.create_proxy().into();
+
+        // Start polling the servers
+        let poller: Poller = spawn_poller(&state, &proxy, replay.as_ref())?;
+
+        // Forward tray icon (menu) events to the event loop
+        // (and check that what's sent that way actually arrives, since it'd otherwise be silently dropped)
+        let (reply, probe): (Sender<()>, Receiver<()>) = std::sync::mpsc::channel();
+        let probe: Option<Receiver<()>> = match proxy.send_event(AppEvent::HealthCheck { reply }) {
+            Ok(_) => Some(probe),
+            Err(err) => {
+                warn!("Failed to send startup probe to event loop: {err}");
+                None
+            },
+        };
+        let menu_proxy: AppEventLoopProxy = proxy.clone();
+        MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+            if let Err(err) = menu_proxy.send_event(AppEvent::Menu(event)) {
+                warn!("Failed to forward tray icon menu event to event loop: {err}");
+            }
+        }));
+        let tray_proxy: AppEventLoopProxy = proxy.clone();
+        TrayIconEvent::set_event_handler(Some(move |event: TrayIconEvent| {
+            if let Err(err) = tray_proxy.send_event(AppEvent::Tray(event)) {
+                warn!("Failed to forward tray icon event to event loop: {err}");
+            }
+        }));
+
+        // Start the things that talk to us from the outside
+        spawn_services(&state, &mut instance, &proxy);
+
+        // On Linux, the tray icon requires GTK; so spawn a thread that runs its event loop
+        #[cfg(target_os = "linux")]
+        let tray: Option<TrayIconHandle> = {
+            debug!("Spawning GTK thread for tray icon...");
+            let state: AppState = state.clone();
+            let (sender, receiver): (Sender<TrayIconHandle>, Receiver<TrayIconHandle>) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                if let Err(err) = gtk::init() {
+                    log::error!("Failed to initialize GTK: {err} (no tray icon for this session)");
+                    return;
+                }
+                let tray_icon: TrayIcon = match TrayIcon::new(&state) {
+                    Ok(tray_icon) => tray_icon,
+                    Err(err) => {
+                        log::error!("{} (no tray icon for this session)", Error::TrayIconCreate { err }.trace());
+                        return;
+                    },
+                };
+                // If the app is gone already, then there's no-one to hand the icon to
+                let _ = sender.send(TrayIconHandle::install(tray_icon));
+                gtk::main();
+            });
+            // The thread drops the sender if it fails, so this doesn't hang
+            receiver.recv().ok()
+        };
+        #[cfg(not(target_os = "linux"))]
+        let tray: Option<TrayIconHandle> = None;
+
         // Done; build self
         info!("App initialization complete");
-        Ok(Self { state, eloop, window: None })
+        Ok(Self { state, eloop, poller, replaying: replay.is_some(), proxy, _instance: instance, tray, window: None, popover: None, probe })
     }
 
     /// Runs the app, receiving events and doing stuff based on that.
@@ -122,31 +923,116 @@ impl App {
     pub fn run(mut self) -> Result<(), Error> {
         // We start by running the event loop
         info!("Entering event loop...");
+        #[cfg_attr(target_os = "linux", allow(unused_mut))]
+        let mut res: Result<(), Error> = Ok(());
+        let mut next_prune: Instant = Instant::now() + PRUNE_INTERVAL;
+        // What the "Mute" items in the tray icon's menu show, which the mute state may have moved away from (e.g., through the GUI)
+        let mut menu_muted: bool = self.state.access(|state| state.muted.is_muted());
         match self.eloop.run(|event, eloop| {
             // Received an event!
             match event {
                 // Init event
-                // Event::NewEvents(StartCause::Init) => {},
+                Event::NewEvents(StartCause::Init) =>
+                {
+                    #[cfg(not(target_os = "linux"))]
+                    match TrayIcon::new(&self.state) {
+                        Ok(tray_icon) => self.tray = Some(TrayIconHandle::install(tray_icon)),
+                        Err(err) => {
+                            res = Err(Error::TrayIconCreate { err });
+                            eloop.exit();
+                            return;
+                        },
+                    }
+                },
+                // Timer event
+                Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
+                    if Instant::now() >= next_prune {
+                        prune_events(&self.state);
+                        next_prune = Instant::now() + PRUNE_INTERVAL;
+                    }
+                    resolve_mute(&self.state);
+                    let now: Instant = Instant::now();
+                    for window in self.window.iter_mut().chain(self.popover.iter_mut()) {
+                        window.request_redraw_if_due(now);
+                    }
+                },
+
+                // Our own events
+                Event::UserEvent(event) => {
+                    // Reloading the config may change the servers, which a running poller doesn't notice by itself
+                    let before: Option<serde_json::Value> = (matches!(event, AppEvent::ReloadConfig) && !self.replaying).then(|| poller_config(&self.state));
+                    handle_app_event(&self.state, self.tray.as_ref(), Some(&self.poller), &mut self.window, &mut self.popover, eloop, event);
+                    if before.is_some_and(|before| before != poller_config(&self.state)) {
+                        respawn_poller(&self.state, &self.proxy, &mut self.poller);
+                    }
+                },
 
                 // Window events
+                Event::WindowEvent { window_id, event: WindowEvent::RedrawRequested } => {
+                    // The only place where we paint, such that any number of requests leads to one frame
+                    if let Some(window) = self.window.iter_mut().chain(self.popover.iter_mut()).find(|window| window.id() == window_id) {
+                        window.render();
+                    }
+                },
                 Event::WindowEvent { window_id, event } => {
-                    let mut close: bool = false;
-                    if let Some(window) = &mut self.window {
-                        // See if it's about this window
-                        if window_id == window.id() {
-                            close = window.handle_event(event);
+                    // See which window it's about
+                    if let Some(window) = self.window.as_mut().filter(|window| window.id() == window_id) {
+                        // There's no point in opening the window while the user is looking at it
+                        let enabled: Option<bool> = match window.handle_event(event) {
+                            WindowResponse::Nothing => None,
+                            WindowResponse::Repaint | WindowResponse::Resized => {
+                                window.request_redraw();
+                                None
+                            },
+                            WindowResponse::Focused(focused) => {
+                                window.request_redraw();
+                                Some(!focused)
+                            },
+                            WindowResponse::Close => {
+                                self.window = None;
+                                Some(true)
+                            },
+                        };
+                        if let (Some(tray), Some(enabled)) = (&self.tray, enabled) {
+                            tray.set_open_enabled(enabled);
+                        }
+                    } else if let Some(popover) = self.popover.as_mut().filter(|popover| popover.id() == window_id) {
+                        // The popover also disappears as soon as the user looks elsewhere
+                        match popover.handle_event(event) {
+                            WindowResponse::Nothing => {},
+                            WindowResponse::Repaint | WindowResponse::Resized | WindowResponse::Focused(true) => popover.request_redraw(),
+                            WindowResponse::Focused(false) | WindowResponse::Close => self.popover = None,
+                        }
+                    }
+                },
+
+                // Sleep until something happens or until we have scheduled work, but never busy-loop
+                Event::AboutToWait => {
+                    // Events sent before the loop started are dispatched in its first iteration, so the probe must be answered by now
+                    if let Some(probe) = self.probe.take() {
+                        match probe.try_recv() {
+                            Ok(_) => debug!("Event loop dispatches events sent through its proxy"),
+                            Err(_) => warn!("Event loop did not dispatch the startup probe; tray icon events may not arrive"),
                         }
                     }
-                    if close {
-                        self.window = None;
+                    // Keep the checkmark of the "Mute" items in line with the actual mute state
+                    let muted: bool = self.state.access(|state| state.muted.is_muted());
+                    if let Some(tray) = self.tray.as_ref().filter(|_| muted != menu_muted) {
+                        tray.set_muted_checked(muted);
+                        menu_muted = muted;
                     }
+                    let repaint: Option<Instant> = self.window.iter().chain(self.popover.iter()).filter_map(Window::repaint_at).min();
+                    eloop.set_control_flow(match next_wakeup(&self.state, next_prune).into_iter().chain(repaint).min() {
+                        Some(wakeup) => ControlFlow::WaitUntil(wakeup),
+                        None => ControlFlow::Wait,
+                    });
                 },
 
                 // Other events are ignored
-                _ => return,
+                _ => (),
             }
         }) {
-            Ok(_) => Ok(()),
+            Ok(_) => res,
             Err(err) => Err(Error::EventLoopRun { err }),
         }
     }
@@ -4,7 +4,7 @@
 //  Created:
 //    01 May 2024, 19:50:40
 //  Last edited:
-//    01 May 2024, 20:06:43
+//    16 Oct 2026, 10:05:34
 //  Auto updated?
 //    Yes
 //
@@ -13,14 +13,213 @@
 //!   the egui window.
 //
 
-use wgpu;
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::sync::Arc;
 
+use egui::epaint::ClippedShape;
+use egui::{ClippedPrimitive, TexturesDelta};
+use egui_wgpu::{Renderer, ScreenDescriptor};
+use egui_winit::{egui, winit};
+use log::{debug, trace};
+use winit::dpi::PhysicalSize;
 
-/***** LIBRARY *****/
-/// Implements the wgpu part of the application.
+
+/***** ERRORS *****/
+/// Defines errors originating from a [`Pipeline`].
 #[derive(Debug)]
-pub struct Pipeline {}
+pub enum Error {
+    /// Failed to create a [`wgpu::Surface`] for the window.
+    SurfaceCreate { err: wgpu::CreateSurfaceError },
+    /// Failed to find a graphics adapter that can draw to the window.
+    AdapterRequest,
+    /// Failed to open a [`wgpu::Device`] on the chosen adapter.
+    DeviceRequest { adapter: String, err: wgpu::RequestDeviceError },
+    /// The chosen adapter cannot present to the window's surface.
+    SurfaceUnsupported { adapter: String },
+    /// Failed to get the next texture to draw on from the surface.
+    SurfaceTextureGet { err: wgpu::SurfaceError },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            SurfaceCreate { .. } => write!(f, "Failed to create render surface for window"),
+            AdapterRequest => write!(f, "Failed to find a graphics adapter that can render to the window"),
+            DeviceRequest { adapter, .. } => write!(f, "Failed to open device on graphics adapter '{adapter}'"),
+            SurfaceUnsupported { adapter } => write!(f, "Graphics adapter '{adapter}' cannot render to the window"),
+            SurfaceTextureGet { .. } => write!(f, "Failed to get next frame from render surface"),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            SurfaceCreate { err } => Some(err),
+            AdapterRequest => None,
+            DeviceRequest { err, .. } => Some(err),
+            SurfaceUnsupported { .. } => None,
+            SurfaceTextureGet { err } => Some(err),
+        }
+    }
+}
 
+
+
+
+
+/***** LIBRARY *****/
+/// Implements the wgpu part of the application.
+///
+/// Owns everything needed to paint the output of egui onto a single window.
+pub struct Pipeline {
+    /// The surface of the window that we paint on.
+    surface:  wgpu::Surface<'static>,
+    /// The logical device that does the painting.
+    device:   wgpu::Device,
+    /// The queue on which we submit work to the `device`.
+    queue:    wgpu::Queue,
+    /// How the `surface` is currently configured (e.g., its size).
+    config:   wgpu::SurfaceConfiguration,
+    /// The egui renderer that turns egui's meshes into draw calls.
+    renderer: Renderer,
+}
 impl Pipeline {
     /// Constructor for the Pipeline that initializes it with default resources.
+    ///
+    /// # Arguments
+    /// - `window`: The [`winit::Window`](winit::window::Window) to render to. Shared with the surface, which must not outlive it.
+    ///
+    /// # Returns
+    /// A new Pipeline that paints on the given `window`.
+    ///
+    /// # Errors
+    /// This function errors if we failed to find a graphics adapter or device that can render to the `window`.
+    pub fn new(window: &Arc<winit::window::Window>) -> Result<Self, Error> {
+        debug!("Building render pipeline...");
+        let instance: wgpu::Instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let surface: wgpu::Surface<'static> = match instance.create_surface(window.clone()) {
+            Ok(surface) => surface,
+            Err(err) => return Err(Error::SurfaceCreate { err }),
+        };
+
+        // Pick something that can draw to the window (we're just a list of events, so save the battery)
+        let adapter: wgpu::Adapter = match pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::LowPower,
+            force_fallback_adapter: false,
+            compatible_surface: Some(&surface),
+        })) {
+            Some(adapter) => adapter,
+            None => return Err(Error::AdapterRequest),
+        };
+        let info: wgpu::AdapterInfo = adapter.get_info();
+        debug!("Rendering on graphics adapter '{}' ({:?})", info.name, info.backend);
+        let (device, queue): (wgpu::Device, wgpu::Queue) = match pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("Server Events"),
+                required_features: wgpu::Features::empty(),
+                required_limits: wgpu::Limits::downlevel_webgl2_defaults().using_resolution(adapter.limits()),
+            },
+            None,
+        )) {
+            Ok(res) => res,
+            Err(err) => return Err(Error::DeviceRequest { adapter: info.name, err }),
+        };
+
+        // Configure the surface, preferring a format that egui can blend in directly
+        let size: PhysicalSize<u32> = window.inner_size();
+        let mut config: wgpu::SurfaceConfiguration = match surface.get_default_config(&adapter, size.width.max(1), size.height.max(1)) {
+            Some(config) => config,
+            None => return Err(Error::SurfaceUnsupported { adapter: info.name }),
+        };
+        if let Ok(format) = egui_wgpu::preferred_framebuffer_format(&surface.get_capabilities(&adapter).formats) {
+            config.format = format;
+        }
+        surface.configure(&device, &config);
+
+        // Finally, the egui part
+        let renderer: Renderer = Renderer::new(&device, config.format, None, 1);
+        Ok(Self { surface, device, queue, config, renderer })
+    }
+
+    /// Resizes the surface to match the window it paints on.
+    ///
+    /// # Arguments
+    /// - `size`: The new inner size of the window. Ignored if either dimension is zero (e.g., when minimized).
+    pub fn resize(&mut self, size: PhysicalSize<u32>) {
+        if size.width == 0 || size.height == 0 || (size.width == self.config.width && size.height == self.config.height) {
+            return;
+        }
+        trace!("Resizing render surface to {}x{}", size.width, size.height);
+        self.config.width = size.width;
+        self.config.height = size.height;
+        self.surface.configure(&self.device, &self.config);
+    }
+
+    /// Paints one frame of egui output to the window.
+    ///
+    /// # Arguments
+    /// - `ctx`: The [`egui::Context`] that produced the output, used to tessellate it.
+    /// - `shapes`: The [`ClippedShape`]s to paint.
+    /// - `textures`: The [`TexturesDelta`] with textures to upload before painting and free afterwards.
+    /// - `pixels_per_point`: The scale at which egui laid out the `shapes`.
+    ///
+    /// # Errors
+    /// This function errors if we failed to get a new frame from the surface, even after reconfiguring it.
+    pub fn render(&mut self, ctx: &egui::Context, shapes: Vec<ClippedShape>, textures: TexturesDelta, pixels_per_point: f32) -> Result<(), Error> {
+        // Get something to draw on, reconfiguring if the surface went bad (e.g., because the window was resized behind our back)
+        let frame: wgpu::SurfaceTexture = match self.surface.get_current_texture() {
+            Ok(frame) => frame,
+            Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                debug!("Render surface is lost or outdated; reconfiguring");
+                self.surface.configure(&self.device, &self.config);
+                match self.surface.get_current_texture() {
+                    Ok(frame) => frame,
+                    Err(err) => return Err(Error::SurfaceTextureGet { err }),
+                }
+            },
+            Err(wgpu::SurfaceError::Timeout) => {
+                // Happens now and then (e.g., while the window is hidden); the next frame will do
+                trace!("Timed out getting next frame from render surface; skipping frame");
+                return Ok(());
+            },
+            Err(err) => return Err(Error::SurfaceTextureGet { err }),
+        };
+        let view: wgpu::TextureView = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Upload what egui needs
+        for (id, delta) in &textures.set {
+            self.renderer.update_texture(&self.device, &self.queue, *id, delta);
+        }
+        let jobs: Vec<ClippedPrimitive> = ctx.tessellate(shapes, pixels_per_point);
+        let screen: ScreenDescriptor = ScreenDescriptor { size_in_pixels: [self.config.width, self.config.height], pixels_per_point };
+        let mut encoder: wgpu::CommandEncoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("egui") });
+        let mut commands: Vec<wgpu::CommandBuffer> = self.renderer.update_buffers(&self.device, &self.queue, &mut encoder, &jobs, &screen);
+
+        // Then paint it
+        {
+            let mut pass: wgpu::RenderPass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("egui"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: wgpu::StoreOp::Store },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            self.renderer.render(&mut pass, &jobs, &screen);
+        }
+        commands.push(encoder.finish());
+        self.queue.submit(commands);
+        frame.present();
+
+        // Textures egui no longer uses can go after this frame
+        for id in &textures.free {
+            self.renderer.free_texture(id);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,749 @@
+//  TRAY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 08:17:56
+//  Last edited:
+//    16 Oct 2026, 10:56:58
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the tray icon that lives in the taskbar and gives quick
+//!   access to the most important actions.
+//
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDate, Timelike as _, Utc};
+use enum_debug::EnumDebug;
+use error_trace::ErrorTrace as _;
+use log::{debug, info, warn};
+use parking_lot::RwLock;
+use tray_icon::menu::{CheckMenuItem, Menu, MenuId, MenuItem, PredefinedMenuItem, Submenu};
+use tray_icon::{Icon, TrayIconBuilder};
+
+use crate::app::icons::{IconStatus, Icons};
+use crate::event::Severity;
+use crate::i18n::{Lang, Text};
+use crate::state::{AppState, BuiltinMenuItem, MenuItemSpec, MutableAppState, MuteState, ServerStatus};
+
+
+/***** CONSTANTS *****/
+/// The maximum number of servers that we list in the tooltip before summarizing the rest.
+const TOOLTIP_MAX_SERVERS: usize = 4;
+/// The hour (local time) at which [`MuteDuration::TomorrowMorning`] ends.
+const MUTE_MORNING_HOUR: u32 = 8;
+
+
+
+
+
+/***** STATICS *****/
+thread_local! {
+    /// The [`TrayIcon`] owned by the current thread, if any, such that [`TrayIconHandle`]s can reach it.
+    static TRAY_ICON: RefCell<Option<TrayIcon>> = const { RefCell::new(None) };
+}
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from the [`TrayIcon`].
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to update the icon of the backend tray icon.
+    IconSet { err: tray_icon::Error },
+    /// Failed to render the icon to show.
+    IconRender { err: crate::app::icons::Error },
+    /// Failed to load the icons.
+    IconsLoad { err: crate::app::icons::Error },
+    /// Failed to add an item to the tray icon's menu.
+    MenuAppend { item: TrayIconMenuItem, err: tray_icon::menu::Error },
+    /// Failed to add a separator to the tray icon's menu.
+    SeparatorAppend { err: tray_icon::menu::Error },
+    /// Failed to add a submenu to the tray icon's menu.
+    SubmenuAppend { name: &'static str, err: tray_icon::menu::Error },
+    /// Failed to update the tooltip of the backend tray icon.
+    TooltipSet { err: tray_icon::Error },
+    /// Failed to create the backend tray icon.
+    TrayIconCreate { err: tray_icon::Error },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            IconSet { .. } => write!(f, "Failed to update tray icon image"),
+            IconRender { .. } => write!(f, "Failed to render tray icon image"),
+            IconsLoad { .. } => write!(f, "Failed to load tray icon images"),
+            MenuAppend { item, .. } => write!(f, "Failed to add menu item '{}' to tray icon menu", item.variant()),
+            SeparatorAppend { .. } => write!(f, "Failed to add separator to tray icon menu"),
+            SubmenuAppend { name, .. } => write!(f, "Failed to add submenu '{name}' to tray icon menu"),
+            TooltipSet { .. } => write!(f, "Failed to update tray icon tooltip"),
+            TrayIconCreate { .. } => write!(f, "Failed to create tray icon"),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            IconSet { err } => Some(err),
+            IconRender { err } => Some(err),
+            IconsLoad { err } => Some(err),
+            MenuAppend { err, .. } => Some(err),
+            SeparatorAppend { err } => Some(err),
+            SubmenuAppend { err, .. } => Some(err),
+            TooltipSet { err } => Some(err),
+            TrayIconCreate { err } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Summarizes the state of the servers for in the tray icon's tooltip.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] to summarize.
+///
+/// # Returns
+/// A string like `"1/2 servers OK · prod: 0 · staging: 3"`.
+fn tooltip(state: &AppState) -> String {
+    state.access(|state| {
+        if state.servers.is_empty() {
+            return "server-events client".into();
+        }
+        let mut parts: Vec<String> =
+            state.servers.iter().take(TOOLTIP_MAX_SERVERS).map(|server| format!("{}: {}", server.name, state.unread(&server.name))).collect();
+        if state.servers.len() > TOOLTIP_MAX_SERVERS {
+            parts.push(format!("+{} more", state.servers.len() - TOOLTIP_MAX_SERVERS));
+        }
+        let ok: usize = state.servers.iter().filter(|server| state.status.get(&server.name) == Some(&ServerStatus::Ok)).count();
+        parts.insert(0, format!("{ok}/{} servers OK", state.servers.len()));
+        parts.join(" · ")
+    })
+}
+
+/// Counts the events that the badge on the tray icon shows.
+///
+/// # Arguments
+/// - `state`: The [`MutableAppState`] to count in.
+///
+/// # Returns
+/// The number of unread events.
+#[inline]
+fn unread(state: &MutableAppState) -> usize { state.events.iter().filter(|event| state.is_unread(event)).count() }
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Defines the choices in the tray icon's "Mute for..." submenu.
+#[derive(Clone, Copy, Debug, EnumDebug, Eq, Hash, PartialEq)]
+pub enum MuteDuration {
+    /// Mutes for 15 minutes.
+    Minutes15,
+    /// Mutes for an hour.
+    Hour1,
+    /// Mutes for four hours.
+    Hours4,
+    /// Mutes until [`MUTE_MORNING_HOUR`] the next morning.
+    TomorrowMorning,
+}
+impl MuteDuration {
+    /// All choices, in the order in which they appear in the submenu.
+    pub const ALL: [Self; 4] = [Self::Minutes15, Self::Hour1, Self::Hours4, Self::TomorrowMorning];
+
+    /// Returns a short name for this choice that is fixed, for use in [`MenuId`]s.
+    ///
+    /// # Returns
+    /// A string like `"1h"`.
+    #[inline]
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Minutes15 => "15m",
+            Self::Hour1 => "1h",
+            Self::Hours4 => "4h",
+            Self::TomorrowMorning => "morning",
+        }
+    }
+
+    /// Returns the text that describes this choice in the submenu.
+    ///
+    /// # Returns
+    /// The [`Text`] to translate.
+    #[inline]
+    pub fn text(&self) -> Text {
+        match self {
+            Self::Minutes15 => Text::MenuMuteFor15Min,
+            Self::Hour1 => Text::MenuMuteFor1Hour,
+            Self::Hours4 => Text::MenuMuteFor4Hours,
+            Self::TomorrowMorning => Text::MenuMuteForTomorrow,
+        }
+    }
+
+    /// Computes how long to mute for when this choice is clicked.
+    ///
+    /// Note that [`MuteDuration::TomorrowMorning`] ends later today if it's clicked in the small hours, since that's what people
+    /// mean by it at that time.
+    ///
+    /// # Arguments
+    /// - `now`: The current time.
+    ///
+    /// # Returns
+    /// A [`MuteState::After`] with the computed deadline, or [`MuteState::Manual`] if that deadline does not exist.
+    pub fn mute_state(&self, now: DateTime<Local>) -> MuteState {
+        match self {
+            Self::Minutes15 => MuteState::lasting(now, Duration::from_secs(15 * 60)),
+            Self::Hour1 => MuteState::lasting(now, Duration::from_secs(60 * 60)),
+            Self::Hours4 => MuteState::lasting(now, Duration::from_secs(4 * 60 * 60)),
+            Self::TomorrowMorning => {
+                let today: NaiveDate = now.date_naive();
+                let day: Option<NaiveDate> = if now.time().hour() < MUTE_MORNING_HOUR { Some(today) } else { today.succ_opt() };
+                match day.and_then(|day| day.and_hms_opt(MUTE_MORNING_HOUR, 0, 0)).and_then(|deadline| deadline.and_local_timezone(Local).earliest()) {
+                    Some(deadline) => MuteState::After(deadline.with_timezone(&Utc)),
+                    None => MuteState::Manual,
+                }
+            },
+        }
+    }
+}
+
+
+
+/// Defines the items in the tray icon's menu.
+#[derive(Clone, Copy, Debug, EnumDebug, Eq, Hash, PartialEq)]
+pub enum TrayIconMenuItem {
+    /// Opens (or focuses) the main window.
+    Open,
+    /// Toggles whether notifications are muted.
+    Mute,
+    /// Mutes notifications for the given time.
+    MuteFor(MuteDuration),
+    /// Only notifies about events at least as severe as the given one.
+    MinSeverity(Severity),
+    /// Opens the dashboard of the server with the given index in the browser.
+    Dashboard(usize),
+    /// Polls all servers right away.
+    Refresh,
+    /// Acknowledges the newest unread event, such that it no longer counts towards the badge.
+    AcknowledgeNewest,
+    /// Runs the action of the custom item with the given index in [`MutableAppState::menu`](crate::state::MutableAppState::menu).
+    Custom(usize),
+    /// Closes the app.
+    Exit,
+}
+impl TrayIconMenuItem {
+    /// Returns the [`MenuId`] that identifies this item in the menu.
+    ///
+    /// # Returns
+    /// A [`MenuId`] that is fixed for this item.
+    #[inline]
+    pub fn id(&self) -> MenuId {
+        match self {
+            Self::Open => MenuId::new("open"),
+            Self::Mute => MenuId::new("mute"),
+            Self::MuteFor(duration) => MenuId::new(format!("mute-for-{}", duration.name())),
+            Self::MinSeverity(severity) => MenuId::new(format!("min-severity-{}", severity.variant().to_string().to_lowercase())),
+            Self::Dashboard(i) => MenuId::new(format!("dashboard-{i}")),
+            Self::Refresh => MenuId::new("refresh"),
+            Self::AcknowledgeNewest => MenuId::new("acknowledge-newest"),
+            Self::Custom(i) => MenuId::new(format!("custom-{i}")),
+            Self::Exit => MenuId::new("exit"),
+        }
+    }
+
+    /// Resolves a [`MenuId`] back to the item it identifies.
+    ///
+    /// # Arguments
+    /// - `id`: The [`MenuId`] of a clicked item.
+    ///
+    /// # Returns
+    /// The matching TrayIconMenuItem, or [`None`] if the ID is not one of ours.
+    #[inline]
+    pub fn from_id(id: &MenuId) -> Option<Self> {
+        match id.as_ref() {
+            "open" => Some(Self::Open),
+            "mute" => Some(Self::Mute),
+            "refresh" => Some(Self::Refresh),
+            "acknowledge-newest" => Some(Self::AcknowledgeNewest),
+            "exit" => Some(Self::Exit),
+            id => {
+                if let Some(name) = id.strip_prefix("mute-for-") {
+                    MuteDuration::ALL.into_iter().find(|duration| duration.name() == name).map(Self::MuteFor)
+                } else if let Some(name) = id.strip_prefix("min-severity-") {
+                    Severity::ALL.into_iter().find(|severity| severity.variant().to_string().to_lowercase() == name).map(Self::MinSeverity)
+                } else if let Some(i) = id.strip_prefix("dashboard-") {
+                    i.parse().ok().map(Self::Dashboard)
+                } else {
+                    id.strip_prefix("custom-").and_then(|i| i.parse().ok()).map(Self::Custom)
+                }
+            },
+        }
+    }
+}
+
+
+
+/// The menu of a [`TrayIcon`], together with the items in it that we keep track of.
+struct TrayMenu {
+    /// The menu itself, which is handed to the backend.
+    menu: Menu,
+    /// The items that are actually in the menu, to tell our clicks apart from unknown ones.
+    items: HashSet<TrayIconMenuItem>,
+    /// The choices in the "Notify about" submenu, if it's there.
+    severities: Vec<(Severity, CheckMenuItem)>,
+    /// The "Open" items in the menu.
+    open: Vec<MenuItem>,
+    /// The "Mute" items in the menu.
+    mute: Vec<CheckMenuItem>,
+}
+impl TrayMenu {
+    /// Builds the menu as the user configured it.
+    ///
+    /// # Arguments
+    /// - `state`: The [`AppState`] that determines the menu's contents (see [`MutableAppState::menu`](crate::state::MutableAppState::menu)).
+    ///
+    /// # Returns
+    /// A new TrayMenu, with its check items matching the current state.
+    ///
+    /// # Errors
+    /// This function errors if we failed to add any of the items to the menu.
+    fn build(state: &AppState) -> Result<Self, Error> {
+        debug!("Building tray icon menu...");
+        let (specs, names, lang): (Vec<MenuItemSpec>, Vec<String>, Lang) = state.access(|state| {
+            (
+                if state.menu.is_empty() { MenuItemSpec::defaults() } else { state.menu.clone() },
+                state.servers.iter().map(|server| server.name.clone()).collect(),
+                state.lang(),
+            )
+        });
+        let menu: Menu = Menu::new();
+        let mut items: HashSet<TrayIconMenuItem> = HashSet::new();
+        let mut severities: Vec<(Severity, CheckMenuItem)> = Vec::new();
+        let mut open: Vec<MenuItem> = Vec::new();
+        let mut mute: Vec<CheckMenuItem> = Vec::new();
+        let (min_severity, muted): (Severity, bool) = state.access(|state| (state.min_severity, state.muted.is_muted()));
+        for (i, spec) in specs.into_iter().enumerate() {
+            let (item, text): (TrayIconMenuItem, String) = match spec {
+                MenuItemSpec::Builtin { item: BuiltinMenuItem::Refresh } => (TrayIconMenuItem::Refresh, lang.tr(Text::MenuRefresh).into()),
+                MenuItemSpec::Builtin { item: BuiltinMenuItem::AcknowledgeNewest } => {
+                    (TrayIconMenuItem::AcknowledgeNewest, lang.tr(Text::MenuAcknowledgeNewest).into())
+                },
+                MenuItemSpec::Builtin { item: BuiltinMenuItem::Exit } => (TrayIconMenuItem::Exit, lang.tr(Text::MenuExit).into()),
+                MenuItemSpec::Custom { label, .. } => (TrayIconMenuItem::Custom(i), label),
+
+                // Keep the items that reflect the app's state around, such that they can be updated later
+                MenuItemSpec::Builtin { item: BuiltinMenuItem::Open } => {
+                    let item: TrayIconMenuItem = TrayIconMenuItem::Open;
+                    let entry: MenuItem = MenuItem::with_id(item.id(), lang.tr(Text::MenuOpen), true, None);
+                    if let Err(err) = menu.append(&entry) {
+                        return Err(Error::MenuAppend { item, err });
+                    }
+                    items.insert(item);
+                    open.push(entry);
+                    continue;
+                },
+                MenuItemSpec::Builtin { item: BuiltinMenuItem::Mute } => {
+                    let item: TrayIconMenuItem = TrayIconMenuItem::Mute;
+                    let check: CheckMenuItem = CheckMenuItem::with_id(item.id(), lang.tr(Text::MenuMute), true, muted, None);
+                    if let Err(err) = menu.append(&check) {
+                        return Err(Error::MenuAppend { item, err });
+                    }
+                    items.insert(item);
+                    mute.push(check);
+                    continue;
+                },
+
+                // Add a submenu with the durations to mute for
+                MenuItemSpec::Builtin { item: BuiltinMenuItem::MuteFor } => {
+                    let mute_for: Submenu = Submenu::new(lang.tr(Text::MenuMuteFor), true);
+                    for duration in MuteDuration::ALL {
+                        let item: TrayIconMenuItem = TrayIconMenuItem::MuteFor(duration);
+                        if let Err(err) = mute_for.append(&MenuItem::with_id(item.id(), lang.tr(duration.text()), true, None)) {
+                            return Err(Error::MenuAppend { item, err });
+                        }
+                        items.insert(item);
+                    }
+                    if let Err(err) = menu.append(&mute_for) {
+                        return Err(Error::SubmenuAppend { name: lang.tr(Text::MenuMuteFor), err });
+                    }
+                    continue;
+                },
+
+                // Add a submenu with the severities to notify about, checking the current one
+                MenuItemSpec::Builtin { item: BuiltinMenuItem::MinSeverity } => {
+                    let submenu: Submenu = Submenu::new(lang.tr(Text::MenuMinSeverity), true);
+                    for severity in Severity::ALL {
+                        let item: TrayIconMenuItem = TrayIconMenuItem::MinSeverity(severity);
+                        let check: CheckMenuItem =
+                            CheckMenuItem::with_id(item.id(), lang.tr(Text::min_severity(severity)), true, severity == min_severity, None);
+                        if let Err(err) = submenu.append(&check) {
+                            return Err(Error::MenuAppend { item, err });
+                        }
+                        items.insert(item);
+                        severities.push((severity, check));
+                    }
+                    if let Err(err) = menu.append(&submenu) {
+                        return Err(Error::SubmenuAppend { name: lang.tr(Text::MenuMinSeverity), err });
+                    }
+                    continue;
+                },
+
+                // Add a submenu for opening server dashboards, if there are any servers
+                MenuItemSpec::Builtin { item: BuiltinMenuItem::Dashboards } => {
+                    if names.is_empty() {
+                        continue;
+                    }
+                    let dashboards: Submenu = Submenu::new(lang.tr(Text::MenuDashboards), true);
+                    for (i, name) in names.iter().enumerate() {
+                        let item: TrayIconMenuItem = TrayIconMenuItem::Dashboard(i);
+                        if let Err(err) = dashboards.append(&MenuItem::with_id(item.id(), name, true, None)) {
+                            return Err(Error::MenuAppend { item, err });
+                        }
+                        items.insert(item);
+                    }
+                    if let Err(err) = menu.append(&dashboards) {
+                        return Err(Error::SubmenuAppend { name: lang.tr(Text::MenuDashboards), err });
+                    }
+                    continue;
+                },
+                MenuItemSpec::Separator => {
+                    if let Err(err) = menu.append(&PredefinedMenuItem::separator()) {
+                        return Err(Error::SeparatorAppend { err });
+                    }
+                    continue;
+                },
+            };
+            if let Err(err) = menu.append(&MenuItem::with_id(item.id(), text, true, None)) {
+                return Err(Error::MenuAppend { item, err });
+            }
+            items.insert(item);
+        }
+        Ok(Self { menu, items, severities, open, mute })
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Implements the tray icon that lives in the taskbar.
+///
+/// Note that, on Linux, this must be created and kept on a thread that runs the GTK event loop.
+pub struct TrayIcon {
+    /// The backend tray icon that we wrap.
+    tray_icon: tray_icon::TrayIcon,
+    /// The decoded icons that we swap between.
+    icons: Icons,
+    /// The status and the badge count that the current icon shows.
+    shown: Cell<(IconStatus, usize)>,
+    /// The items that are actually in the menu, to tell our clicks apart from unknown ones. Shared with the [`TrayIconHandle`].
+    items: Arc<RwLock<HashSet<TrayIconMenuItem>>>,
+    /// The choices in the "Notify about" submenu, if it's there, such that the current one can be checked.
+    severities: Vec<(Severity, CheckMenuItem)>,
+    /// The "Open" items in the menu, such that they can be disabled while the window has focus.
+    open: Vec<MenuItem>,
+    /// The "Mute" items in the menu, such that they can be checked while muted.
+    mute: Vec<CheckMenuItem>,
+}
+impl TrayIcon {
+    /// Constructor for the TrayIcon.
+    ///
+    /// # Arguments
+    /// - `state`: The [`AppState`] that determines the menu's contents (see [`MutableAppState::menu`](crate::state::MutableAppState::menu)).
+    ///
+    /// # Returns
+    /// A new TrayIcon that is immediately visible in the taskbar.
+    ///
+    /// # Errors
+    /// This function errors if we failed to load the icon or create the backend tray icon.
+    pub fn new(state: &AppState) -> Result<Self, Error> {
+        info!("Initializing TrayIcon...");
+
+        // Load the icons, which the user may have replaced
+        let icons: Icons = match Icons::load(state.config_dir()) {
+            Ok(icons) => icons,
+            Err(err) => return Err(Error::IconsLoad { err }),
+        };
+        let (status, unread): (IconStatus, usize) = state.access(|state| (IconStatus::of(state), unread(state)));
+        let icon: Icon = match icons.render(status, unread) {
+            Ok(icon) => icon,
+            Err(err) => return Err(Error::IconRender { err }),
+        };
+
+        // Build the menu as the user configured it
+        let TrayMenu { menu, items, severities, open, mute } = TrayMenu::build(state)?;
+
+        // Build the tray icon itself
+        debug!("Building backend tray icon...");
+        let builder: TrayIconBuilder = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            // Left-clicks open the window (or popover) instead; see `handle_app_event()`
+            .with_menu_on_left_click(false)
+            .with_tooltip(tooltip(state))
+            .with_icon(icon);
+        let tray_icon: tray_icon::TrayIcon = match builder.build() {
+            Ok(tray_icon) => tray_icon,
+            Err(err) => return Err(Error::TrayIconCreate { err }),
+        };
+
+        // Done, build self
+        Ok(Self { tray_icon, icons, shown: Cell::new((status, unread)), items: Arc::new(RwLock::new(items)), severities, open, mute })
+    }
+
+    /// Finds out which item in the menu has been clicked.
+    ///
+    /// # Arguments
+    /// - `id`: The [`MenuId`] of the clicked item.
+    ///
+    /// # Returns
+    /// The clicked [`TrayIconMenuItem`], or [`None`] if the ID is not one of the items in this menu.
+    #[inline]
+    pub fn classify(&self, id: &MenuId) -> Option<TrayIconMenuItem> { TrayIconMenuItem::from_id(id).filter(|item| self.items.read().contains(item)) }
+
+    /// Rebuilds the menu, such that it reflects changes to the settings (e.g., the servers listed under "Open dashboard").
+    ///
+    /// # Arguments
+    /// - `state`: The [`AppState`] that determines the menu's contents (see [`MutableAppState::menu`](crate::state::MutableAppState::menu)).
+    ///
+    /// # Errors
+    /// This function errors if we failed to build the new menu, in which case the old one is kept.
+    pub fn rebuild_menu(&mut self, state: &AppState) -> Result<(), Error> {
+        let TrayMenu { menu, items, severities, open, mute } = TrayMenu::build(state)?;
+        let enabled: bool = self.open.iter().all(MenuItem::is_enabled);
+        self.tray_icon.set_menu(Some(Box::new(menu)));
+        *self.items.write() = items;
+        self.severities = severities;
+        self.open = open;
+        self.mute = mute;
+        // The window may still have focus
+        self.set_open_enabled(enabled);
+        Ok(())
+    }
+
+    /// Rebuilds the tooltip and swaps the icon to reflect the current state.
+    ///
+    /// # Arguments
+    /// - `state`: The [`AppState`] to summarize in the tooltip and icon.
+    ///
+    /// # Errors
+    /// This function errors if the backend failed to update the tooltip or the icon.
+    pub fn refresh(&self, state: &AppState) -> Result<(), Error> {
+        self.set_tooltip(&tooltip(state))?;
+
+        // Clicking a check item toggles it, so always put the check back where it belongs
+        let (min_severity, muted): (Severity, bool) = state.access(|state| (state.min_severity, state.muted.is_muted()));
+        for (severity, check) in &self.severities {
+            check.set_checked(*severity == min_severity);
+        }
+        self.set_muted_checked(muted);
+
+        // Only bother the backend if the icon actually changes
+        let (status, unread): (IconStatus, usize) = state.access(|state| (IconStatus::of(state), unread(state)));
+        if status != self.shown.get().0 {
+            debug!("Changing tray icon from {} to {}", self.shown.get().0.variant(), status.variant());
+            return self.show(status, unread);
+        }
+        self.set_badge(unread)
+    }
+
+    /// Enables or disables the "Open" items in the menu.
+    ///
+    /// # Arguments
+    /// - `enabled`: Whether the items can be clicked, which makes little sense while the window is open and focused.
+    #[inline]
+    pub fn set_open_enabled(&self, enabled: bool) {
+        for item in &self.open {
+            item.set_enabled(enabled);
+        }
+    }
+
+    /// Checks or unchecks the "Mute" items in the menu.
+    ///
+    /// # Arguments
+    /// - `checked`: Whether to show a checkmark, which should match whether notifications are muted.
+    #[inline]
+    pub fn set_muted_checked(&self, checked: bool) {
+        for check in &self.mute {
+            check.set_checked(checked);
+        }
+    }
+
+    /// Changes the text shown when hovering over the icon.
+    ///
+    /// # Arguments
+    /// - `text`: The new tooltip.
+    ///
+    /// # Errors
+    /// This function errors if the backend failed to update the tooltip.
+    pub fn set_tooltip(&self, text: &str) -> Result<(), Error> {
+        match self.tray_icon.set_tooltip(Some(text)) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Error::TooltipSet { err }),
+        }
+    }
+
+    /// Shows the given count in the corner of the icon.
+    ///
+    /// # Arguments
+    /// - `count`: The count to show, which is typically the number of unread events. Anything above 99 is shown as `99+`, and `0`
+    ///   removes the badge.
+    ///
+    /// # Errors
+    /// This function errors if we failed to render the new icon or if the backend failed to show it.
+    pub fn set_badge(&self, count: usize) -> Result<(), Error> {
+        let (status, shown): (IconStatus, usize) = self.shown.get();
+        if count == shown {
+            return Ok(());
+        }
+        self.show(status, count)
+    }
+
+    /// Renders the icon for the given status and badge, and hands it to the backend.
+    ///
+    /// # Arguments
+    /// - `status`: The [`IconStatus`] to show.
+    /// - `badge`: The count to show in the corner of the icon, or `0` to show none.
+    ///
+    /// # Errors
+    /// This function errors if we failed to render the new icon or if the backend failed to show it.
+    fn show(&self, status: IconStatus, badge: usize) -> Result<(), Error> {
+        let icon: Icon = match self.icons.render(status, badge) {
+            Ok(icon) => icon,
+            Err(err) => return Err(Error::IconRender { err }),
+        };
+        if let Err(err) = self.tray_icon.set_icon(Some(icon)) {
+            return Err(Error::IconSet { err });
+        }
+        self.shown.set((status, badge));
+        Ok(())
+    }
+}
+
+
+
+/// A handle to the [`TrayIcon`] that can be used from the main thread, even if the icon itself lives on another one.
+///
+/// On Linux, calls are forwarded to the GTK thread that owns the icon. Elsewhere, the icon lives on the main thread anyway.
+#[derive(Clone, Debug)]
+pub struct TrayIconHandle {
+    /// The main context of the GTK thread that owns the icon.
+    #[cfg(target_os = "linux")]
+    context: gtk::glib::MainContext,
+    /// The items in the icon's menu, such that clicks can be classified without a trip to the GTK thread.
+    items:   Arc<RwLock<HashSet<TrayIconMenuItem>>>,
+}
+impl TrayIconHandle {
+    /// Hands the given [`TrayIcon`] to the current thread, and returns a handle to reach it.
+    ///
+    /// This must be called on the thread that owns the icon (i.e., the GTK thread on Linux).
+    ///
+    /// # Arguments
+    /// - `tray_icon`: The [`TrayIcon`] to install.
+    ///
+    /// # Returns
+    /// A new TrayIconHandle that can be sent to the main thread.
+    pub fn install(tray_icon: TrayIcon) -> Self {
+        let items: Arc<RwLock<HashSet<TrayIconMenuItem>>> = tray_icon.items.clone();
+        TRAY_ICON.with(|cell| *cell.borrow_mut() = Some(tray_icon));
+        Self {
+            #[cfg(target_os = "linux")]
+            context: gtk::glib::MainContext::default(),
+            items,
+        }
+    }
+
+    /// Finds out which item in the [`TrayIcon`]'s menu has been clicked.
+    ///
+    /// Unlike most other calls, this one is answered right away, also on Linux.
+    ///
+    /// # Arguments
+    /// - `id`: The [`MenuId`] of the clicked item.
+    ///
+    /// # Returns
+    /// The clicked [`TrayIconMenuItem`], or [`None`] if the ID is not one of the items in the menu.
+    #[inline]
+    pub fn classify(&self, id: &MenuId) -> Option<TrayIconMenuItem> { TrayIconMenuItem::from_id(id).filter(|item| self.items.read().contains(item)) }
+
+    /// Runs the given closure on the installed [`TrayIcon`].
+    ///
+    /// On Linux, this happens asynchronously on the GTK thread.
+    ///
+    /// # Arguments
+    /// - `access_fn`: Some closure that does something with the [`TrayIcon`].
+    #[inline]
+    pub fn with(&self, access_fn: impl 'static + Send + FnOnce(&TrayIcon)) { self.with_mut(move |tray_icon| access_fn(tray_icon)) }
+
+    /// Runs the given closure on the installed [`TrayIcon`], with mutable access to it.
+    ///
+    /// On Linux, this happens asynchronously on the GTK thread.
+    ///
+    /// # Arguments
+    /// - `access_fn`: Some closure that does something with the [`TrayIcon`].
+    fn with_mut(&self, access_fn: impl 'static + Send + FnOnce(&mut TrayIcon)) {
+        let run = move || {
+            TRAY_ICON.with(|cell| {
+                if let Some(tray_icon) = &mut *cell.borrow_mut() {
+                    access_fn(tray_icon);
+                }
+            })
+        };
+        #[cfg(target_os = "linux")]
+        self.context.invoke(run);
+        #[cfg(not(target_os = "linux"))]
+        run();
+    }
+
+    /// Rebuilds the [`TrayIcon`]'s menu to reflect changes to the settings (see [`TrayIcon::rebuild_menu()`]).
+    ///
+    /// # Arguments
+    /// - `state`: The [`AppState`] that determines the menu's contents.
+    pub fn rebuild_menu(&self, state: &AppState) {
+        let state: AppState = state.clone();
+        self.with_mut(move |tray_icon| {
+            if let Err(err) = tray_icon.rebuild_menu(&state) {
+                warn!("{}", err.trace());
+            }
+        });
+    }
+
+    /// Enables or disables the "Open" items in the [`TrayIcon`]'s menu (see [`TrayIcon::set_open_enabled()`]).
+    ///
+    /// # Arguments
+    /// - `enabled`: Whether the items can be clicked.
+    #[inline]
+    pub fn set_open_enabled(&self, enabled: bool) { self.with(move |tray_icon| tray_icon.set_open_enabled(enabled)); }
+
+    /// Checks or unchecks the "Mute" items in the [`TrayIcon`]'s menu (see [`TrayIcon::set_muted_checked()`]).
+    ///
+    /// # Arguments
+    /// - `checked`: Whether to show a checkmark.
+    #[inline]
+    pub fn set_muted_checked(&self, checked: bool) { self.with(move |tray_icon| tray_icon.set_muted_checked(checked)); }
+
+    /// Rebuilds the tooltip and icon of the [`TrayIcon`] to reflect the current state.
+    ///
+    /// # Arguments
+    /// - `state`: The [`AppState`] to summarize in the tooltip and icon.
+    pub fn refresh(&self, state: &AppState) {
+        let state: AppState = state.clone();
+        self.with(move |tray_icon| {
+            if let Err(err) = tray_icon.refresh(&state) {
+                warn!("{}", err.trace());
+            }
+        });
+    }
+}
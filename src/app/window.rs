@@ -4,7 +4,7 @@
 //  Created:
 //    02 Apr 2024, 15:14:28
 //  Last edited:
-//    01 May 2024, 19:52:01
+//    16 Oct 2026, 12:01:51
 //  Auto updated?
 //    Yes
 //
@@ -12,18 +12,32 @@
 //!   Implements code for handling our [`Window`]-abstraction.
 //
 
+use std::convert::Infallible;
 use std::error;
 use std::fmt::{Display, Formatter, Result as FResult};
+use std::sync::Arc;
+use std::time::Instant;
 
-use egui::ViewportId;
+use egui::{RequestRepaintInfo, ViewportId};
 use egui_winit::winit::event::WindowEvent;
 use egui_winit::{egui, winit, EventResponse};
-use log::{debug, info, trace};
-use tray_icon::menu::MenuEvent;
-use winit::event_loop::EventLoopWindowTarget;
-use winit::window::{WindowBuilder, WindowId};
+use enum_debug::EnumDebug as _;
+use error_trace::ErrorTrace as _;
+use log::{debug, info, trace, warn};
+use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
+use winit::window::{WindowBuilder, WindowId, WindowLevel};
 
 use super::gui::Gui;
+use super::pipeline::{self, Pipeline};
+use super::AppEventLoopTarget;
+use crate::state::{AppState, GuiLayout};
+
+
+/***** CONSTANTS *****/
+/// The (fixed) size of the window when in [`GuiLayout::Mini`].
+const MINI_SIZE: LogicalSize<f64> = LogicalSize::new(320.0, 200.0);
+/// The (fixed) size of the popover near the tray icon.
+const POPOVER_SIZE: PhysicalSize<u32> = PhysicalSize::new(320, 200);
 
 
 /***** ERRORS *****/
@@ -32,12 +46,15 @@ use super::gui::Gui;
 pub enum Error {
     /// Failed to create a new [`winit::Window`](winit::window::Window).
     WindowCreate { title: String, err: winit::error::OsError },
+    /// Failed to create the [`Pipeline`] that renders to the window.
+    PipelineCreate { title: String, err: pipeline::Error },
 }
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
         use Error::*;
         match self {
             WindowCreate { title, .. } => write!(f, "Failed to create new Window with title '{title}'"),
+            PipelineCreate { title, .. } => write!(f, "Failed to create render pipeline for Window with title '{title}'"),
         }
     }
 }
@@ -46,6 +63,7 @@ impl error::Error for Error {
         use Error::*;
         match self {
             WindowCreate { err, .. } => Some(err),
+            PipelineCreate { err, .. } => Some(err),
         }
     }
 }
@@ -54,68 +72,282 @@ impl error::Error for Error {
 
 
 
+/***** AUXILLARY *****/
+/// The size and position of the main window as remembered in the [`AppState`], if at all.
+type SavedGeometry = (Option<(u32, u32)>, Option<(i32, i32)>);
+
+/// Tells the caller of [`Window::handle_event()`] what should happen to the window next.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WindowResponse {
+    /// Nothing has to happen (e.g., the event was irrelevant or the window was just painted).
+    Nothing,
+    /// The window wants to be repainted.
+    Repaint,
+    /// The window changed size or scale. Its surface is already resized, but it needs to be repainted.
+    Resized,
+    /// The window gained (`true`) or lost (`false`) focus. It needs to be repainted as well.
+    Focused(bool),
+    /// The user asked to close the window.
+    Close,
+}
+
+
+
+
+
 /***** LIBRARY *****/
 /// Implements an abstraction of a Window over some backend.
 ///
 /// Currently, only [`winit`] (through [`egui_winit`]) is supported.
 pub struct Window {
-    /// The wrapper [`eframe::Window`] we actually render on.
-    window: winit::window::Window,
+    /// The wrapper [`eframe::Window`] we actually render on. Shared with egui, such that it can ask for repaints from other threads.
+    window: Arc<winit::window::Window>,
     /// Some [`egui_winit::State`] that we can use to wrap the window.
     egui_state: egui_winit::State,
+    /// The [`Pipeline`] that paints egui's output on the `window`.
+    pipeline: Pipeline,
     /// The [`Gui`] that we will draw in this window.
     gui: Gui,
+    /// The layout that is currently applied to the backend window.
+    layout: GuiLayout,
+    /// The size and position of the window before it went into [`GuiLayout::Mini`], if it did.
+    full_geometry: Option<(PhysicalSize<u32>, Option<PhysicalPosition<i32>>)>,
+    /// The size the user gave the window that is not yet written to the [`AppState`], if any.
+    unsaved_size: Option<PhysicalSize<u32>>,
+    /// The position the user gave the window that is not yet written to the [`AppState`], if any.
+    unsaved_pos: Option<PhysicalPosition<i32>>,
+    /// When egui wants to be repainted next without any other reason to, if ever.
+    repaint_at: Option<Instant>,
 }
 impl Window {
     /// Constructor for the Window.
     ///
     /// # Arguments
-    /// - `event_loop`: Some [`EventLoop`] that we use to listen to this window's events.
+    /// - `event_loop`: The [`AppEventLoopTarget`] that we use to listen to this window's events.
+    /// - `state`: The [`AppState`] that the window visualizes.
     /// - `title`: The (initial) title of the window.
     ///
     /// # Returns
     /// A new Window.
     ///
     /// # Errors
-    /// This function errors if it fails to build a new [`winit::Window`](winit::window::Window).
-    pub fn new(event_loop: &EventLoopWindowTarget<MenuEvent>, title: impl AsRef<str>) -> Result<Self, Error> {
+    /// This function errors if it fails to build a new [`winit::Window`](winit::window::Window) or the [`Pipeline`] to render to it.
+    pub fn new(event_loop: &AppEventLoopTarget, state: &AppState, title: impl AsRef<str>) -> Result<Self, Error> {
         let title: &str = title.as_ref();
         info!("Initializing Window '{title}'...");
 
+        // Restore where the user left the window last time (negative positions are fine, e.g., on a monitor left of the primary one)
+        let mut builder: WindowBuilder = WindowBuilder::new().with_title(title);
+        let (size, pos): SavedGeometry = state.access(|state| (state.width.zip(state.height), state.x.zip(state.y)));
+        if let Some((width, height)) = size {
+            builder = builder.with_inner_size(PhysicalSize::new(width, height));
+        }
+        if let Some((x, y)) = pos {
+            builder = builder.with_position(PhysicalPosition::new(x, y));
+        }
+        Self::build(event_loop, builder, title, Gui::new(state.clone()))
+    }
+
+    /// Constructor for a Window that acts as a small popover near the tray icon.
+    ///
+    /// It is borderless, always on top and positioned near the given point.
+    ///
+    /// # Arguments
+    /// - `event_loop`: The [`AppEventLoopTarget`] that we use to listen to this window's events.
+    /// - `state`: The [`AppState`] that the window visualizes.
+    /// - `anchor`: The (physical) point near which to show the popover, e.g., where the tray icon was clicked.
+    ///
+    /// # Returns
+    /// A new Window.
+    ///
+    /// # Errors
+    /// This function errors if it fails to build a new [`winit::Window`](winit::window::Window) or the [`Pipeline`] to render to it.
+    pub fn popover(event_loop: &AppEventLoopTarget, state: &AppState, anchor: PhysicalPosition<f64>) -> Result<Self, Error> {
+        info!("Initializing popover Window at ({}, {})...", anchor.x, anchor.y);
+
+        // Open towards the middle of the screen, such that we don't fall off (the taskbar may be at any edge)
+        let screen: PhysicalSize<u32> = event_loop.primary_monitor().map(|monitor| monitor.size()).unwrap_or(PhysicalSize::new(u32::MAX, u32::MAX));
+        let (width, height): (f64, f64) = (POPOVER_SIZE.width as f64, POPOVER_SIZE.height as f64);
+        let x: f64 = if anchor.x > screen.width as f64 / 2.0 { anchor.x - width } else { anchor.x };
+        let y: f64 = if anchor.y > screen.height as f64 / 2.0 { anchor.y - height } else { anchor.y };
+
+        let builder: WindowBuilder = WindowBuilder::new()
+            .with_title("Server Events")
+            .with_decorations(false)
+            .with_resizable(false)
+            .with_window_level(WindowLevel::AlwaysOnTop)
+            .with_inner_size(POPOVER_SIZE)
+            .with_position(PhysicalPosition::new(x.max(0.0), y.max(0.0)));
+        let this: Self = Self::build(event_loop, builder, "Server Events", Gui::popover(state.clone()))?;
+        this.window.focus_window();
+        Ok(this)
+    }
+
+    /// Builds a new Window from the given backend window settings.
+    ///
+    /// # Arguments
+    /// - `event_loop`: The [`AppEventLoopTarget`] that we use to listen to this window's events.
+    /// - `builder`: The [`WindowBuilder`] that describes the backend window.
+    /// - `title`: The title of the window, for in errors.
+    /// - `gui`: The [`Gui`] to draw in the window.
+    ///
+    /// # Returns
+    /// A new Window.
+    ///
+    /// # Errors
+    /// This function errors if it fails to build a new [`winit::Window`](winit::window::Window) or the [`Pipeline`] to render to it.
+    fn build(event_loop: &AppEventLoopTarget, builder: WindowBuilder, title: &str, gui: Gui) -> Result<Self, Error> {
         // Build the eframe window
         debug!("Building backend window...");
-        let window: winit::window::Window = match WindowBuilder::new().with_title(title).build(event_loop) {
-            Ok(win) => win,
+        let window: Arc<winit::window::Window> = match builder.build(event_loop) {
+            Ok(win) => Arc::new(win),
             Err(err) => return Err(Error::WindowCreate { title: title.into(), err }),
         };
 
         // Create an egui context and then an egui-winit state
         debug!("Building egui state...");
         let egui_ctx: egui::Context = egui::Context::default();
+        let repaint_window: Arc<winit::window::Window> = window.clone();
+        egui_ctx.set_request_repaint_callback(move |info: RequestRepaintInfo| {
+            // Delayed repaints are scheduled by the event loop (see `Window::repaint_at()`)
+            if info.delay.is_zero() {
+                repaint_window.request_redraw();
+            }
+        });
         let egui_state: egui_winit::State = egui_winit::State::new(egui_ctx, ViewportId::ROOT, &window, None, None);
 
-        // Build the context
-        debug!("Building GUI...");
-        let gui: Gui = Gui {};
+        // Then the wgpu side of things
+        let pipeline: Pipeline = match Pipeline::new(&window) {
+            Ok(pipeline) => pipeline,
+            Err(err) => return Err(Error::PipelineCreate { title: title.into(), err }),
+        };
 
-        // Done, build self
-        Ok(Self { window, egui_state, gui })
+        // Done, build self (applying the last-used layout)
+        let mut this: Self =
+            Self { window, egui_state, pipeline, gui, layout: GuiLayout::Full, full_geometry: None, unsaved_size: None, unsaved_pos: None, repaint_at: None };
+        if !this.gui.is_popover() {
+            this.apply_layout(this.gui.layout());
+        }
+        Ok(this)
+    }
+
+    /// Updates the backend window to match the given layout.
+    ///
+    /// # Arguments
+    /// - `layout`: The [`GuiLayout`] to apply.
+    fn apply_layout(&mut self, layout: GuiLayout) {
+        if layout == self.layout {
+            return;
+        }
+        debug!("Applying layout '{}' to Window", layout.variant());
+        match layout {
+            GuiLayout::Full => {
+                self.window.set_window_level(WindowLevel::Normal);
+                self.window.set_resizable(true);
+                if let Some((size, pos)) = self.full_geometry.take() {
+                    let _ = self.window.request_inner_size(size);
+                    if let Some(pos) = pos {
+                        self.window.set_outer_position(pos);
+                    }
+                }
+            },
+            GuiLayout::Mini => {
+                self.full_geometry = Some((self.window.inner_size(), self.window.outer_position().ok()));
+                self.window.set_window_level(WindowLevel::AlwaysOnTop);
+                let _ = self.window.request_inner_size(MINI_SIZE);
+                self.window.set_resizable(false);
+            },
+        }
+        self.layout = layout;
+    }
+
+    /// Notes down the size and position of the main window, such that [`Window::save_geometry()`] can remember them later.
+    ///
+    /// Nothing is noted for the popover or in [`GuiLayout::Mini`], as those have a fixed size.
+    ///
+    /// # Arguments
+    /// - `size`: The new inner size of the window, if it changed.
+    /// - `pos`: The new outer position of the window, if it changed.
+    fn track_geometry(&mut self, size: Option<PhysicalSize<u32>>, pos: Option<PhysicalPosition<i32>>) {
+        if self.gui.is_popover() || self.layout != GuiLayout::Full {
+            return;
+        }
+        self.unsaved_size = size.or(self.unsaved_size);
+        self.unsaved_pos = pos.or(self.unsaved_pos);
+    }
+
+    /// Remembers the size and position of the main window, such that it can be restored next time.
+    ///
+    /// Dragging or resizing the window fires a flood of events, so this only happens once the user is done with it (i.e., when the
+    /// window loses focus or is closed) instead of on every one of them.
+    fn save_geometry(&mut self) {
+        let size: Option<(Option<u32>, Option<u32>)> = self.unsaved_size.take().map(|size| (Some(size.width), Some(size.height)));
+        let pos: Option<(Option<i32>, Option<i32>)> = self.unsaved_pos.take().map(|pos| (Some(pos.x), Some(pos.y)));
+        if size.is_none() && pos.is_none() {
+            return;
+        }
+
+        // Don't bother the disk if nothing changed
+        let state: &AppState = self.gui.state();
+        if state.access(|state| size.is_none_or(|size| size == (state.width, state.height)) && pos.is_none_or(|pos| pos == (state.x, state.y))) {
+            return;
+        }
+        if let Ok(Err(err)) = state.access_mut(|state| -> Result<(), Infallible> {
+            if let Some((width, height)) = size {
+                (state.width, state.height) = (width, height);
+            }
+            if let Some((x, y)) = pos {
+                (state.x, state.y) = (x, y);
+            }
+            state.mark_dirty();
+            Ok(())
+        }) {
+            warn!("{}", err.trace());
+        }
     }
 
     /// Handles [`WindowEvent`]s with the builtin egui state.
     ///
+    /// The event is handed to egui, after which the caller is told what else should happen to
+    /// the window. Note that [`WindowEvent::RedrawRequested`] is not handled here, but by calling
+    /// [`Window::render()`].
+    ///
     /// # Arguments
     /// - `event`: The [`WindowEvent`] to handle.
     ///
     /// # Returns
-    /// The same WindowEvent if egui is OK with it. Else, egui consumed it.
-    #[inline]
-    pub fn handle_event(&mut self, event: WindowEvent) -> Option<WindowEvent> {
+    /// A [`WindowResponse`] that describes whether the window should be repainted or closed.
+    pub fn handle_event(&mut self, event: WindowEvent) -> WindowResponse {
+        // Dropped files are config files to import
+        if let WindowEvent::DroppedFile(path) = &event {
+            debug!("File '{}' dropped onto Window", path.display());
+            self.gui.import_config(path);
+        }
+
+        // Remember where the user puts the window
+        match &event {
+            WindowEvent::Resized(size) => {
+                self.pipeline.resize(*size);
+                self.track_geometry(Some(*size), None);
+            },
+            WindowEvent::Moved(pos) => self.track_geometry(None, Some(*pos)),
+            WindowEvent::Focused(false) | WindowEvent::CloseRequested => self.save_geometry(),
+            _ => {},
+        }
+
+        // egui picks up the new scale factor itself; we only have to match the surface to the new size
         let response: EventResponse = self.egui_state.on_window_event(&self.window, &event);
-        if response.repaint {
-            self.render();
+        match event {
+            WindowEvent::CloseRequested => WindowResponse::Close,
+            WindowEvent::Focused(focused) => WindowResponse::Focused(focused),
+            WindowEvent::Resized(_) => WindowResponse::Resized,
+            WindowEvent::ScaleFactorChanged { .. } => {
+                self.pipeline.resize(self.window.inner_size());
+                WindowResponse::Resized
+            },
+            _ if response.repaint => WindowResponse::Repaint,
+            _ => WindowResponse::Nothing,
         }
-        if !response.consumed { Some(event) } else { None }
     }
 
     /// Renders the given egui application to the screen.
@@ -125,6 +357,53 @@ impl Window {
         let raw_input = self.egui_state.take_egui_input(&self.window);
         let output = self.egui_state.egui_ctx().run(raw_input, |egui_ctx| self.gui.ui(egui_ctx));
         self.egui_state.handle_platform_output(&self.window, output.platform_output);
+        if let Err(err) = self.pipeline.render(self.egui_state.egui_ctx(), output.shapes, output.textures_delta, output.pixels_per_point) {
+            warn!("{}", err.trace());
+        }
+        self.repaint_at = output.viewport_output.get(&ViewportId::ROOT).and_then(|output| Instant::now().checked_add(output.repaint_delay));
+
+        // Apply any layout changes the user made
+        if !self.gui.is_popover() {
+            self.apply_layout(self.gui.layout());
+        }
+    }
+
+    /// Schedules a repaint of this window through the event loop.
+    ///
+    /// Useful when the state it visualizes has changed without the user interacting with it (e.g., new events arrived).
+    #[inline]
+    pub fn request_redraw(&self) { self.window.request_redraw(); }
+
+    /// Returns when this window wants to be repainted next, even if nothing happens in the meantime.
+    ///
+    /// # Returns
+    /// The [`Instant`] at which to call [`Window::request_redraw()`], or [`None`] if it only needs to be repainted when something happens.
+    #[inline]
+    pub fn repaint_at(&self) -> Option<Instant> { self.repaint_at }
+
+    /// Schedules a repaint of this window if the moment egui asked for has passed.
+    ///
+    /// The moment is forgotten until the next paint, such that a window that doesn't get
+    /// painted (e.g., because it's minimized) doesn't keep the event loop awake.
+    ///
+    /// # Arguments
+    /// - `now`: The current time.
+    #[inline]
+    pub fn request_redraw_if_due(&mut self, now: Instant) {
+        if self.repaint_at.is_some_and(|at| at <= now) {
+            self.repaint_at = None;
+            self.request_redraw();
+        }
+    }
+
+    /// Scrolls the event list to the given event and shows its details.
+    ///
+    /// # Arguments
+    /// - `key`: The [key](crate::event::Event::key()) of the event to show.
+    #[inline]
+    pub fn reveal(&mut self, key: String) {
+        self.gui.reveal(key);
+        self.request_redraw();
     }
 
     /// Makes this already existing window active.
@@ -142,3 +421,9 @@ impl Window {
     #[inline]
     pub fn inner(&self) -> &winit::window::Window { &self.window }
 }
+impl Drop for Window {
+    fn drop(&mut self) {
+        // Quitting from the tray doesn't close or unfocus the window first
+        self.save_geometry();
+    }
+}
@@ -0,0 +1,80 @@
+//  CLOCK.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 08:19:13
+//  Last edited:
+//    16 Oct 2026, 08:19:13
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines an abstraction over the current time, such that time-based
+//!   logic can be driven by something else than the system clock.
+//
+
+use std::fmt::Debug;
+
+use chrono::{DateTime, Duration, Local};
+use parking_lot::Mutex;
+
+
+/***** LIBRARY *****/
+/// Abstracts over some source of the current time.
+pub trait Clock: Debug + Send + Sync {
+    /// Returns the current time according to this clock.
+    ///
+    /// # Returns
+    /// A [`DateTime`] describing what this clock thinks "now" is.
+    fn now(&self) -> DateTime<Local>;
+}
+
+
+
+/// A [`Clock`] that simply returns the system time.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> DateTime<Local> { Local::now() }
+}
+
+
+
+/// A [`Clock`] that only moves when it is told to.
+///
+/// Useful to deterministically test time-based logic.
+#[derive(Debug)]
+pub struct MockClock {
+    /// The time that this clock currently reports.
+    now: Mutex<DateTime<Local>>,
+}
+impl MockClock {
+    /// Constructor for the MockClock.
+    ///
+    /// # Arguments
+    /// - `now`: The time that the clock initially reports.
+    ///
+    /// # Returns
+    /// A new MockClock frozen at `now`.
+    #[inline]
+    pub fn new(now: DateTime<Local>) -> Self { Self { now: Mutex::new(now) } }
+
+    /// Sets the time of this clock to a new value.
+    ///
+    /// # Arguments
+    /// - `now`: The new time to report.
+    #[inline]
+    pub fn set(&self, now: DateTime<Local>) { *self.now.lock() = now; }
+
+    /// Moves the time of this clock forward.
+    ///
+    /// # Arguments
+    /// - `delta`: The [`Duration`] to advance the clock by.
+    #[inline]
+    pub fn advance(&self, delta: Duration) { *self.now.lock() += delta; }
+}
+impl Clock for MockClock {
+    #[inline]
+    fn now(&self) -> DateTime<Local> { *self.now.lock() }
+}
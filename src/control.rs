@@ -0,0 +1,368 @@
+//  CONTROL.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 09:19:53
+//  Last edited:
+//    16 Oct 2026, 10:25:12
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a local Unix socket on which the running app accepts
+//!   simple line commands, such that it can be scripted from the shell
+//!   (e.g., `echo mute 1h | nc -U <socket>`).
+//!   
+//!   Every command is answered with a single line: `ok`, `error: ...`,
+//!   or JSON for `status` and `events`.
+//
+
+use std::convert::Infallible;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::io::{BufRead as _, BufReader, ErrorKind, Read as _, Write as _};
+use std::os::unix::fs::PermissionsExt as _;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{error, fs, thread};
+
+use chrono::{DateTime, Local};
+use error_trace::ErrorTrace as _;
+use log::{debug, info};
+use serde_json::json;
+
+use crate::app::tray::TrayIconMenuItem;
+use crate::app::{AppEvent, AppEventLoopProxy};
+use crate::event::Event;
+use crate::state::{AppState, MuteState};
+
+
+/***** CONSTANTS *****/
+/// How long a client may stay silent before we hang up on it.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// The maximum length of a command that we read.
+const MAX_LINE_LEN: u64 = 64 * 1024;
+/// The [`Event::source`] of injected events that don't name one.
+const INJECT_SOURCE: &str = "control";
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from the control socket.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to listen on the configured path.
+    Bind { path: PathBuf, err: std::io::Error },
+    /// Failed to connect to a running app on the given path.
+    Connect { path: PathBuf, err: std::io::Error },
+    /// Failed to send a command to (or read the answer of) a running app.
+    Exchange { path: PathBuf, err: std::io::Error },
+    /// Another process is already listening on the configured path.
+    InUse { path: PathBuf },
+    /// A running app answered a command with an error.
+    Refused { path: PathBuf, reason: String },
+    /// Failed to remove a socket left behind by a previous run.
+    StaleRemove { path: PathBuf, err: std::io::Error },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            Bind { path, .. } => write!(f, "Failed to listen for commands on '{}'", path.display()),
+            Connect { path, .. } => write!(f, "Failed to connect to running app on '{}'", path.display()),
+            Exchange { path, .. } => write!(f, "Failed to send command to running app on '{}'", path.display()),
+            InUse { path } => write!(f, "Cannot listen for commands on '{}' because something else already does", path.display()),
+            Refused { path, reason } => write!(f, "Running app on '{}' refused command: {}", path.display(), reason),
+            StaleRemove { path, .. } => write!(f, "Failed to remove stale control socket '{}'", path.display()),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            Bind { err, .. } => Some(err),
+            Connect { err, .. } => Some(err),
+            Exchange { err, .. } => Some(err),
+            InUse { .. } => None,
+            Refused { .. } => None,
+            StaleRemove { err, .. } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Parses a duration like `90`, `90s`, `30m`, `2h` or `1d`.
+///
+/// # Arguments
+/// - `raw`: The duration to parse. Without a unit, it's in seconds.
+///
+/// # Returns
+/// The parsed [`Duration`], or [`None`] if it's not a valid one.
+fn parse_duration(raw: &str) -> Option<Duration> {
+    let split: usize = raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len());
+    let value: u64 = raw[..split].parse().ok()?;
+    let unit: u64 = match &raw[split..] {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return None,
+    };
+    Some(Duration::from_secs(value.checked_mul(unit)?))
+}
+
+/// Changes whether notifications are muted, like the tray menu does.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] to change.
+/// - `muted`: The new [`MuteState`].
+///
+/// # Errors
+/// This function errors if we failed to persist the change.
+fn set_muted(state: &AppState, muted: MuteState) -> Result<(), String> {
+    info!("Notifications are now {} over the control socket", if muted.is_muted() { "muted" } else { "unmuted" });
+    match state.access_mut_from("control", |state| -> Result<(), Infallible> {
+        state.muted = muted;
+        state.mark_dirty();
+        Ok(())
+    }) {
+        Ok(res) => res.map_err(|err| format!("{}", err.trace())),
+        Err(err) => match err {},
+    }
+}
+
+/// Describes the state of the app as JSON.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] to describe.
+///
+/// # Returns
+/// A JSON object with the mute state, the number of events and the state of every server.
+fn status(state: &AppState) -> serde_json::Value {
+    state.access(|state| {
+        let until: Option<String> = if let MuteState::After(deadline) = &state.muted { Some(deadline.with_timezone(&Local).to_rfc3339()) } else { None };
+        let servers: Vec<serde_json::Value> = state
+            .servers
+            .iter()
+            .map(|server| {
+                json!({
+                    "name": server.name,
+                    "online": !state.offline.contains(&server.name),
+                    "auth_failed": state.auth_failed.contains(&server.name),
+                    "last_poll": state.last_poll.get(&server.name).map(DateTime::to_rfc3339),
+                    "unread": state.unread(&server.name),
+                })
+            })
+            .collect();
+        json!({
+            "muted": state.muted.is_muted(),
+            "muted_until": until,
+            "events": state.events.len(),
+            "unread": state.events.iter().filter(|event| !event.read).count(),
+            "servers": servers,
+        })
+    })
+}
+
+/// Describes the events that the app keeps track of as JSON.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] with the events.
+///
+/// # Returns
+/// A JSON list of the events, newest first.
+///
+/// # Errors
+/// This function errors if an event could not be serialized.
+fn events(state: &AppState) -> Result<serde_json::Value, String> {
+    state.access(|state| serde_json::to_value(&state.events)).map_err(|err| format!("failed to serialize events: {err}"))
+}
+
+/// Adds an event as if a server reported it.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] to add the event to.
+/// - `proxy`: An [`AppEventLoopProxy`] to tell the main loop about the new event with.
+/// - `raw`: The event, as JSON.
+///
+/// # Errors
+/// This function errors if the event is not valid, or if we failed to persist the state afterwards.
+fn inject(state: &AppState, proxy: &AppEventLoopProxy, raw: &str) -> Result<(), String> {
+    let mut event: Event = serde_json::from_str(raw).map_err(|err| format!("invalid event: {err}"))?;
+    if event.source.is_empty() {
+        event.source = INJECT_SOURCE.into();
+    }
+    let server: String = event.source.clone();
+
+    let now: DateTime<Local> = state.now();
+    let count: usize = match state.access_mut(|state| -> Result<usize, Infallible> { Ok(state.ingest([event], now).len()) }) {
+        Ok(res) => res.map_err(|err| format!("{}", err.trace()))?,
+        Err(err) => match err {},
+    };
+    if count > 0 {
+        debug!("Injected event for server '{server}' over the control socket");
+        let _ = proxy.send_event(AppEvent::NewEvents { server, count, silent: false });
+    }
+    Ok(())
+}
+
+/// Executes a single command.
+///
+/// # Arguments
+/// - `line`: The command, without the trailing newline.
+/// - `state`: The [`AppState`] to act on.
+/// - `proxy`: An [`AppEventLoopProxy`] to reach the main loop with.
+///
+/// # Returns
+/// The line to answer with.
+fn execute(line: &str, state: &AppState, proxy: &AppEventLoopProxy) -> String {
+    let (command, arg): (&str, &str) = match line.split_once(char::is_whitespace) {
+        Some((command, arg)) => (command, arg.trim()),
+        None => (line, ""),
+    };
+    let res: Result<Option<serde_json::Value>, String> = match (command, arg) {
+        ("mute", "") => set_muted(state, MuteState::Manual).map(|_| None),
+        ("mute", duration) => match parse_duration(duration) {
+            Some(duration) => set_muted(state, MuteState::lasting(state.now(), duration)).map(|_| None),
+            None => Err(format!("invalid duration '{duration}' (expected e.g. '90s', '30m' or '2h')")),
+        },
+        ("unmute", "") => set_muted(state, MuteState::Unmuted).map(|_| None),
+        ("refresh", "") => {
+            // Do as if the user clicked "Refresh" in the tray menu
+            match proxy.send_event(AppEvent::Action(TrayIconMenuItem::Refresh)) {
+                Ok(_) => Ok(None),
+                Err(_) => Err("app is shutting down".into()),
+            }
+        },
+        ("reload", "") => match proxy.send_event(AppEvent::ReloadConfig) {
+            Ok(_) => Ok(None),
+            Err(_) => Err("app is shutting down".into()),
+        },
+        ("status", "") => Ok(Some(status(state))),
+        ("events", "") => events(state).map(Some),
+        ("inject", "") => Err("missing event (expected 'inject <json>')".into()),
+        ("inject", raw) => inject(state, proxy, raw).map(|_| None),
+        _ => Err(format!("unknown command '{line}' (expected mute [<duration>], unmute, refresh, reload, status, events or inject <json>)")),
+    };
+    match res {
+        Ok(Some(json)) => json.to_string(),
+        Ok(None) => "ok".into(),
+        Err(err) => format!("error: {err}"),
+    }
+}
+
+/// Answers the commands of a single client, until it hangs up.
+///
+/// # Arguments
+/// - `stream`: The [`UnixStream`] of the client.
+/// - `state`: The [`AppState`] to act on.
+/// - `proxy`: An [`AppEventLoopProxy`] to reach the main loop with.
+///
+/// # Errors
+/// This function errors if we failed to talk to the client.
+fn handle(stream: UnixStream, state: &AppState, proxy: &AppEventLoopProxy) -> Result<(), std::io::Error> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader: BufReader<&UnixStream> = BufReader::new(&stream);
+    loop {
+        let mut line: String = String::new();
+        if reader.by_ref().take(MAX_LINE_LEN).read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let line: &str = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        debug!("Received command '{}' over the control socket", line.split_whitespace().next().unwrap_or(""));
+        writeln!(&stream, "{}", execute(line, state, proxy))?;
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Starts listening for commands on a Unix socket at the given path, on a thread of its own.
+///
+/// The socket is only accessible to the current user. Supported commands are `mute`, `mute <duration>`, `unmute`, `refresh`,
+/// `reload`, `status`, `events` and `inject <json>`.
+///
+/// # Arguments
+/// - `path`: The path of the socket. A socket left behind by a previous run is replaced.
+/// - `state`: The [`AppState`] to act on.
+/// - `proxy`: An [`AppEventLoopProxy`] to reach the main loop with.
+///
+/// # Errors
+/// This function errors if we failed to listen on the path, or if something else already does.
+pub fn serve(path: &Path, state: AppState, proxy: AppEventLoopProxy) -> Result<(), Error> {
+    // Clean up after a previous run, but don't steal the socket from a live one
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(Error::InUse { path: path.into() });
+        }
+        debug!("Removing stale control socket '{}'...", path.display());
+        if let Err(err) = fs::remove_file(path) {
+            if err.kind() != ErrorKind::NotFound {
+                return Err(Error::StaleRemove { path: path.into(), err });
+            }
+        }
+    }
+
+    // Listen, only to ourselves
+    let listener: UnixListener = match UnixListener::bind(path) {
+        Ok(listener) => listener,
+        Err(err) => return Err(Error::Bind { path: path.into(), err }),
+    };
+    if let Err(err) = fs::set_permissions(path, fs::Permissions::from_mode(0o600)) {
+        return Err(Error::Bind { path: path.into(), err });
+    }
+    info!("Listening for commands on '{}'", path.display());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let res: Result<(), std::io::Error> = match stream {
+                Ok(stream) => handle(stream, &state, &proxy),
+                Err(err) => Err(err),
+            };
+            if let Err(err) = res {
+                debug!("Failed to answer command: {}", err.trace());
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Sends a single command to the app that listens on a Unix socket at the given path, and waits for its answer.
+///
+/// # Arguments
+/// - `path`: The path of the socket.
+/// - `command`: The command to send (see [`serve()`]), without a trailing newline.
+///
+/// # Returns
+/// The answer, without its trailing newline.
+///
+/// # Errors
+/// This function errors if nobody listens on the path, if we failed to talk to the app, or if it answered with an error.
+pub fn request(path: &Path, command: &str) -> Result<String, Error> {
+    let stream: UnixStream = match UnixStream::connect(path) {
+        Ok(stream) => stream,
+        Err(err) => return Err(Error::Connect { path: path.into(), err }),
+    };
+    let mut answer: String = String::new();
+    if let Err(err) =
+        stream.set_read_timeout(Some(READ_TIMEOUT)).and_then(|_| writeln!(&stream, "{command}")).and_then(|_| BufReader::new(&stream).read_line(&mut answer))
+    {
+        return Err(Error::Exchange { path: path.into(), err });
+    }
+    let answer: &str = answer.trim_end();
+    match answer.strip_prefix("error: ") {
+        Some(reason) => Err(Error::Refused { path: path.into(), reason: reason.into() }),
+        None => Ok(answer.into()),
+    }
+}
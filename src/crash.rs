@@ -0,0 +1,180 @@
+//  CRASH.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 08:27:13
+//  Last edited:
+//    16 Oct 2026, 08:27:13
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements writing (opt-in) crash reports when the app exits with
+//!   an error.
+//!   
+//!   Reports never leave the machine; they're only written to the cache
+//!   directory such that the error is not lost when the app runs
+//!   without a terminal.
+//
+
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::path::{Path, PathBuf};
+use std::{error, fs};
+
+use chrono::{DateTime, Local};
+use error_trace::ErrorTrace as _;
+use log::{debug, warn};
+use serializable::Serializable as _;
+use url::Url;
+
+use crate::state::{ConfigFile, CACHE_DIR_NAME, CONFIG_FILE_NAME};
+
+
+/***** ERRORS *****/
+/// Defines errors originating from writing crash reports.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to create the cache directory.
+    CacheDirCreate { path: PathBuf, err: std::io::Error },
+    /// Failed to write the report file.
+    ReportWrite { path: PathBuf, err: std::io::Error },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            CacheDirCreate { path, .. } => write!(f, "Failed to create cache directory '{}'", path.display()),
+            ReportWrite { path, .. } => write!(f, "Failed to write crash report '{}'", path.display()),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            CacheDirCreate { err, .. } => Some(err),
+            ReportWrite { err, .. } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Removes anything that might be secret or identifying from the given text.
+///
+/// In particular, credentials and queries are stripped from URLs and the user's home directory is replaced by `~`.
+///
+/// # Arguments
+/// - `text`: The text to redact.
+///
+/// # Returns
+/// A redacted copy of `text`.
+fn redact(text: &str) -> String {
+    let mut res: String = String::with_capacity(text.len());
+    for word in text.split_inclusive(char::is_whitespace) {
+        // Only look at the word itself, not the quotes or whitespace around it
+        let start: usize = word.len() - word.trim_start_matches(['\'', '"', '(', '<']).len();
+        let end: usize = word.trim_end().trim_end_matches(['\'', '"', ')', '>', ',', '.', ':']).len().max(start);
+        let (prefix, core, suffix): (&str, &str, &str) = (&word[..start], &word[start..end], &word[end..]);
+
+        // Strip secrets from anything URL-like
+        match Url::parse(core) {
+            Ok(mut url) if url.has_host() => {
+                if !url.username().is_empty() || url.password().is_some() {
+                    let _ = url.set_username("redacted");
+                    let _ = url.set_password(None);
+                }
+                if url.query().is_some() {
+                    url.set_query(Some("redacted"));
+                }
+                url.set_fragment(None);
+                res.push_str(prefix);
+                res.push_str(url.as_str());
+                res.push_str(suffix);
+            },
+            _ => res.push_str(word),
+        }
+    }
+
+    // Hide the user's home directory
+    if let Some(home) = dirs::home_dir() {
+        let home: String = home.display().to_string();
+        if home.len() > 1 {
+            res = res.replace(&home, "~");
+        }
+    }
+    res
+}
+
+/// Writes a crash report for the given error trace.
+///
+/// # Arguments
+/// - `config_dir`: The directory where the app's persistent config is stored.
+/// - `trace`: The (unredacted) error trace to include in the report.
+///
+/// # Returns
+/// The path of the written report.
+///
+/// # Errors
+/// This function errors if we failed to create the cache directory or write the report.
+fn write_report(config_dir: &Path, trace: &str) -> Result<PathBuf, Error> {
+    // Make sure the cache directory exists
+    let cache_dir: PathBuf = config_dir.join(CACHE_DIR_NAME);
+    if !cache_dir.exists() {
+        debug!("Cache directory '{}' does not exist, creating...", cache_dir.display());
+        if let Err(err) = fs::create_dir_all(&cache_dir) {
+            return Err(Error::CacheDirCreate { path: cache_dir, err });
+        }
+    }
+
+    // Build the report
+    let now: DateTime<Local> = Local::now();
+    let report: String = format!(
+        "{} v{}\ntime: {}\nos: {}\narch: {}\n\n{}\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        now.to_rfc3339(),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        redact(trace)
+    );
+
+    // Write it
+    let path: PathBuf = cache_dir.join(format!("crash-{}.log", now.format("%Y%m%d-%H%M%S")));
+    match fs::write(&path, report) {
+        Ok(_) => Ok(path),
+        Err(err) => Err(Error::ReportWrite { path, err }),
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Writes a crash report for the given error to the cache directory, if the user enabled this in their config.
+///
+/// Any failures are logged instead of returned, as this is typically called right before exiting.
+///
+/// # Arguments
+/// - `config_dir`: The directory where the app's persistent config is stored.
+/// - `err`: The error that made the app exit.
+pub fn report<E: error::Error>(config_dir: &Path, err: &E) {
+    // This is strictly opt-in, so we stay quiet if we can't even tell
+    let enabled: bool = match ConfigFile::from_path(config_dir.join(CONFIG_FILE_NAME)) {
+        Ok(config) => config.crash_reports,
+        Err(_) => false,
+    };
+    if !enabled {
+        return;
+    }
+
+    // Write it
+    match write_report(config_dir, &format!("{}", err.trace())) {
+        Ok(path) => eprintln!("Crash report written to '{}'", path.display()),
+        Err(err) => warn!("{}", err.trace()),
+    }
+}
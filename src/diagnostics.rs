@@ -0,0 +1,178 @@
+//  DIAGNOSTICS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 09:17:36
+//  Last edited:
+//    16 Oct 2026, 09:17:36
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Collects information about the platform and the backends that the
+//!   client depends on, to help figuring out why it doesn't work on
+//!   someone's machine.
+//!   
+//!   This never launches the GUI itself; it only checks whether the
+//!   pieces it needs are there.
+//
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use error_trace::ErrorTrace as _;
+use serializable::Serializable as _;
+
+use crate::state::{ConfigFile, CACHE_DIR_NAME, CONFIG_FILE_NAME};
+
+
+/***** CONSTANTS *****/
+/// The name of the file that we briefly create to see if a directory is writable.
+const PROBE_FILE_NAME: &str = ".diagnostics-probe";
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Describes whether a directory exists and whether we can write to it.
+///
+/// # Arguments
+/// - `path`: The path of the directory.
+///
+/// # Returns
+/// A short description like `"writable"` or `"missing"`.
+fn dir_status(path: &Path) -> String {
+    if !path.exists() {
+        return "missing (created on first run)".into();
+    }
+    if !path.is_dir() {
+        return "not a directory".into();
+    }
+    let probe: PathBuf = path.join(PROBE_FILE_NAME);
+    match fs::write(&probe, b"") {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            "writable".into()
+        },
+        Err(err) => format!("not writable ({err})"),
+    }
+}
+
+/// Describes whether the config file can be loaded.
+///
+/// # Arguments
+/// - `path`: The path of the config file.
+///
+/// # Returns
+/// A short description like `"valid"` or the reason it isn't.
+fn config_status(path: &Path) -> String {
+    if !path.exists() {
+        return "missing (defaults are used)".into();
+    }
+    match ConfigFile::from_path(path) {
+        Ok(config) => format!("valid ({} server(s))", config.servers.len()),
+        Err(err) => format!("invalid: {}", err.trace()),
+    }
+}
+
+/// Initializes GTK, which the tray icon needs on Linux.
+///
+/// # Returns
+/// Whether that worked (with the reason if it didn't), or [`None`] if this OS doesn't use GTK.
+#[cfg(target_os = "linux")]
+fn gtk_init() -> Option<Result<(), String>> { Some(gtk::init().map_err(|err| err.to_string())) }
+#[cfg(not(target_os = "linux"))]
+fn gtk_init() -> Option<Result<(), String>> { None }
+
+/// Describes whether a tray icon can be created.
+///
+/// On Linux, this actually creates (and immediately removes) one, as it depends on GTK and on the desktop providing a tray. Other
+/// OSes always have one.
+///
+/// # Arguments
+/// - `gtk`: Whether GTK initialized (only relevant on Linux).
+///
+/// # Returns
+/// A short description.
+fn tray_status(gtk: bool) -> String {
+    if cfg!(target_os = "linux") {
+        if !gtk {
+            return "unavailable (GTK did not initialize)".into();
+        }
+        match tray_icon::TrayIconBuilder::new().with_tooltip(env!("CARGO_PKG_NAME")).build() {
+            Ok(_) => "available".into(),
+            Err(err) => format!("unavailable ({err})"),
+        }
+    } else {
+        "native".into()
+    }
+}
+
+/// Lists the GPU adapters that wgpu can render the window with.
+///
+/// # Returns
+/// A description of every adapter, which is empty if there are none.
+fn adapters() -> Vec<String> {
+    let instance: wgpu::Instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+    instance
+        .enumerate_adapters(wgpu::Backends::all())
+        .into_iter()
+        .map(|adapter| {
+            let info: wgpu::AdapterInfo = adapter.get_info();
+            format!("{} ({:?}, {:?}, driver '{}' {})", info.name, info.backend, info.device_type, info.driver, info.driver_info)
+        })
+        .collect()
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Collects information about the platform and the backends that the client depends on.
+///
+/// This includes the app version, the OS, whether GTK and a tray are available, the config and cache directories and the GPU
+/// adapters. Any failures are part of the report rather than errors, since finding those is the point.
+///
+/// # Arguments
+/// - `config_dir`: The directory where the app's persistent config is stored.
+///
+/// # Returns
+/// A human-readable report, one fact per line.
+pub fn report(config_dir: &Path) -> String {
+    let mut res: String = String::new();
+    let _ = writeln!(res, "version:    {} v{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(res, "os:         {} ({}, {})", std::env::consts::OS, std::env::consts::FAMILY, std::env::consts::ARCH);
+
+    // The backends
+    let gtk: Option<Result<(), String>> = gtk_init();
+    match &gtk {
+        Some(Ok(_)) => {
+            let _ = writeln!(res, "gtk:        initialized");
+        },
+        Some(Err(err)) => {
+            let _ = writeln!(res, "gtk:        failed to initialize ({err})");
+        },
+        None => {},
+    }
+    let _ = writeln!(res, "tray:       {}", tray_status(matches!(gtk, Some(Ok(_)))));
+
+    // The directories
+    let config_path: PathBuf = config_dir.join(CONFIG_FILE_NAME);
+    let cache_dir: PathBuf = config_dir.join(CACHE_DIR_NAME);
+    let _ = writeln!(res, "config dir: {} ({})", config_dir.display(), dir_status(config_dir));
+    let _ = writeln!(res, "config:     {} ({})", config_path.display(), config_status(&config_path));
+    let _ = writeln!(res, "cache dir:  {} ({})", cache_dir.display(), dir_status(&cache_dir));
+
+    // The GPU
+    let adapters: Vec<String> = adapters();
+    if adapters.is_empty() {
+        let _ = writeln!(res, "adapters:   none found");
+    }
+    for (i, adapter) in adapters.iter().enumerate() {
+        let _ = writeln!(res, "{:<12}{adapter}", if i == 0 { "adapters:" } else { "" });
+    }
+    res
+}
@@ -0,0 +1,105 @@
+//  DND.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 08:56:59
+//  Last edited:
+//    16 Oct 2026, 08:56:59
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Finds out whether the OS is in a do-not-disturb (or focus) mode, such
+//!   that we can hold back notifications while it is.
+//!   
+//!   This is only supported on macOS and Windows. Elsewhere, the OS is
+//!   never considered to be in such a mode.
+//
+
+#[cfg(target_os = "macos")]
+use std::fs;
+#[cfg(target_os = "macos")]
+use std::path::PathBuf;
+
+#[cfg(any(target_os = "macos", windows))]
+use log::debug;
+
+
+/***** CONSTANTS *****/
+/// The file in which macOS (12 and up) keeps the assertions of active focus modes, relative to the user's home directory.
+#[cfg(target_os = "macos")]
+const MACOS_ASSERTIONS_PATH: &str = "Library/DoNotDisturb/DB/Assertions.json";
+/// The values of `QUERY_USER_NOTIFICATION_STATE` in which Windows does not want to be disturbed (busy, fullscreen Direct3D,
+/// presentation mode and quiet time, respectively).
+#[cfg(windows)]
+const WINDOWS_QUIET_STATES: [i32; 4] = [2, 3, 4, 6];
+
+
+
+
+
+/***** LIBRARY *****/
+/// Checks whether the OS is currently in a do-not-disturb mode.
+///
+/// Any failure to find out is logged and treated as not being in such a mode, since holding back notifications for no reason is
+/// worse than showing them during a focus session.
+///
+/// # Returns
+/// True if the user asked the OS to not be disturbed, or false otherwise (or if we can't tell).
+#[cfg(target_os = "macos")]
+pub fn is_active() -> bool {
+    let Some(path): Option<PathBuf> = dirs::home_dir().map(|home| home.join(MACOS_ASSERTIONS_PATH)) else { return false };
+    let raw: String = match fs::read_to_string(&path) {
+        Ok(raw) => raw,
+        Err(err) => {
+            debug!("Failed to read focus assertions '{}' (the app may need full disk access): {err}", path.display());
+            return false;
+        },
+    };
+    let assertions: serde_json::Value = match serde_json::from_str(&raw) {
+        Ok(assertions) => assertions,
+        Err(err) => {
+            debug!("Failed to parse focus assertions '{}': {err}", path.display());
+            return false;
+        },
+    };
+
+    // Any focus mode that is active has a record in here
+    assertions.get("data").and_then(serde_json::Value::as_array).is_some_and(|data| {
+        data.iter().any(|entry| entry.get("storeAssertionRecords").and_then(serde_json::Value::as_array).is_some_and(|records| !records.is_empty()))
+    })
+}
+
+/// Checks whether the OS is currently in a do-not-disturb mode.
+///
+/// Any failure to find out is logged and treated as not being in such a mode, since holding back notifications for no reason is
+/// worse than showing them during a focus session.
+///
+/// # Returns
+/// True if the user asked the OS to not be disturbed, or false otherwise (or if we can't tell).
+#[cfg(windows)]
+pub fn is_active() -> bool {
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHQueryUserNotificationState(state: *mut i32) -> i32;
+    }
+
+    let mut state: i32 = 0;
+    // SAFETY: The function only writes a `QUERY_USER_NOTIFICATION_STATE` to the pointer, which points to a valid `i32`.
+    let res: i32 = unsafe { SHQueryUserNotificationState(&mut state) };
+    if res != 0 {
+        debug!("Failed to query user notification state (HRESULT {res:#010x})");
+        return false;
+    }
+    WINDOWS_QUIET_STATES.contains(&state)
+}
+
+/// Checks whether the OS is currently in a do-not-disturb mode.
+///
+/// This platform is not supported, so this always says it isn't.
+///
+/// # Returns
+/// False.
+#[cfg(not(any(target_os = "macos", windows)))]
+#[inline]
+pub fn is_active() -> bool { false }
@@ -0,0 +1,147 @@
+//  EVENT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 08:23:33
+//  Last edited:
+//    16 Oct 2026, 11:57:05
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines the events that remote servers emit and that we keep track
+//!   of.
+//
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local};
+use enum_debug::EnumDebug;
+use serde::{Deserialize, Deserializer, Serialize};
+use url::Url;
+
+
+/***** HELPER FUNCTIONS *****/
+/// Deserializes [`Event::metadata`] without failing the whole event if a server uses the field for something else.
+///
+/// # Arguments
+/// - `deserializer`: The [`Deserializer`] to read from.
+///
+/// # Returns
+/// The metadata, where non-string values are kept as JSON. If the field is not an object at all, it's ignored.
+///
+/// # Errors
+/// This function errors if the `deserializer` fails to produce any value.
+fn deserialize_metadata<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<String, String>, D::Error> {
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Object(fields) => Ok(fields
+            .into_iter()
+            .map(|(key, value)| match value {
+                serde_json::Value::String(value) => (key, value),
+                value => (key, value.to_string()),
+            })
+            .collect()),
+        _ => Ok(BTreeMap::new()),
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines how important an [`Event`] is.
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumDebug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Purely informative; nothing to worry about.
+    #[default]
+    Info,
+    /// Something is off, but not (yet) broken.
+    Warning,
+    /// Something is broken.
+    Error,
+    /// Something is broken and needs immediate attention.
+    Critical,
+}
+impl Severity {
+    /// All severities, from least to most severe.
+    pub const ALL: [Self; 4] = [Self::Info, Self::Warning, Self::Error, Self::Critical];
+
+    /// Checks whether this severity is strictly more severe than another.
+    ///
+    /// # Arguments
+    /// - `other`: The [`Severity`] to compare with.
+    ///
+    /// # Returns
+    /// True if this is more severe than `other`, or false if it's equally or less severe.
+    #[inline]
+    pub fn is_more_severe_than(&self, other: Severity) -> bool { *self > other }
+}
+
+
+
+/// Defines why an [`Event`]'s timestamp is not the one that the server sent.
+#[derive(Clone, Copy, Debug, Deserialize, EnumDebug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampFlag {
+    /// The server sent no timestamp.
+    Missing,
+    /// The server sent a timestamp too far in the future (e.g., because its clock is off).
+    Future,
+}
+
+
+
+/// Defines a single event that happened on a remote server.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Event {
+    /// Some identifier that is unique for this event on the server that emitted it.
+    pub id: String,
+    /// The moment the event happened.
+    pub timestamp: DateTime<Local>,
+    /// How important the event is.
+    #[serde(default)]
+    pub severity: Severity,
+    /// The name of the server that emitted the event. Filled in by the poller if the server doesn't.
+    #[serde(default)]
+    pub source: String,
+    /// A short summary of what happened.
+    pub title: String,
+    /// A longer description of what happened.
+    #[serde(default)]
+    pub message: String,
+    /// A link to more information about the event (e.g., a log line or a dashboard), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<Url>,
+    /// Whether the user has seen this event.
+    #[serde(default)]
+    pub read: bool,
+    /// Whether the user pinned this event, such that it's kept around regardless of how many newer events there are.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Why [`Event::timestamp`] is the time we received the event instead of the one the server sent, if it is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_flag: Option<TimestampFlag>,
+    /// Any other fields that the server reported, as `key -> value`. Non-string values are kept as JSON.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty", deserialize_with = "deserialize_metadata")]
+    pub metadata: BTreeMap<String, String>,
+}
+impl Event {
+    /// Returns a key that identifies this event among those of all servers.
+    ///
+    /// # Returns
+    /// A string combining the [`Event::source`] and [`Event::id`].
+    #[inline]
+    pub fn key(&self) -> String { format!("{}/{}", self.source, self.id) }
+
+    /// Checks whether this event is strictly more severe than the given severity.
+    ///
+    /// # Arguments
+    /// - `other`: The [`Severity`] to compare with.
+    ///
+    /// # Returns
+    /// True if this event's [`Event::severity`] is more severe than `other`, or false if it's equally or less severe.
+    #[inline]
+    pub fn is_more_severe_than(&self, other: Severity) -> bool { self.severity.is_more_severe_than(other) }
+}
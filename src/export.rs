@@ -0,0 +1,154 @@
+//  EXPORT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 08:45:07
+//  Last edited:
+//    16 Oct 2026, 08:45:07
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements keeping a record of events outside of the app, by
+//!   appending them to a CSV file per day.
+//
+
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::fs::{File, OpenOptions};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::{error, fs};
+
+use chrono::{DateTime, Local};
+use enum_debug::EnumDebug as _;
+use log::debug;
+use parking_lot::{Mutex, MutexGuard};
+
+use crate::event::Event;
+
+
+/***** CONSTANTS *****/
+/// The first row of every CSV file.
+const CSV_HEADER: &str = "timestamp,server,severity,id,title";
+
+
+
+
+
+/***** STATICS *****/
+/// Makes sure that pollers don't write to the same file at the same time, e.g., both writing the header of a new file.
+static CSV_LOCK: Mutex<()> = Mutex::new(());
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from exporting events.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to create the export directory.
+    DirCreate { path: PathBuf, err: std::io::Error },
+    /// Failed to open the file to export to.
+    FileOpen { path: PathBuf, err: std::io::Error },
+    /// Failed to write to the file to export to.
+    FileWrite { path: PathBuf, err: std::io::Error },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            DirCreate { path, .. } => write!(f, "Failed to create export directory '{}'", path.display()),
+            FileOpen { path, .. } => write!(f, "Failed to open export file '{}'", path.display()),
+            FileWrite { path, .. } => write!(f, "Failed to write to export file '{}'", path.display()),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            DirCreate { err, .. } => Some(err),
+            FileOpen { err, .. } => Some(err),
+            FileWrite { err, .. } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Escapes a single CSV field.
+///
+/// # Arguments
+/// - `field`: The raw value of the field.
+///
+/// # Returns
+/// The field as-is, or quoted (with any quotes doubled) if it contains commas, quotes or newlines.
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.into()
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Appends the given events to today's CSV file in the given directory.
+///
+/// Files are named `events-YYYY-MM-DD.csv` after the day of the export, and get a header row when they're created.
+///
+/// # Arguments
+/// - `dir`: The directory to write the CSV files to.
+/// - `events`: The [`Event`]s to append.
+/// - `now`: The current time, which decides the file to append to.
+///
+/// # Errors
+/// This function errors if we failed to create the directory or to write the file.
+pub fn append_csv(dir: &Path, events: &[Event], now: DateTime<Local>) -> Result<(), Error> {
+    if events.is_empty() {
+        return Ok(());
+    }
+    if !dir.exists() {
+        debug!("Export directory '{}' does not exist, creating...", dir.display());
+        if let Err(err) = fs::create_dir_all(dir) {
+            return Err(Error::DirCreate { path: dir.into(), err });
+        }
+    }
+
+    // Build the rows in one go, such that they're written in one go as well
+    let path: PathBuf = dir.join(format!("events-{}.csv", now.format("%Y-%m-%d")));
+    let _lock: MutexGuard<()> = CSV_LOCK.lock();
+    let mut rows: String = String::new();
+    if !path.exists() {
+        rows.push_str(CSV_HEADER);
+        rows.push('\n');
+    }
+    for event in events {
+        rows.push_str(&format!(
+            "{},{},{},{},{}\n",
+            escape(&event.timestamp.to_rfc3339()),
+            escape(&event.source),
+            escape(&event.severity.variant().to_string()),
+            escape(&event.id),
+            escape(&event.title)
+        ));
+    }
+
+    // Write them
+    let mut handle: File = match OpenOptions::new().create(true).append(true).open(&path) {
+        Ok(handle) => handle,
+        Err(err) => return Err(Error::FileOpen { path, err }),
+    };
+    if let Err(err) = handle.write_all(rows.as_bytes()) {
+        return Err(Error::FileWrite { path, err });
+    }
+    debug!("Exported {} event(s) to '{}'", events.len(), path.display());
+    Ok(())
+}
@@ -0,0 +1,559 @@
+//  I18N.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 09:09:34
+//  Last edited:
+//    16 Oct 2026, 11:57:05
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Translates the texts that the client shows to the user (menu labels,
+//!   window texts and notifications) into the language they prefer.
+//!   
+//!   Texts that are missing in a language are shown in English instead.
+//
+
+use std::env;
+
+use enum_debug::EnumDebug;
+use serde::{Deserialize, Serialize};
+
+use crate::event::{Severity, TimestampFlag};
+
+
+/***** CONSTANTS *****/
+/// The environment variables that may describe the user's locale, in order of precedence.
+const LOCALE_VARS: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Returns the English version of a text, which every text has.
+///
+/// # Arguments
+/// - `text`: The [`Text`] to look up.
+///
+/// # Returns
+/// The English text.
+fn english(text: Text) -> &'static str {
+    match text {
+        Text::MenuOpen => "Open",
+        Text::MenuMute => "Mute / unmute",
+        Text::MenuMuteFor => "Mute for...",
+        Text::MenuMuteFor15Min => "15 minutes",
+        Text::MenuMuteFor1Hour => "1 hour",
+        Text::MenuMuteFor4Hours => "4 hours",
+        Text::MenuMuteForTomorrow => "Until tomorrow morning",
+        Text::MenuRefresh => "Refresh",
+        Text::MenuAcknowledgeNewest => "Acknowledge newest event",
+        Text::MenuMinSeverity => "Notify about",
+        Text::MenuSeverityInfo => "All events",
+        Text::MenuSeverityWarning => "Warnings and worse",
+        Text::MenuSeverityError => "Errors and worse",
+        Text::MenuSeverityCritical => "Only critical events",
+        Text::MenuDashboards => "Open dashboard",
+        Text::MenuExit => "Exit",
+
+        Text::MiniMode => "Mini mode",
+        Text::FullMode => "Back to full mode",
+        Text::Servers => "Servers",
+        Text::AllServers => "All",
+        Text::SearchHint => "Search events...",
+        Text::SearchClear => "Clear the search",
+        Text::Snoozed => "Snoozed",
+        Text::SilenceAll => "🔕 Silence all",
+        Text::SilenceAllHint => "Stop all notifications until you un-silence them",
+        Text::Silenced => "🔕 Notifications are silenced",
+        Text::SilencedUntil => "🔕 Notifications are silenced until {time}",
+        Text::Unsilence => "Un-silence",
+        Text::SyncError => "⚠ Settings could not be saved: {err}",
+        Text::NotPersistent => "⚠ Settings won't be saved, as '{dir}' is not writable",
+        Text::Insecure => "⚠ Insecure: {servers}",
+        Text::InsecureHint => "TLS certificates of these servers are not verified",
+        Text::Muted => "🔕 Muted",
+        Text::Unmuted => "🔔 Unmuted",
+        Text::OsDnd => "(🌙 OS do not disturb)",
+        Text::OsDndHint => "Notifications are held back while the OS is in do-not-disturb mode",
+        Text::Activity => "Events per minute over the last hour ({n} in total, at most {max} in a minute)",
+        Text::Selected => "{n} selected",
+        Text::MarkRead => "Mark read",
+        Text::Delete => "Delete",
+        Text::CopyJson => "Copy as JSON",
+        Text::NoEvents => "No events yet",
+        Text::DetailTime => "Time",
+        Text::DetailSeverity => "Severity",
+        Text::DetailServer => "Server",
+        Text::DetailId => "ID",
+        Text::DetailLink => "Link",
+        Text::NoServers => "No servers configured",
+        Text::Unsnooze => "Unsnooze",
+        Text::UnsnoozeHint => "Notify about events with this title again",
+        Text::UnsnoozeAll => "Unsnooze all",
+        Text::NoSnoozed => "No event titles are snoozed",
+        Text::SnoozedUntil => "Until {time}",
+        Text::SnoozedUntilRestart => "Until restart",
+        Text::SnoozedUntilUnsnoozed => "Until unsnoozed",
+        Text::ImportTitle => "Import config",
+        Text::ImportQuestion => "Import '{path}' with {n} server(s)?",
+        Text::ImportHint => "Merging only adds servers that you don't have yet. Replacing overwrites all your settings.",
+        Text::ImportMerge => "Merge",
+        Text::ImportReplace => "Replace",
+        Text::Cancel => "Cancel",
+        Text::ErrorTitle => "Error",
+        Text::Ok => "OK",
+        Text::SnoozeHour => "For an hour",
+        Text::SnoozeDay => "For a day",
+        Text::SnoozeRestart => "Until restart",
+        Text::AgoSeconds => "{n}s ago",
+        Text::AgoMinutes => "{n}m ago",
+        Text::AgoHours => "{n}h ago",
+        Text::Never => "never",
+        Text::SerializeFailed => "Failed to serialize events: {err}",
+        Text::Pin => "Pin",
+        Text::PinHint => "Pin, to keep it around",
+        Text::Unpin => "Unpin",
+        Text::Pinned => "📌 Pinned",
+        Text::PinnedHint => "Keep this event around regardless of how many newer events there are",
+        Text::SnoozeThisHint => "Stop notifying about events with this title",
+        Text::CloseDetail => "Close (Esc)",
+        Text::Metadata => "Metadata ({n})",
+        Text::ServerUnread => "{server} ({n} unread)",
+        Text::TimestampMissing => "The server sent no time, so this is when the event was received",
+        Text::TimestampFuture => "The server sent a time in the future, so this is when the event was received",
+        Text::SortNewest => "Newest first",
+        Text::SortSeverity => "Most severe first",
+        Text::SortServer => "By server",
+        Text::PolledAgo => "{server}: {time}",
+        Text::AuthFailed => "{server}: authentication failed (polled {time})",
+        Text::AuthFailedHint => "Check the token or client credentials of this server",
+        Text::Offline => "{server}: offline (polled {time})",
+        Text::TestConnection => "Test",
+        Text::Reachable => "✔ Reachable ({n} event(s))",
+        Text::TestFailed => "✖ Failed",
+
+        Text::SnoozeAction => "Snooze this",
+        Text::OpenAction => "Open",
+        Text::FloodTitle => "Notification flood suppressed ({n} hidden)",
+        Text::FloodMessage => "Too many notifications arrived at once, so some were not shown. The events themselves are still in the list.",
+    }
+}
+
+/// Returns the Dutch version of a text, if it has one.
+///
+/// # Arguments
+/// - `text`: The [`Text`] to look up.
+///
+/// # Returns
+/// The Dutch text, or [`None`] if it hasn't been translated yet.
+fn dutch(text: Text) -> Option<&'static str> {
+    match text {
+        Text::MenuOpen => Some("Openen"),
+        Text::MenuMute => Some("Dempen / niet dempen"),
+        Text::MenuMuteFor => Some("Dempen voor..."),
+        Text::MenuMuteFor15Min => Some("15 minuten"),
+        Text::MenuMuteFor1Hour => Some("1 uur"),
+        Text::MenuMuteFor4Hours => Some("4 uur"),
+        Text::MenuMuteForTomorrow => Some("Tot morgenochtend"),
+        Text::MenuRefresh => Some("Verversen"),
+        Text::MenuAcknowledgeNewest => Some("Nieuwste gebeurtenis bevestigen"),
+        Text::MenuMinSeverity => Some("Melden bij"),
+        Text::MenuSeverityInfo => Some("Alle gebeurtenissen"),
+        Text::MenuSeverityWarning => Some("Waarschuwingen en erger"),
+        Text::MenuSeverityError => Some("Fouten en erger"),
+        Text::MenuSeverityCritical => Some("Alleen kritieke gebeurtenissen"),
+        Text::MenuDashboards => Some("Dashboard openen"),
+        Text::MenuExit => Some("Afsluiten"),
+
+        Text::MiniMode => Some("Minimodus"),
+        Text::FullMode => Some("Terug naar volledige modus"),
+        Text::Servers => Some("Servers"),
+        Text::AllServers => Some("Alle"),
+        Text::SearchHint => Some("Gebeurtenissen zoeken..."),
+        Text::SearchClear => Some("Zoekopdracht wissen"),
+        Text::Snoozed => Some("Gesluimerd"),
+        Text::SilenceAll => Some("🔕 Alles stil"),
+        Text::SilenceAllHint => Some("Stop alle meldingen totdat je ze weer aanzet"),
+        Text::Silenced => Some("🔕 Meldingen staan stil"),
+        Text::SilencedUntil => Some("🔕 Meldingen staan stil tot {time}"),
+        Text::Unsilence => Some("Weer aanzetten"),
+        Text::SyncError => Some("⚠ Instellingen konden niet worden opgeslagen: {err}"),
+        Text::NotPersistent => Some("⚠ Instellingen worden niet opgeslagen, omdat '{dir}' niet beschrijfbaar is"),
+        Text::Insecure => Some("⚠ Onveilig: {servers}"),
+        Text::InsecureHint => Some("De TLS-certificaten van deze servers worden niet gecontroleerd"),
+        Text::Muted => Some("🔕 Gedempt"),
+        Text::Unmuted => Some("🔔 Niet gedempt"),
+        Text::OsDnd => Some("(🌙 Niet storen van het OS)"),
+        Text::OsDndHint => Some("Meldingen worden tegengehouden zolang het OS op niet storen staat"),
+        Text::Activity => Some("Gebeurtenissen per minuut over het afgelopen uur ({n} in totaal, hoogstens {max} in een minuut)"),
+        Text::Selected => Some("{n} geselecteerd"),
+        Text::MarkRead => Some("Als gelezen markeren"),
+        Text::Delete => Some("Verwijderen"),
+        Text::CopyJson => Some("Kopiëren als JSON"),
+        Text::NoEvents => Some("Nog geen gebeurtenissen"),
+        Text::DetailTime => Some("Tijd"),
+        Text::DetailSeverity => Some("Ernst"),
+        Text::DetailServer => Some("Server"),
+        Text::DetailId => Some("ID"),
+        Text::DetailLink => Some("Link"),
+        Text::NoServers => Some("Geen servers ingesteld"),
+        Text::Unsnooze => Some("Wekken"),
+        Text::UnsnoozeHint => Some("Weer melden bij gebeurtenissen met deze titel"),
+        Text::UnsnoozeAll => Some("Alles wekken"),
+        Text::NoSnoozed => Some("Er sluimeren geen titels"),
+        Text::SnoozedUntil => Some("Tot {time}"),
+        Text::SnoozedUntilRestart => Some("Tot herstart"),
+        Text::SnoozedUntilUnsnoozed => Some("Tot ze gewekt worden"),
+        Text::ImportTitle => Some("Config importeren"),
+        Text::ImportQuestion => Some("'{path}' met {n} server(s) importeren?"),
+        Text::ImportHint => Some("Samenvoegen voegt alleen servers toe die je nog niet hebt. Vervangen overschrijft al je instellingen."),
+        Text::ImportMerge => Some("Samenvoegen"),
+        Text::ImportReplace => Some("Vervangen"),
+        Text::Cancel => Some("Annuleren"),
+        Text::ErrorTitle => Some("Fout"),
+        Text::Ok => Some("OK"),
+        Text::SnoozeHour => Some("Een uur lang"),
+        Text::SnoozeDay => Some("Een dag lang"),
+        Text::SnoozeRestart => Some("Tot herstart"),
+        Text::AgoSeconds => Some("{n}s geleden"),
+        Text::AgoMinutes => Some("{n}m geleden"),
+        Text::AgoHours => Some("{n}u geleden"),
+        Text::Never => Some("nooit"),
+        Text::SerializeFailed => Some("Gebeurtenissen konden niet worden omgezet: {err}"),
+        Text::Pin => Some("Vastzetten"),
+        Text::PinHint => Some("Vastzetten, om hem te bewaren"),
+        Text::Unpin => Some("Losmaken"),
+        Text::Pinned => Some("📌 Vastgezet"),
+        Text::PinnedHint => Some("Bewaar deze gebeurtenis, ongeacht hoeveel nieuwere er zijn"),
+        Text::SnoozeThisHint => Some("Geen meldingen meer geven over gebeurtenissen met deze titel"),
+        Text::CloseDetail => Some("Sluiten (Esc)"),
+        Text::Metadata => Some("Metadata ({n})"),
+        Text::ServerUnread => Some("{server} ({n} ongelezen)"),
+        Text::TimestampMissing => Some("De server stuurde geen tijd, dus dit is wanneer de gebeurtenis binnenkwam"),
+        Text::TimestampFuture => Some("De server stuurde een tijd in de toekomst, dus dit is wanneer de gebeurtenis binnenkwam"),
+        Text::SortNewest => Some("Nieuwste eerst"),
+        Text::SortSeverity => Some("Ernstigste eerst"),
+        Text::SortServer => Some("Per server"),
+        Text::PolledAgo => Some("{server}: {time}"),
+        Text::AuthFailed => Some("{server}: authenticatie mislukt (gepeild {time})"),
+        Text::AuthFailedHint => Some("Controleer het token of de clientgegevens van deze server"),
+        Text::Offline => Some("{server}: offline (gepeild {time})"),
+        Text::TestConnection => Some("Testen"),
+        Text::Reachable => Some("✔ Bereikbaar ({n} gebeurtenis(sen))"),
+        Text::TestFailed => Some("✖ Mislukt"),
+
+        Text::SnoozeAction => Some("Laten sluimeren"),
+        Text::OpenAction => Some("Openen"),
+        Text::FloodTitle => Some("Stortvloed aan meldingen onderdrukt ({n} verborgen)"),
+        Text::FloodMessage => Some("Er kwamen te veel meldingen tegelijk binnen, dus sommige zijn niet getoond. De gebeurtenissen zelf staan nog in de lijst."),
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines the languages that the client can be shown in.
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumDebug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Lang {
+    /// English, which every text is available in.
+    #[default]
+    English,
+    /// Dutch.
+    Dutch,
+}
+impl Lang {
+    /// Finds the language that matches a POSIX-style locale (e.g., `nl_NL.UTF-8`).
+    ///
+    /// # Arguments
+    /// - `locale`: The locale to match.
+    ///
+    /// # Returns
+    /// The matching Lang, or [`None`] if we don't speak the locale's language.
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let lang: &str = locale.split(['_', '-', '.', '@']).next().unwrap_or(locale);
+        match lang.to_ascii_lowercase().as_str() {
+            "en" => Some(Self::English),
+            "nl" => Some(Self::Dutch),
+            _ => None,
+        }
+    }
+
+    /// Decides which language to show the client in.
+    ///
+    /// # Arguments
+    /// - `configured`: The language that the user chose in the config, if any.
+    ///
+    /// # Returns
+    /// The `configured` language if given, or else the one from the OS' locale (as given by `LC_ALL`, `LC_MESSAGES` or `LANG`),
+    /// falling back to English.
+    pub fn resolve(configured: Option<Self>) -> Self {
+        if let Some(lang) = configured {
+            return lang;
+        }
+        LOCALE_VARS
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|locale| !locale.is_empty())
+            .and_then(|locale| Self::from_locale(&locale))
+            .unwrap_or_default()
+    }
+
+    /// Returns a text in this language.
+    ///
+    /// Some texts have placeholders (e.g., `{n}`), which the caller should replace.
+    ///
+    /// # Arguments
+    /// - `text`: The [`Text`] to look up.
+    ///
+    /// # Returns
+    /// The text in this language, or in English if it hasn't been translated yet.
+    pub fn tr(&self, text: Text) -> &'static str {
+        let translated: Option<&'static str> = match self {
+            Self::English => None,
+            Self::Dutch => dutch(text),
+        };
+        translated.unwrap_or_else(|| english(text))
+    }
+}
+
+
+
+/// Defines the texts that can be translated.
+#[derive(Clone, Copy, Debug, EnumDebug, Eq, PartialEq)]
+pub enum Text {
+    /// The tray menu item that opens the window.
+    MenuOpen,
+    /// The tray menu item that toggles muting.
+    MenuMute,
+    /// The tray submenu with the durations to mute for.
+    MenuMuteFor,
+    /// The choice in [`Text::MenuMuteFor`] that mutes for 15 minutes.
+    MenuMuteFor15Min,
+    /// The choice in [`Text::MenuMuteFor`] that mutes for an hour.
+    MenuMuteFor1Hour,
+    /// The choice in [`Text::MenuMuteFor`] that mutes for four hours.
+    MenuMuteFor4Hours,
+    /// The choice in [`Text::MenuMuteFor`] that mutes until the next morning.
+    MenuMuteForTomorrow,
+    /// The tray menu item that polls all servers.
+    MenuRefresh,
+    /// The tray menu item that acknowledges the newest unread event.
+    MenuAcknowledgeNewest,
+    /// The tray submenu with the least severe events to notify about.
+    MenuMinSeverity,
+    /// The choice in [`Text::MenuMinSeverity`] for [`Severity::Info`](crate::event::Severity::Info).
+    MenuSeverityInfo,
+    /// The choice in [`Text::MenuMinSeverity`] for [`Severity::Warning`](crate::event::Severity::Warning).
+    MenuSeverityWarning,
+    /// The choice in [`Text::MenuMinSeverity`] for [`Severity::Error`](crate::event::Severity::Error).
+    MenuSeverityError,
+    /// The choice in [`Text::MenuMinSeverity`] for [`Severity::Critical`](crate::event::Severity::Critical).
+    MenuSeverityCritical,
+    /// The tray submenu with the server dashboards.
+    MenuDashboards,
+    /// The tray menu item that closes the app.
+    MenuExit,
+
+    /// The button that switches the window to the mini layout.
+    MiniMode,
+    /// The button that switches the window back to the full layout.
+    FullMode,
+    /// The toggle for the panel with servers.
+    Servers,
+    /// The tab of the event list that shows the events of all servers.
+    AllServers,
+    /// The placeholder of the box that filters the event list.
+    SearchHint,
+    /// The button that empties the box that filters the event list.
+    SearchClear,
+    /// The toggle for the panel with snoozed event titles.
+    Snoozed,
+    /// The button that silences all notifications.
+    SilenceAll,
+    /// Explains [`Text::SilenceAll`].
+    SilenceAllHint,
+    /// The banner shown while silenced.
+    Silenced,
+    /// The banner shown while silenced until `{time}`.
+    SilencedUntil,
+    /// The button that un-silences notifications.
+    Unsilence,
+    /// Tells that the settings could not be saved because of `{err}`.
+    SyncError,
+    /// Tells that the settings are not saved at all, because `{dir}` is not writable.
+    NotPersistent,
+    /// Lists the `{servers}` whose certificates aren't verified.
+    Insecure,
+    /// Explains [`Text::Insecure`].
+    InsecureHint,
+    /// Tells that notifications are muted.
+    Muted,
+    /// Tells that notifications are not muted.
+    Unmuted,
+    /// Tells that the OS is in do-not-disturb mode.
+    OsDnd,
+    /// Explains [`Text::OsDnd`].
+    OsDndHint,
+    /// Explains the activity sparkline, which shows `{n}` events with at most `{max}` in a minute.
+    Activity,
+    /// Tells that `{n}` events are selected in the list.
+    Selected,
+    /// The button that marks the selected events as read.
+    MarkRead,
+    /// The button that deletes the selected events.
+    Delete,
+    /// The button that copies the selected events to the clipboard.
+    CopyJson,
+    /// Shown instead of the event list while it's empty.
+    NoEvents,
+    /// The label of an event's timestamp in its details.
+    DetailTime,
+    /// The label of an event's severity in its details.
+    DetailSeverity,
+    /// The label of the server that reported an event in its details.
+    DetailServer,
+    /// The label of an event's ID in its details.
+    DetailId,
+    /// The label of an event's link in its details.
+    DetailLink,
+    /// Shown instead of the list of servers while it's empty.
+    NoServers,
+    /// The button that unsnoozes an event title.
+    Unsnooze,
+    /// Explains [`Text::Unsnooze`].
+    UnsnoozeHint,
+    /// The button that unsnoozes all event titles.
+    UnsnoozeAll,
+    /// Shown instead of the list of snoozed event titles while it's empty.
+    NoSnoozed,
+    /// Tells that a title is snoozed until `{time}`.
+    SnoozedUntil,
+    /// Tells that a title is snoozed until the app restarts.
+    SnoozedUntilRestart,
+    /// Tells that a title is snoozed until the user unsnoozes it.
+    SnoozedUntilUnsnoozed,
+    /// The title of the dialog that asks what to do with an imported config file.
+    ImportTitle,
+    /// Asks whether to import the config file at `{path}`, which has `{n}` servers.
+    ImportQuestion,
+    /// Explains the choices in the import dialog.
+    ImportHint,
+    /// The choice in the import dialog that merges the imported config into the current one.
+    ImportMerge,
+    /// The choice in the import dialog that replaces the current config with the imported one.
+    ImportReplace,
+    /// The button that closes a dialog without doing anything.
+    Cancel,
+    /// The title of the dialog that shows an error.
+    ErrorTitle,
+    /// The button that dismisses a dialog.
+    Ok,
+    /// The choice of how long to snooze a title that snoozes it for an hour.
+    SnoozeHour,
+    /// The choice of how long to snooze a title that snoozes it for a day.
+    SnoozeDay,
+    /// The choice of how long to snooze a title that snoozes it until the app restarts.
+    SnoozeRestart,
+    /// Tells that something happened `{n}` seconds ago.
+    AgoSeconds,
+    /// Tells that something happened `{n}` minutes ago.
+    AgoMinutes,
+    /// Tells that something happened `{n}` hours ago.
+    AgoHours,
+    /// Tells that something never happened (yet).
+    Never,
+    /// Tells that the selected events could not be copied because of `{err}`.
+    SerializeFailed,
+    /// The action that pins an event.
+    Pin,
+    /// Explains the button that pins an event.
+    PinHint,
+    /// The action that unpins an event.
+    Unpin,
+    /// The toggle that pins the event in the details.
+    Pinned,
+    /// Explains [`Text::Pinned`].
+    PinnedHint,
+    /// Explains snoozing an event's title in its details.
+    SnoozeThisHint,
+    /// The button that closes the details of an event.
+    CloseDetail,
+    /// The header of the `{n}` metadata fields of an event in its details.
+    Metadata,
+    /// The header of the events of `{server}` in the event list, of which `{n}` are unread.
+    ServerUnread,
+    /// Explains that an event's time is when it was received, as the server sent none.
+    TimestampMissing,
+    /// Explains that an event's time is when it was received, as the server sent one in the future.
+    TimestampFuture,
+    /// The order of the event list that shows the newest events first.
+    SortNewest,
+    /// The order of the event list that shows the most severe events first.
+    SortSeverity,
+    /// The order of the event list that groups events by server.
+    SortServer,
+    /// Tells in the status bar that `{server}` was last polled `{time}`.
+    PolledAgo,
+    /// Tells in the status bar that we couldn't authenticate with `{server}`, which was last polled `{time}`.
+    AuthFailed,
+    /// Explains [`Text::AuthFailed`].
+    AuthFailedHint,
+    /// Tells in the status bar that `{server}` is unreachable, which was last polled `{time}`.
+    Offline,
+    /// The button that tests the connection to a server.
+    TestConnection,
+    /// Tells that a server could be polled, and returned `{n}` events.
+    Reachable,
+    /// Tells that a server could not be polled.
+    TestFailed,
+
+    /// The action on a notification that snoozes its title.
+    SnoozeAction,
+    /// The action on a notification that opens the window at its event (which is what clicking the notification does).
+    OpenAction,
+    /// The title of the notice that `{n}` notifications were hidden.
+    FloodTitle,
+    /// The message of the notice that notifications were hidden.
+    FloodMessage,
+}
+impl Text {
+    /// Returns the text that describes showing (or notifying about) the events of at least the given severity.
+    ///
+    /// These are the choices in the tray icon's "Notify about" submenu, and in the window's severity filter.
+    ///
+    /// # Arguments
+    /// - `severity`: The least severe [`Severity`] to include.
+    ///
+    /// # Returns
+    /// The matching Text.
+    #[inline]
+    pub fn min_severity(severity: Severity) -> Self {
+        match severity {
+            Severity::Info => Self::MenuSeverityInfo,
+            Severity::Warning => Self::MenuSeverityWarning,
+            Severity::Error => Self::MenuSeverityError,
+            Severity::Critical => Self::MenuSeverityCritical,
+        }
+    }
+
+    /// Returns the text that explains why the timestamp of an event was replaced.
+    ///
+    /// # Arguments
+    /// - `flag`: The [`TimestampFlag`] of the event.
+    ///
+    /// # Returns
+    /// The matching Text.
+    #[inline]
+    pub fn timestamp_flag(flag: TimestampFlag) -> Self {
+        match flag {
+            TimestampFlag::Missing => Self::TimestampMissing,
+            TimestampFlag::Future => Self::TimestampFuture,
+        }
+    }
+}
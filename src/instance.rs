@@ -0,0 +1,278 @@
+//  INSTANCE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 08:44:38
+//  Last edited:
+//    16 Oct 2026, 09:24:39
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Makes sure that only one instance of the app runs per config
+//!   directory.
+//!   
+//!   The running instance holds a lock file that names a local socket on
+//!   which it listens. A second instance asks it to open its window
+//!   through that socket and then exits. If nobody answers, the lock is
+//!   left behind by a crashed instance and is taken over.
+//
+
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::io::{BufRead as _, BufReader, ErrorKind, Read as _, Write as _};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{error, fs, process, thread};
+
+use log::{debug, info, warn};
+
+use crate::app::tray::TrayIconMenuItem;
+use crate::app::{AppEvent, AppEventLoopProxy};
+
+
+/***** CONSTANTS *****/
+/// The name of the lock file in the config directory.
+const LOCK_FILE_NAME: &str = "instance.lock";
+/// How long we give another instance to answer before we decide that it's not running anymore.
+const SIGNAL_TIMEOUT: Duration = Duration::from_millis(500);
+/// The maximum length of a line that we read from the socket.
+const MAX_LINE_LEN: u64 = 256;
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from claiming the single-instance lock.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to create the config directory.
+    ConfigDirCreate { path: PathBuf, err: std::io::Error },
+    /// Failed to open the local socket.
+    ListenerCreate { err: std::io::Error },
+    /// Failed to create the lock file.
+    LockCreate { path: PathBuf, err: std::io::Error },
+    /// Failed to remove a lock file left behind by a crashed instance.
+    LockRemove { path: PathBuf, err: std::io::Error },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            ConfigDirCreate { path, .. } => write!(f, "Failed to create config directory '{}'", path.display()),
+            ListenerCreate { .. } => write!(f, "Failed to open local socket for other instances"),
+            LockCreate { path, .. } => write!(f, "Failed to create lock file '{}'", path.display()),
+            LockRemove { path, .. } => write!(f, "Failed to remove stale lock file '{}'", path.display()),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            ConfigDirCreate { err, .. } => Some(err),
+            ListenerCreate { err } => Some(err),
+            LockCreate { err, .. } => Some(err),
+            LockRemove { err, .. } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Atomically creates the lock file, failing if it already exists.
+///
+/// The contents are written to a temporary file first, such that other instances never see a half-written lock.
+///
+/// # Arguments
+/// - `path`: The path of the lock file.
+/// - `contents`: What to write in it.
+///
+/// # Errors
+/// This function errors with [`ErrorKind::AlreadyExists`] if the lock file exists, or with something else if we failed to write it.
+fn publish(path: &Path, contents: &str) -> Result<(), std::io::Error> {
+    let tmp: PathBuf = path.with_extension(format!("lock.{}", process::id()));
+    fs::write(&tmp, contents)?;
+    let res: Result<(), std::io::Error> = fs::hard_link(&tmp, path);
+    let _ = fs::remove_file(&tmp);
+    res
+}
+
+/// Asks the instance that holds the given lock file to open its window.
+///
+/// # Arguments
+/// - `path`: The path of the lock file.
+///
+/// # Returns
+/// True if the instance answered, or false if it didn't (and the lock is thus stale).
+fn signal(path: &Path) -> bool {
+    // Find out where the other instance listens
+    let raw: String = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) => {
+            debug!("Failed to read lock file '{}': {err}", path.display());
+            return false;
+        },
+    };
+    let Some((port, nonce)): Option<(u16, &str)> = raw.trim().split_once(' ').and_then(|(port, nonce)| Some((port.parse().ok()?, nonce))) else {
+        debug!("Lock file '{}' is malformed", path.display());
+        return false;
+    };
+
+    // Knock on the door and see if the right instance opens it
+    let mut stream: TcpStream = match TcpStream::connect_timeout(&SocketAddr::from((Ipv4Addr::LOCALHOST, port)), SIGNAL_TIMEOUT) {
+        Ok(stream) => stream,
+        Err(err) => {
+            debug!("Failed to reach instance on port {port}: {err}");
+            return false;
+        },
+    };
+    let _ = stream.set_read_timeout(Some(SIGNAL_TIMEOUT));
+    if let Err(err) = writeln!(stream, "{nonce}") {
+        debug!("Failed to signal instance on port {port}: {err}");
+        return false;
+    }
+    let mut reply: String = String::new();
+    match BufReader::new(stream.take(MAX_LINE_LEN)).read_line(&mut reply) {
+        Ok(_) => reply.trim() == "ok",
+        Err(err) => {
+            debug!("Instance on port {port} did not answer: {err}");
+            false
+        },
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Proves that this is the only running instance for some config directory.
+///
+/// The lock is released when this is dropped.
+#[derive(Debug)]
+pub struct InstanceLock {
+    /// The path of the lock file.
+    path:     PathBuf,
+    /// A random-ish string that other instances have to send to prove that they read our lock file.
+    nonce:    String,
+    /// The socket on which other instances reach us, until we [listen](InstanceLock::listen()) on it.
+    listener: Option<TcpListener>,
+}
+impl InstanceLock {
+    /// Tries to claim the lock for the given config directory.
+    ///
+    /// If another instance holds it, that instance is asked to open its window instead. Locks left behind by crashed instances are
+    /// taken over.
+    ///
+    /// # Arguments
+    /// - `config_dir`: The directory where the app's persistent config is stored.
+    ///
+    /// # Returns
+    /// A new InstanceLock, or [`None`] if another instance is already running.
+    ///
+    /// # Errors
+    /// This function errors if we failed to open the socket or to create the lock file.
+    pub fn acquire(config_dir: &Path) -> Result<Option<Self>, Error> {
+        if !config_dir.exists() {
+            debug!("Config directory '{}' does not exist, creating...", config_dir.display());
+            if let Err(err) = fs::create_dir_all(config_dir) {
+                return Err(Error::ConfigDirCreate { path: config_dir.into(), err });
+            }
+        }
+
+        // Open the socket that we'll announce in the lock file
+        let listener: TcpListener = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+            Ok(listener) => listener,
+            Err(err) => return Err(Error::ListenerCreate { err }),
+        };
+        let port: u16 = match listener.local_addr() {
+            Ok(addr) => addr.port(),
+            Err(err) => return Err(Error::ListenerCreate { err }),
+        };
+        let nonce: String = format!("{:x}-{:x}", process::id(), SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos());
+
+        // Claim the lock, taking it over if it turns out to be stale
+        let path: PathBuf = config_dir.join(LOCK_FILE_NAME);
+        let contents: String = format!("{port} {nonce}\n");
+        let mut res: Result<(), std::io::Error> = publish(&path, &contents);
+        if matches!(&res, Err(err) if err.kind() == ErrorKind::AlreadyExists) {
+            if signal(&path) {
+                info!("Another instance is already running; asked it to open its window");
+                return Ok(None);
+            }
+            warn!("Removing lock file '{}' of an instance that is no longer running", path.display());
+            if let Err(err) = fs::remove_file(&path) {
+                if err.kind() != ErrorKind::NotFound {
+                    return Err(Error::LockRemove { path, err });
+                }
+            }
+            res = publish(&path, &contents);
+        }
+        match res {
+            Ok(_) => {
+                debug!("Claimed instance lock '{}' (listening on port {port})", path.display());
+                Ok(Some(Self { path, nonce, listener: Some(listener) }))
+            },
+            Err(err) => Err(Error::LockCreate { path, err }),
+        }
+    }
+
+    /// Starts answering other instances, opening the main window whenever they ask for it.
+    ///
+    /// # Arguments
+    /// - `proxy`: An [`AppEventLoopProxy`] to ask the main loop to open the window with.
+    pub fn listen(&mut self, proxy: AppEventLoopProxy) {
+        let Some(listener) = self.listener.take() else { return };
+        let nonce: String = self.nonce.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream: TcpStream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        debug!("Failed to accept connection from other instance: {err}");
+                        continue;
+                    },
+                };
+
+                // Only listen to instances that could read our lock file
+                let _ = stream.set_read_timeout(Some(SIGNAL_TIMEOUT));
+                let mut line: String = String::new();
+                if let Err(err) = BufReader::new((&stream).take(MAX_LINE_LEN)).read_line(&mut line) {
+                    debug!("Failed to read from other instance: {err}");
+                    continue;
+                }
+                if line.trim() != nonce {
+                    warn!("Ignoring connection on the instance socket that does not know our lock file");
+                    continue;
+                }
+                let _ = writeln!(stream, "ok");
+
+                // Do as if the user clicked "Open" in the tray menu
+                info!("Another instance was started; opening the window instead");
+                if proxy.send_event(AppEvent::Action(TrayIconMenuItem::Open)).is_err() {
+                    // The event loop is gone, so we're shutting down anyway
+                    break;
+                }
+            }
+        });
+    }
+}
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        // Make sure it's still ours, in case it was taken over in the meantime
+        match fs::read_to_string(&self.path) {
+            Ok(raw) if raw.trim().ends_with(&self.nonce) => {
+                debug!("Releasing instance lock '{}'", self.path.display());
+                if let Err(err) = fs::remove_file(&self.path) {
+                    warn!("Failed to remove lock file '{}': {err}", self.path.display());
+                }
+            },
+            _ => {},
+        }
+    }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    02 Apr 2024, 15:09:15
 //  Last edited:
-//    01 May 2024, 19:51:50
+//    16 Oct 2026, 10:07:48
 //  Auto updated?
 //    Yes
 //
@@ -14,8 +14,28 @@
 //!   
 //!   This library bundles the core functionality of the client as a separate
 //!   library.
+//!   
+//!   The supported entry points are [`app::App`], which runs the client with
+//!   a window and a tray icon, and [`app::headless::Headless`], which runs it
+//!   without either. Both load their state from a config directory (see
+//!   [`state::AppState`]) and keep running until asked to exit.
 //
 
 // Declare modules
 pub mod app;
+pub mod clock;
+#[cfg(unix)]
+pub mod control;
+pub mod crash;
+pub mod diagnostics;
+pub mod dnd;
+pub mod event;
+pub mod export;
+pub mod i18n;
+pub mod instance;
+pub mod metrics;
+pub mod notify;
+pub mod poller;
+pub mod reload;
 pub mod state;
+pub mod wake;
@@ -4,7 +4,7 @@
 //  Created:
 //    02 Apr 2024, 15:08:52
 //  Last edited:
-//    01 May 2024, 19:51:49
+//    16 Oct 2026, 10:28:38
 //  Auto updated?
 //    Yes
 //
@@ -13,14 +13,22 @@
 //
 
 use std::ffi::OsString;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use error_trace::ErrorTrace as _;
 use humanlog::{DebugMode, HumanLogger};
 use lazy_static::lazy_static;
 use log::{error, info};
-use server_events::app::app::App;
+use serializable::Serializable as _;
+use server_events::app::headless::Headless;
+use server_events::app::App;
+#[cfg(unix)]
+use server_events::control;
+use server_events::instance::InstanceLock;
+use server_events::poller::Replay;
+use server_events::state::{AppState, ConfigFile, CONFIG_FILE_NAME};
+use server_events::{crash, diagnostics};
 
 
 /***** STATICS *****/
@@ -47,6 +55,73 @@ struct Arguments {
     /// The persistent config directory that we use to keep settings and stuff in.
     #[clap(short, long, default_value = DEFAULT_CONFIG_DIR.as_os_str())]
     config_dir: PathBuf,
+
+    /// If given, prompts for the token of the server with the given name, stores it in the OS secret store and exits.
+    #[clap(long, value_name = "SERVER")]
+    set_token: Option<String>,
+    /// If given, prints the event history as JSON to stdout and exits. The history is asked from the running app over its control
+    /// socket, if it has one; otherwise, the history that the app stored last is printed.
+    #[clap(long)]
+    dump_events: bool,
+    /// If given, runs without any windows or tray icon until interrupted (e.g., with Ctrl+C). Notifications go to the notifiers in
+    /// the config file, so configure e.g. a webhook for machines without a desktop.
+    #[clap(long)]
+    headless: bool,
+    /// If given, replays the events in this file (one JSON event per line) instead of polling the servers.
+    #[clap(long, value_name = "FILE")]
+    replay: Option<PathBuf>,
+    /// How much faster than their timestamps suggest to replay events with `--replay`. Use 0 to replay them all at once.
+    #[clap(long, value_name = "FACTOR", default_value = "1.0", requires = "replay")]
+    replay_speed: f64,
+
+    /// Something else to do instead of running the app, if any.
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+/// Defines the things that this binary can do besides running the app.
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Checks the config file in the config directory, prints what's wrong with it (if anything) and exits with 1 if it's invalid.
+    CheckConfig,
+    /// Prints information about the platform and the backends that the app needs, for bug reports, and exits.
+    Diagnostics,
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Checks the given config file and prints a report of what's wrong with it.
+///
+/// # Arguments
+/// - `path`: The path of the config file to check.
+///
+/// # Returns
+/// The exit code of the process, which is `0` if the config is valid and `1` otherwise.
+fn check_config(path: &Path) -> i32 {
+    let config: ConfigFile = match ConfigFile::from_path(path) {
+        Ok(config) => config,
+        Err(err) => {
+            println!("Config file '{}' is invalid:", path.display());
+            println!(" - {}", err.trace());
+            return 1;
+        },
+    };
+    match config.validate() {
+        Ok(_) => {
+            println!("Config file '{}' is valid ({} server(s))", path.display(), config.servers.len());
+            0
+        },
+        Err(errs) => {
+            println!("Config file '{}' has {} problem(s):", path.display(), errs.len());
+            for err in &errs {
+                println!(" - {err}");
+            }
+            1
+        },
+    }
 }
 
 
@@ -64,11 +139,117 @@ fn main() {
     }
     info!("{} v{}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
 
+    // Report on the environment or the config instead of running the app, if requested
+    match args.command {
+        Some(Command::CheckConfig) => std::process::exit(check_config(&args.config_dir.join(CONFIG_FILE_NAME))),
+        Some(Command::Diagnostics) => {
+            print!("{}", diagnostics::report(&args.config_dir));
+            return;
+        },
+        None => {},
+    }
+
+    // Store a token instead of running the app, if requested
+    if let Some(server) = &args.set_token {
+        let state: AppState = match AppState::new(args.config_dir.clone()) {
+            Ok(state) => state,
+            Err(err) => {
+                error!("{}", err.trace());
+                crash::report(&args.config_dir, &err);
+                std::process::exit(1);
+            },
+        };
+        let token: String = match rpassword::prompt_password(format!("Token for server '{server}': ")) {
+            Ok(token) => token,
+            Err(err) => {
+                error!("Failed to read token from terminal: {err}");
+                crash::report(&args.config_dir, &err);
+                std::process::exit(1);
+            },
+        };
+        if let Err(err) = state.store_token(server, token.trim()) {
+            error!("{}", err.trace());
+            crash::report(&args.config_dir, &err);
+            std::process::exit(1);
+        }
+        println!("Stored token for server '{server}' in the OS secret store");
+        return;
+    }
+
+    // Print the events instead of running the app, if requested
+    if args.dump_events {
+        let state: AppState = match AppState::new(args.config_dir.clone()) {
+            Ok(state) => state,
+            Err(err) => {
+                error!("{}", err.trace());
+                crash::report(&args.config_dir, &err);
+                std::process::exit(1);
+            },
+        };
+
+        // The running app knows best (e.g., if it cannot store its events), so ask it if we can
+        #[cfg(unix)]
+        let live: Option<String> = state.access(|state| state.control_socket.clone()).and_then(|path| match control::request(&path, "events") {
+            Ok(events) => Some(events),
+            Err(err) => {
+                info!("{} (showing stored events instead)", err.trace());
+                None
+            },
+        });
+        #[cfg(not(unix))]
+        let live: Option<String> = None;
+        let events: String = match live {
+            Some(events) => events,
+            None => match state.access(|state| serde_json::to_string(&state.events)) {
+                Ok(events) => events,
+                Err(err) => {
+                    error!("Failed to serialize events: {err}");
+                    crash::report(&args.config_dir, &err);
+                    std::process::exit(1);
+                },
+            },
+        };
+        println!("{events}");
+        return;
+    }
+
+    // Make sure we're the only one; otherwise, the other instance takes it from here
+    let instance: InstanceLock = match InstanceLock::acquire(&args.config_dir) {
+        Ok(Some(instance)) => instance,
+        Ok(None) => return,
+        Err(err) => {
+            error!("{}", err.trace());
+            crash::report(&args.config_dir, &err);
+            std::process::exit(1);
+        },
+    };
+
+    // Run without a GUI, if requested
+    let replay: Option<Replay> = args.replay.map(|path| Replay { path, speed: args.replay_speed });
+    if args.headless {
+        let app: Headless = match Headless::new(args.config_dir.clone(), instance, replay) {
+            Ok(app) => app,
+            Err(err) => {
+                error!("{}", err.trace());
+                crash::report(&args.config_dir, &err);
+                std::process::exit(1);
+            },
+        };
+        if let Err(err) = app.run() {
+            error!("{}", err.trace());
+            crash::report(&args.config_dir, &err);
+            std::process::exit(1);
+        }
+        info!("Bye.");
+        return;
+    }
+
     // Build the app
-    let app: App = match App::new(args.config_dir) {
+    let app: App = match App::new(args.config_dir.clone(), instance, replay) {
         Ok(app) => app,
         Err(err) => {
             error!("{}", err.trace());
+            crash::report(&args.config_dir, &err);
             std::process::exit(1);
         },
     };
@@ -76,6 +257,7 @@ fn main() {
     // Then run it for all it's worth
     if let Err(err) = app.run() {
         error!("{}", err.trace());
+        crash::report(&args.config_dir, &err);
         std::process::exit(1);
     }
 
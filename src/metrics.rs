@@ -0,0 +1,250 @@
+//  METRICS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 09:02:26
+//  Last edited:
+//    16 Oct 2026, 12:02:05
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a tiny local HTTP endpoint that tells whether the client
+//!   itself is healthy, and that exposes some basic statistics in
+//!   Prometheus' text format.
+//
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Result as FResult, Write as _};
+use std::io::{BufRead as _, BufReader, Read as _, Write as _};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+use std::{error, thread};
+
+use log::{debug, info};
+
+use crate::app::{AppEvent, AppEventLoopProxy};
+use crate::state::AppState;
+
+
+/***** CONSTANTS *****/
+/// How long we give the event loop to answer before we consider it stuck.
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(1);
+/// How long we wait for a client to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// The maximum length of the request line that we read.
+const MAX_LINE_LEN: u64 = 1024;
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from the metrics endpoint.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to listen on the configured address.
+    Bind { addr: SocketAddr, err: std::io::Error },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            Bind { addr, .. } => write!(f, "Failed to serve metrics on '{addr}'"),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            Bind { err, .. } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Escapes a value for use as a label value in Prometheus' text format.
+///
+/// # Arguments
+/// - `value`: The value to escape.
+///
+/// # Returns
+/// The value with backslashes, quotes and newlines escaped.
+fn escape(value: &str) -> String { value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n") }
+
+/// Asks the event loop whether it's still responsive.
+///
+/// # Arguments
+/// - `proxy`: An [`AppEventLoopProxy`] to reach the event loop with.
+///
+/// # Returns
+/// True if the event loop answered in time, or false otherwise.
+fn is_alive(proxy: &AppEventLoopProxy) -> bool {
+    let (reply, answer): (Sender<()>, Receiver<()>) = mpsc::channel();
+    if proxy.send_event(AppEvent::HealthCheck { reply }).is_err() {
+        return false;
+    }
+    answer.recv_timeout(HEALTH_TIMEOUT).is_ok()
+}
+
+/// Renders the statistics of the client in Prometheus' text format.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] to describe.
+/// - `alive`: Whether the event loop is responsive.
+///
+/// # Returns
+/// The body of a response to `/metrics`.
+fn render(state: &AppState, alive: bool) -> String {
+    let mut body: String = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, values: Vec<(Option<&str>, f64)>| {
+        let _ = writeln!(body, "# HELP {name} {help}");
+        let _ = writeln!(body, "# TYPE {name} {kind}");
+        for (server, value) in values {
+            match server {
+                Some(server) => {
+                    let _ = writeln!(body, "{name}{{server=\"{}\"}} {value}", escape(server));
+                },
+                None => {
+                    let _ = writeln!(body, "{name} {value}");
+                },
+            }
+        }
+    };
+
+    // Take a snapshot such that we don't hold the lock while rendering
+    let (servers, offline, last_poll, ingested, events): Snapshot = state.access(|state| {
+        let mut events: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+        for event in &state.events {
+            let (total, unread): &mut (usize, usize) = events.entry(event.source.clone()).or_default();
+            *total += 1;
+            *unread += usize::from(!event.read);
+        }
+        (
+            state.servers.iter().map(|server| server.name.clone()).collect(),
+            state.servers.iter().map(|server| state.offline.contains(&server.name)).collect(),
+            state.servers.iter().map(|server| state.last_poll.get(&server.name).map(|time| time.timestamp_millis() as f64 / 1000.0)).collect(),
+            state.ingested.iter().map(|(server, count)| (server.clone(), *count)).collect(),
+            events,
+        )
+    });
+
+    metric("server_events_up", "gauge", "Whether the event loop of the client is responsive.", vec![(None, f64::from(u8::from(alive)))]);
+    metric(
+        "server_events_ingested_total",
+        "counter",
+        "The number of new events that a server reported since the client started.",
+        ingested.iter().map(|(server, count)| (Some(server.as_str()), *count as f64)).collect(),
+    );
+    metric(
+        "server_events_events",
+        "gauge",
+        "The number of events of a server that the client keeps.",
+        events.iter().map(|(server, (total, _))| (Some(server.as_str()), *total as f64)).collect(),
+    );
+    metric(
+        "server_events_unread",
+        "gauge",
+        "The number of events of a server that the user hasn't seen yet.",
+        events.iter().map(|(server, (_, unread))| (Some(server.as_str()), *unread as f64)).collect(),
+    );
+    metric(
+        "server_events_server_online",
+        "gauge",
+        "Whether the last poll of a server succeeded.",
+        servers.iter().zip(&offline).map(|(server, offline)| (Some(server.as_str()), f64::from(u8::from(!offline)))).collect(),
+    );
+    metric(
+        "server_events_last_poll_timestamp_seconds",
+        "gauge",
+        "When a server was last polled successfully, as a Unix timestamp.",
+        servers.iter().zip(&last_poll).filter_map(|(server, time)| Some((Some(server.as_str()), (*time)?))).collect(),
+    );
+    body
+}
+
+/// Answers a single request to the endpoint.
+///
+/// # Arguments
+/// - `stream`: The [`TcpStream`] of the client.
+/// - `state`: The [`AppState`] to describe.
+/// - `proxy`: An [`AppEventLoopProxy`] to check whether the event loop is responsive.
+///
+/// # Errors
+/// This function errors if we failed to talk to the client.
+fn handle(mut stream: TcpStream, state: &AppState, proxy: &AppEventLoopProxy) -> Result<(), std::io::Error> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut line: String = String::new();
+    BufReader::new((&stream).take(MAX_LINE_LEN)).read_line(&mut line)?;
+
+    // Only the method and path matter to us
+    let mut parts = line.split_whitespace();
+    let (status, content_type, body): (&str, &str, String) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/healthz")) => {
+            if is_alive(proxy) {
+                ("200 OK", "text/plain", "ok\n".into())
+            } else {
+                ("503 Service Unavailable", "text/plain", "event loop is not responding\n".into())
+            }
+        },
+        (Some("GET"), Some("/metrics")) => ("200 OK", "text/plain; version=0.0.4", render(state, is_alive(proxy))),
+        (Some("GET"), _) => ("404 Not Found", "text/plain", "not found\n".into()),
+        _ => ("405 Method Not Allowed", "text/plain", "method not allowed\n".into()),
+    };
+    debug!("Answering metrics request '{}' with {status}", line.trim());
+    write!(stream, "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len())
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// A snapshot of the [`AppState`] with everything that [`render()`] reports on.
+///
+/// These are the names of the servers, whether they're offline, when they were last polled (in seconds since the epoch), how many
+/// events were ingested per server and how many events (in total and unread) we keep per server.
+type Snapshot = (Vec<String>, Vec<bool>, Vec<Option<f64>>, BTreeMap<String, u64>, BTreeMap<String, (usize, usize)>);
+
+
+
+
+
+/***** LIBRARY *****/
+/// Starts serving `/healthz` and `/metrics` on the given address, on a thread of its own.
+///
+/// `/healthz` answers with 200 if the event loop is responsive, and with 503 otherwise. `/metrics` describes the number of events,
+/// and the state of every server, in Prometheus' text format.
+///
+/// # Arguments
+/// - `addr`: The address to listen on. This should be a local one, as there's no authentication.
+/// - `state`: The [`AppState`] to describe.
+/// - `proxy`: An [`AppEventLoopProxy`] to check whether the event loop is responsive.
+///
+/// # Errors
+/// This function errors if we failed to listen on the address.
+pub fn serve(addr: SocketAddr, state: AppState, proxy: AppEventLoopProxy) -> Result<(), Error> {
+    let listener: TcpListener = match TcpListener::bind(addr) {
+        Ok(listener) => listener,
+        Err(err) => return Err(Error::Bind { addr, err }),
+    };
+    info!("Serving health and metrics on 'http://{addr}'");
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let res: Result<(), std::io::Error> = match stream {
+                Ok(stream) => handle(stream, &state, &proxy),
+                Err(err) => Err(err),
+            };
+            if let Err(err) = res {
+                debug!("Failed to answer metrics request: {err}");
+            }
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,712 @@
+//  NOTIFY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 08:36:55
+//  Last edited:
+//    16 Oct 2026, 12:00:48
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines the backends that tell the user about new events, and a
+//!   registry that dispatches events to all of the configured ones.
+//!   
+//!   Note that the mute state is respected by the caller, not by the
+//!   individual notifiers.
+//
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{Debug, Display, Formatter, Result as FResult};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::{error, thread};
+
+use chrono::{DateTime, Local};
+use enum_debug::EnumDebug as _;
+use error_trace::ErrorTrace as _;
+use log::{debug, info, warn};
+use notify_rust::{Notification, NotificationHandle};
+use parking_lot::{Mutex, MutexGuard};
+use reqwest::blocking::Client;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use url::Url;
+
+use crate::app::{AppEvent, AppEventLoopProxy};
+use crate::clock::{Clock, SystemClock};
+use crate::event::{Event, Severity};
+use crate::i18n::{Lang, Text};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+
+    /// Builds an event to send to webhooks.
+    fn event() -> Event {
+        Event {
+            id: "test".into(),
+            timestamp: Local::now(),
+            severity: Severity::Info,
+            source: "test".into(),
+            title: "Test".into(),
+            message: String::new(),
+            url: None,
+            read: false,
+            pinned: false,
+            timestamp_flag: None,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn forward_does_not_wait_for_the_webhook() {
+        // A webhook that accepts connections but never answers
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url: Url = Url::parse(&format!("http://{}/hook", listener.local_addr().unwrap())).unwrap();
+        let mut notifiers: Notifiers = Notifiers::default();
+        notifiers.set_webhook(url).unwrap();
+
+        // Forwarding more than fits in the queue should neither block nor fail
+        let event: Event = event();
+        let start: Instant = Instant::now();
+        for _ in 0..2 * WEBHOOK_QUEUE_LEN {
+            notifiers.forward("test", &event);
+        }
+        assert!(start.elapsed() < Duration::from_secs(1), "forwarding took {:?}", start.elapsed());
+        drop(listener);
+    }
+
+    #[test]
+    fn notify_does_not_wait_for_the_webhook() {
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url: Url = Url::parse(&format!("http://{}/hook", listener.local_addr().unwrap())).unwrap();
+        let mut notifiers: Notifiers = Notifiers::default();
+        notifiers.register(Box::new(WebhookNotifier::new(url, WebhookFormat::Slack, DEFAULT_WEBHOOK_TEMPLATE.into()).unwrap()));
+
+        // Same as forwarding, notifying through a webhook only queues the event
+        let event: Event = event();
+        let start: Instant = Instant::now();
+        for _ in 0..2 * WEBHOOK_QUEUE_LEN {
+            notifiers.notify(&event, None);
+        }
+        assert!(start.elapsed() < Duration::from_secs(1), "notifying took {:?}", start.elapsed());
+        drop(listener);
+    }
+}
+
+
+/***** CONSTANTS *****/
+/// The template used by [`WebhookNotifier`]s if the user didn't specify one.
+const DEFAULT_WEBHOOK_TEMPLATE: &str = "[{severity}] {source}: {title}\n{body}";
+/// The maximum number of event titles listed in a [digest](digest()).
+const DIGEST_MAX_LINES: usize = 5;
+/// The window over which the rate of notifications is limited (see [`Notifiers::notify()`]).
+const FLOOD_WINDOW: Duration = Duration::from_secs(60);
+/// How many desktop notifications we wait for the user to act on at the same time. Every one of them takes a thread that blocks
+/// until the notification is closed, so any notifications beyond these are shown without actions.
+#[cfg(target_os = "linux")]
+const MAX_ACTION_LISTENERS: usize = 8;
+/// The identifier of the action on desktop notifications that snoozes the event's title.
+#[cfg(target_os = "linux")]
+const SNOOZE_ACTION: &str = "snooze";
+/// The identifier of the action on desktop notifications that opens the event in the window. Being the `default` action, it's what
+/// clicking the notification itself does.
+#[cfg(target_os = "linux")]
+const OPEN_ACTION: &str = "default";
+/// How often we try to send something to a webhook before giving up.
+const WEBHOOK_ATTEMPTS: usize = 3;
+/// How long we wait before trying to send something to a webhook again. Doubles with every attempt.
+const WEBHOOK_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// How long a single request to a webhook may take before we give up on it.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+/// How many events may wait to be sent to a webhook before we drop new ones (see [`WebhookNotifier`]).
+const WEBHOOK_QUEUE_LEN: usize = 256;
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from [`Notifier`]s.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to build the HTTP client of a webhook.
+    ClientCreate { err: reqwest::Error },
+    /// Failed to show a desktop notification.
+    DesktopShow { err: notify_rust::error::Error },
+    /// Failed to send an event to a webhook.
+    WebhookSend { url: Url, err: reqwest::Error },
+    /// A webhook responded with a non-success status code.
+    WebhookStatus { url: Url, status: reqwest::StatusCode },
+    /// Failed to spawn the thread that forwards events to a webhook.
+    WebhookThreadSpawn { url: Url, err: std::io::Error },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            ClientCreate { .. } => write!(f, "Failed to create HTTP client for webhook"),
+            DesktopShow { .. } => write!(f, "Failed to show desktop notification"),
+            WebhookSend { url, .. } => write!(f, "Failed to send event to webhook '{url}'"),
+            WebhookStatus { url, status } => write!(f, "Webhook '{}' responded with non-success status code {}", url, status.as_u16()),
+            WebhookThreadSpawn { url, .. } => write!(f, "Failed to spawn thread sending events to webhook '{url}'"),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            ClientCreate { err } => Some(err),
+            DesktopShow { err } => Some(err),
+            WebhookSend { err, .. } => Some(err),
+            WebhookStatus { .. } => None,
+            WebhookThreadSpawn { err, .. } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Summarizes a bunch of events from the same server in a single event, for in a digest notification.
+///
+/// # Arguments
+/// - `source`: The name of the server that the events came from.
+/// - `events`: The [`Event`]s to summarize. Should not be empty.
+/// - `now`: The time at which the digest is made.
+///
+/// # Returns
+/// A new [`Event`] with the highest severity of the `events`, a title like `"12 new events on prod, 2 critical"` and the titles
+/// of (the first few of) the events as message.
+pub fn digest(source: &str, events: &[Event], now: DateTime<Local>) -> Event {
+    let critical: usize = events.iter().filter(|event| event.severity == Severity::Critical).count();
+    let mut title: String = format!("{} new event{} on {}", events.len(), if events.len() == 1 { "" } else { "s" }, source);
+    if critical > 0 {
+        title.push_str(&format!(", {critical} critical"));
+    }
+    let mut message: String = events.iter().take(DIGEST_MAX_LINES).map(|event| format!("- {}", event.title)).collect::<Vec<String>>().join("\n");
+    if events.len() > DIGEST_MAX_LINES {
+        message.push_str(&format!("\n(and {} more)", events.len() - DIGEST_MAX_LINES));
+    }
+
+    Event {
+        id: format!("digest-{}", now.timestamp_millis()),
+        timestamp: now,
+        severity: events.iter().map(|event| event.severity).max().unwrap_or_default(),
+        source: source.into(),
+        title,
+        message,
+        url: None,
+        read: false,
+        pinned: false,
+        timestamp_flag: None,
+        metadata: BTreeMap::new(),
+    }
+}
+
+/// Builds an event that tells the user how many notifications were hidden because there were too many of them.
+///
+/// # Arguments
+/// - `hidden`: The number of notifications that were hidden.
+/// - `now`: The time at which the notice is made.
+/// - `lang`: The [`Lang`] to write the notice in.
+///
+/// # Returns
+/// A new [`Event`] describing the flood.
+fn flood_notice(hidden: usize, now: DateTime<Local>, lang: Lang) -> Event {
+    Event {
+        id: format!("flood-{}", now.timestamp_millis()),
+        timestamp: now,
+        severity: Severity::Warning,
+        source: env!("CARGO_PKG_NAME").into(),
+        title: lang.tr(Text::FloodTitle).replace("{n}", &hidden.to_string()),
+        message: lang.tr(Text::FloodMessage).into(),
+        url: None,
+        read: false,
+        pinned: false,
+        timestamp_flag: None,
+        metadata: BTreeMap::new(),
+    }
+}
+
+
+
+
+
+/// POSTs the given payload to a webhook, trying again a few times if that fails in a way that may be temporary.
+///
+/// Blocks for as long as that takes, so is only called from the thread of a [`WebhookNotifier`].
+///
+/// # Arguments
+/// - `client`: The [`Client`] to send requests with.
+/// - `url`: The URL of the webhook.
+/// - `payload`: The JSON to send.
+///
+/// # Errors
+/// This function errors if the last attempt failed to reach the webhook or got a non-success status code back.
+fn post(client: &Client, url: &Url, payload: &serde_json::Value) -> Result<(), Error> {
+    let mut delay: Duration = WEBHOOK_RETRY_DELAY;
+    let mut attempt: usize = 1;
+    loop {
+        let err: Error = match client.post(url.clone()).json(payload).send() {
+            Ok(res) if res.status().is_success() => return Ok(()),
+            // Other client errors won't go away by trying again
+            Ok(res) if res.status().is_client_error() && res.status() != StatusCode::TOO_MANY_REQUESTS => {
+                return Err(Error::WebhookStatus { url: url.clone(), status: res.status() });
+            },
+            Ok(res) => Error::WebhookStatus { url: url.clone(), status: res.status() },
+            Err(err) => Error::WebhookSend { url: url.clone(), err },
+        };
+        if attempt >= WEBHOOK_ATTEMPTS {
+            return Err(err);
+        }
+        debug!("{} (attempt {attempt}/{WEBHOOK_ATTEMPTS}; trying again in {}s)", err.trace(), delay.as_secs());
+        thread::sleep(delay);
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Describes the shape of the payload that a [`WebhookNotifier`] sends.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    /// `{ "text": ..., "event": { ... } }`, for custom receivers.
+    #[default]
+    Generic,
+    /// `{ "text": ... }`, for Slack incoming webhooks.
+    Slack,
+    /// `{ "content": ... }`, for Discord webhooks.
+    Discord,
+}
+
+
+
+/// Describes a [`Notifier`] in the config file.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotifierConfig {
+    /// Shows native desktop notifications (see [`DesktopNotifier`]).
+    Desktop,
+    /// POSTs events to some URL (see [`WebhookNotifier`]).
+    Webhook {
+        /// The URL to POST to.
+        url:      Url,
+        /// The shape of the payload.
+        #[serde(default)]
+        format:   WebhookFormat,
+        /// The message to send, where `{title}`, `{body}`, `{severity}`, `{source}` and `{icon}` are replaced by those of the event.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        template: Option<String>,
+    },
+}
+impl NotifierConfig {
+    /// Builds the [`Notifier`] that this config describes.
+    ///
+    /// # Arguments
+    /// - `proxy`: An [`AppEventLoopProxy`] to tell the main loop about what the user does with a notification.
+    ///
+    /// # Returns
+    /// A new [`Notifier`], boxed such that it can be registered in [`Notifiers`].
+    ///
+    /// # Errors
+    /// This function errors if we failed to set up the notifier (e.g., its HTTP client).
+    pub fn build(&self, proxy: &AppEventLoopProxy, lang: Lang) -> Result<Box<dyn Notifier>, Error> {
+        match self {
+            Self::Desktop => Ok(Box::new(DesktopNotifier::new(proxy.clone(), lang))),
+            Self::Webhook { url, format, template } => {
+                Ok(Box::new(WebhookNotifier::new(url.clone(), *format, template.clone().unwrap_or_else(|| DEFAULT_WEBHOOK_TEMPLATE.into()))?))
+            },
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Abstracts over some way of telling the user about new events.
+pub trait Notifier: Debug + Send + Sync {
+    /// Tells the user about a new event.
+    ///
+    /// # Arguments
+    /// - `event`: The new [`Event`] to notify about.
+    /// - `icon`: The icon that the user configured for the event, if any (see [`MutableAppState::icon()`](crate::state::MutableAppState::icon())).
+    ///
+    /// # Errors
+    /// This function errors if the backend failed to deliver the notification.
+    fn notify(&self, event: &Event, icon: Option<&str>) -> Result<(), Error>;
+}
+
+
+
+/// A [`Notifier`] that shows native desktop notifications.
+///
+/// On Linux, these are sent over D-Bus rather than through GTK, so it's fine to call this from the pollers' threads instead of the
+/// GTK thread that owns the tray icon. Only the user's response is routed back to the main loop.
+///
+/// On Linux, these have a "Snooze this" action that stops notifications about events with the same title for a while, and clicking
+/// them opens the window at the event. This is only true for up to [`MAX_ACTION_LISTENERS`] open notifications at a time, though.
+#[derive(Debug)]
+pub struct DesktopNotifier {
+    /// Used to tell the main loop about what the user does with a notification.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    proxy:     Mutex<AppEventLoopProxy>,
+    /// The language in which the actions on a notification are labelled.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    lang:      Lang,
+    /// The number of notifications of which we're currently waiting for the user to act on them.
+    #[cfg(target_os = "linux")]
+    listeners: Arc<AtomicUsize>,
+}
+impl DesktopNotifier {
+    /// Constructor for the DesktopNotifier.
+    ///
+    /// # Arguments
+    /// - `proxy`: An [`AppEventLoopProxy`] to tell the main loop about what the user does with a notification.
+    /// - `lang`: The [`Lang`] in which to label the actions on a notification.
+    ///
+    /// # Returns
+    /// A new DesktopNotifier.
+    #[inline]
+    pub fn new(proxy: AppEventLoopProxy, lang: Lang) -> Self {
+        Self {
+            proxy: Mutex::new(proxy),
+            lang,
+            #[cfg(target_os = "linux")]
+            listeners: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+impl Notifier for DesktopNotifier {
+    fn notify(&self, event: &Event, icon: Option<&str>) -> Result<(), Error> {
+        let summary: String = match icon {
+            Some(icon) => format!("{icon} [{}] {}", event.source, event.title),
+            None => format!("[{}] {}", event.source, event.title),
+        };
+        let mut notification: Notification = Notification::new();
+        notification.appname(env!("CARGO_PKG_NAME")).summary(&summary).body(&event.message);
+
+        // Only offer actions if we have room to wait for them
+        #[cfg(target_os = "linux")]
+        let listening: bool = self.listeners.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < MAX_ACTION_LISTENERS).then_some(n + 1)).is_ok();
+        #[cfg(target_os = "linux")]
+        if listening {
+            notification.action(OPEN_ACTION, self.lang.tr(Text::OpenAction)).action(SNOOZE_ACTION, self.lang.tr(Text::SnoozeAction));
+        } else {
+            debug!("Showing notification of event '{}' from '{}' without actions, as {MAX_ACTION_LISTENERS} others are still open", event.id, event.source);
+        }
+        let handle: NotificationHandle = match notification.show() {
+            Ok(handle) => handle,
+            Err(err) => {
+                #[cfg(target_os = "linux")]
+                if listening {
+                    self.listeners.fetch_sub(1, Ordering::SeqCst);
+                }
+                return Err(Error::DesktopShow { err });
+            },
+        };
+
+        // Waiting for the user to do something blocks until the notification is closed, so do that elsewhere
+        #[cfg(target_os = "linux")]
+        if listening {
+            let (proxy, listeners): (AppEventLoopProxy, Arc<AtomicUsize>) = (self.proxy.lock().clone(), self.listeners.clone());
+            let title: String = event.title.clone();
+            let key: String = event.key();
+            thread::spawn(move || {
+                handle.wait_for_action(|action| {
+                    if action == SNOOZE_ACTION {
+                        debug!("User snoozed event title '{title}' from a notification");
+                        let _ = proxy.send_event(AppEvent::Snooze { title });
+                    } else if action == OPEN_ACTION {
+                        debug!("User opened event '{key}' from a notification");
+                        let _ = proxy.send_event(AppEvent::Reveal { key });
+                    }
+                });
+                listeners.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+        Ok(())
+    }
+}
+
+
+
+/// A [`Notifier`] that POSTs events to some URL, such as a Slack or Discord webhook.
+///
+/// Events are not sent by the caller, but queued for a thread of the notifier's own, such that a slow or dead webhook never holds
+/// up polling. If the webhook falls too far behind, new events are dropped instead.
+#[derive(Debug)]
+pub struct WebhookNotifier {
+    /// The URL to POST to.
+    url:      Url,
+    /// The shape of the payload.
+    format:   WebhookFormat,
+    /// The message to send, with placeholders.
+    template: String,
+    /// Sends payloads to the thread that POSTs them. The thread stops once this is dropped and it sent what's left.
+    queue:    SyncSender<serde_json::Value>,
+}
+impl WebhookNotifier {
+    /// Constructor for the WebhookNotifier.
+    ///
+    /// This spawns the thread that sends the events.
+    ///
+    /// # Arguments
+    /// - `url`: The URL to POST to.
+    /// - `format`: The [`WebhookFormat`] of the payload.
+    /// - `template`: The message to send, where `{title}`, `{body}`, `{severity}`, `{source}` and `{icon}` are replaced by those of the event.
+    ///
+    /// # Returns
+    /// A new WebhookNotifier.
+    ///
+    /// # Errors
+    /// This function errors if we failed to build the HTTP client or to spawn its thread.
+    pub fn new(url: Url, format: WebhookFormat, template: String) -> Result<Self, Error> {
+        let client: Client = match Client::builder().timeout(WEBHOOK_TIMEOUT).connect_timeout(WEBHOOK_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(err) => return Err(Error::ClientCreate { err }),
+        };
+        let (queue, payloads): (SyncSender<serde_json::Value>, Receiver<serde_json::Value>) = mpsc::sync_channel(WEBHOOK_QUEUE_LEN);
+        let thread_url: Url = url.clone();
+        if let Err(err) = thread::Builder::new().name("webhook".into()).spawn(move || {
+            for payload in payloads {
+                if let Err(err) = post(&client, &thread_url, &payload) {
+                    warn!("{}", err.trace());
+                }
+            }
+        }) {
+            return Err(Error::WebhookThreadSpawn { url, err });
+        }
+        Ok(Self { url, format, template, queue })
+    }
+
+    /// Fills in the template for the given event.
+    ///
+    /// # Arguments
+    /// - `event`: The [`Event`] to describe.
+    /// - `icon`: The icon of the event, if any, which is left empty otherwise.
+    ///
+    /// # Returns
+    /// The message to send.
+    fn render(&self, event: &Event, icon: Option<&str>) -> String {
+        self.template
+            .replace("{title}", &event.title)
+            .replace("{body}", &event.message)
+            .replace("{severity}", &event.severity.variant().to_string())
+            .replace("{source}", &event.source)
+            .replace("{icon}", icon.unwrap_or_default())
+    }
+
+    /// Queues a payload about the given event for the thread that sends them.
+    ///
+    /// # Arguments
+    /// - `event`: The [`Event`] that the payload is about, to mention if it can't be queued.
+    /// - `payload`: The JSON to send.
+    fn send(&self, event: &Event, payload: serde_json::Value) {
+        match self.queue.try_send(payload) {
+            Ok(()) => {},
+            Err(TrySendError::Full(_)) => {
+                warn!("Webhook '{}' is falling behind; not sending event '{}' from server '{}'", self.url, event.id, event.source)
+            },
+            Err(TrySendError::Disconnected(_)) => {
+                warn!("Webhook '{}' is no longer sent to; not sending event '{}' from server '{}'", self.url, event.id, event.source)
+            },
+        }
+    }
+
+    /// Sends an event to the webhook for automation, rather than for the user to read.
+    ///
+    /// The payload always has the generic shape, with the name of the server that the event came from added as `server`. Like for
+    /// notifications, this only queues the event, and failures to deliver it are logged.
+    ///
+    /// # Arguments
+    /// - `server`: The name of the server that the event came from.
+    /// - `event`: The [`Event`] to send.
+    pub fn forward(&self, server: &str, event: &Event) { self.send(event, json!({ "server": server, "text": self.render(event, None), "event": event })) }
+}
+impl Notifier for WebhookNotifier {
+    fn notify(&self, event: &Event, icon: Option<&str>) -> Result<(), Error> {
+        let text: String = self.render(event, icon);
+        let payload: serde_json::Value = match self.format {
+            WebhookFormat::Generic => json!({ "text": text, "event": event }),
+            WebhookFormat::Slack => json!({ "text": text }),
+            WebhookFormat::Discord => json!({ "content": text }),
+        };
+        self.send(event, payload);
+        Ok(())
+    }
+}
+
+
+
+/// Keeps track of how many notifications were recently shown, to protect the user from floods of them.
+#[derive(Debug, Default)]
+struct Flood {
+    /// When the notifications within the [`FLOOD_WINDOW`] were shown, oldest first.
+    recent: VecDeque<Instant>,
+    /// The number of notifications that were hidden since the limit was last hit.
+    hidden: usize,
+}
+
+
+
+/// Keeps track of all [`Notifier`]s that events are dispatched to.
+#[derive(Debug)]
+pub struct Notifiers {
+    /// The registered notifiers.
+    notifiers: Vec<Box<dyn Notifier>>,
+    /// The maximum number of notifications per [`FLOOD_WINDOW`], or 0 if there is no limit.
+    max_per_minute: usize,
+    /// The notifications shown and hidden recently.
+    flood: Mutex<Flood>,
+    /// The language in which to write our own notices.
+    lang: Lang,
+    /// The clock that decides when our own notices happened.
+    clock: Arc<dyn Clock>,
+    /// The webhook that all new events are forwarded to, if any (see [`Notifiers::forward()`]).
+    webhook: Option<WebhookNotifier>,
+}
+impl Notifiers {
+    /// Constructor for the Notifiers that builds all the ones in the given config.
+    ///
+    /// # Arguments
+    /// - `configs`: The [`NotifierConfig`]s that describe which notifiers to register.
+    /// - `max_per_minute`: The maximum number of notifications to show per minute, or 0 for no limit.
+    /// - `lang`: The [`Lang`] in which to write notices and label actions.
+    /// - `clock`: The [`Clock`] that decides when our own notices happened (typically that of the [`AppState`](crate::state::AppState)).
+    /// - `proxy`: An [`AppEventLoopProxy`] to tell the main loop about what the user does with a notification.
+    ///
+    /// # Returns
+    /// A new Notifiers with a [`Notifier`] for every config.
+    ///
+    /// # Errors
+    /// This function errors if we failed to build any of the notifiers.
+    #[inline]
+    pub fn from_config(configs: &[NotifierConfig], max_per_minute: usize, lang: Lang, clock: Arc<dyn Clock>, proxy: &AppEventLoopProxy) -> Result<Self, Error> {
+        Ok(Self {
+            notifiers: configs.iter().map(|config| config.build(proxy, lang)).collect::<Result<_, _>>()?,
+            max_per_minute,
+            flood: Mutex::new(Flood::default()),
+            lang,
+            clock,
+            webhook: None,
+        })
+    }
+
+    /// Registers an additional [`Notifier`].
+    ///
+    /// # Arguments
+    /// - `notifier`: The [`Notifier`] to dispatch events to from now on.
+    #[inline]
+    pub fn register(&mut self, notifier: Box<dyn Notifier>) { self.notifiers.push(notifier); }
+
+    /// Sets the webhook that [`Notifiers::forward()`] sends events to.
+    ///
+    /// Like any [`WebhookNotifier`], this spawns a thread that sends the events, which stops once these Notifiers are dropped and it
+    /// sent what's left.
+    ///
+    /// # Arguments
+    /// - `url`: The URL to POST events to.
+    ///
+    /// # Errors
+    /// This function errors if we failed to build the HTTP client for it or to spawn its thread.
+    pub fn set_webhook(&mut self, url: Url) -> Result<(), Error> {
+        self.webhook = Some(WebhookNotifier::new(url, WebhookFormat::Generic, DEFAULT_WEBHOOK_TEMPLATE.into())?);
+        Ok(())
+    }
+
+    /// Forwards the given event to the webhook set with [`Notifiers::set_webhook()`], if any.
+    ///
+    /// Unlike [`Notifiers::notify()`], this is not rate limited, since the webhook is meant for automation. It doesn't wait for the
+    /// webhook either (see [`WebhookNotifier::forward()`]).
+    ///
+    /// # Arguments
+    /// - `server`: The name of the server that the event came from.
+    /// - `event`: The new [`Event`] to forward.
+    pub fn forward(&self, server: &str, event: &Event) {
+        if let Some(webhook) = &self.webhook {
+            webhook.forward(server, event);
+        }
+    }
+
+    /// Dispatches the given event to all registered [`Notifier`]s.
+    ///
+    /// Failures of individual notifiers are logged, and do not stop the others.
+    ///
+    /// If more than the maximum number of notifications were shown in the past minute, the event is hidden instead. Once the rate
+    /// drops again, the next notification is preceded by a single notice saying how many were hidden.
+    ///
+    /// # Arguments
+    /// - `event`: The new [`Event`] to notify about.
+    /// - `icon`: The icon that the user configured for the event, if any.
+    pub fn notify(&self, event: &Event, icon: Option<&str>) {
+        if self.max_per_minute > 0 {
+            let hidden: usize = {
+                let mut flood: MutexGuard<Flood> = self.flood.lock();
+                let now: Instant = Instant::now();
+                while flood.recent.front().is_some_and(|shown| now.duration_since(*shown) >= FLOOD_WINDOW) {
+                    flood.recent.pop_front();
+                }
+                if flood.recent.len() >= self.max_per_minute {
+                    flood.hidden += 1;
+                    if flood.hidden == 1 {
+                        warn!("Shown {} notifications in the past minute; hiding further ones until the rate drops", self.max_per_minute);
+                    }
+                    return;
+                }
+                flood.recent.push_back(now);
+                std::mem::take(&mut flood.hidden)
+            };
+            if hidden > 0 {
+                info!("Notification rate dropped again after hiding {hidden} notification(s)");
+                self.dispatch(&flood_notice(hidden, self.clock.now(), self.lang), None);
+            }
+        }
+        self.dispatch(event, icon);
+    }
+
+    /// Dispatches the given event to all registered [`Notifier`]s, regardless of how many were shown recently.
+    ///
+    /// # Arguments
+    /// - `event`: The [`Event`] to notify about.
+    /// - `icon`: The icon that the user configured for the event, if any.
+    fn dispatch(&self, event: &Event, icon: Option<&str>) {
+        debug!("Notifying {} notifier(s) of event '{}' from '{}'", self.notifiers.len(), event.id, event.source);
+        for notifier in &self.notifiers {
+            if let Err(err) = notifier.notify(event, icon) {
+                warn!("{}", err.trace());
+            }
+        }
+    }
+}
+impl Default for Notifiers {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            notifiers: Vec::new(),
+            max_per_minute: 0,
+            flood: Mutex::new(Flood::default()),
+            lang: Lang::default(),
+            clock: Arc::new(SystemClock),
+            webhook: None,
+        }
+    }
+}
@@ -0,0 +1,2879 @@
+//  POLLER.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 08:23:33
+//  Last edited:
+//    16 Oct 2026, 12:02:41
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the background workers that periodically fetch new
+//!   events from the configured servers.
+//!   
+//!   By default, every server is polled on a thread of its own. With the
+//!   `async` feature, they are polled as tasks on a single tokio runtime
+//!   instead.
+//
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashSet};
+use std::convert::Infallible;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::hash::{Hash as _, Hasher as _};
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs as _};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender};
+use std::sync::Arc;
+#[cfg(not(feature = "async"))]
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use std::{error, fs, thread};
+
+use chrono::{DateTime, Local};
+use enum_debug::EnumDebug as _;
+use error_trace::ErrorTrace as _;
+use log::{debug, error, info, trace, warn};
+#[cfg(not(feature = "async"))]
+use parking_lot::{Condvar, Mutex, MutexGuard};
+use reqwest::blocking::{Client, ClientBuilder, RequestBuilder, Response};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Certificate, StatusCode};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::pem::PemObject as _;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, RootCertStore, SignatureScheme};
+use serde::Deserialize;
+use tungstenite::client::IntoClientRequest as _;
+use tungstenite::handshake::client::Request;
+use tungstenite::handshake::HandshakeError;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Connector, Message, WebSocket};
+use url::Url;
+
+use crate::app::{AppEvent, AppEventLoopProxy};
+use crate::clock::Clock;
+use crate::event::{Event, Severity, TimestampFlag};
+use crate::notify::Notifiers;
+use crate::state::{AccessToken, AppState, AuthConfig, FieldMapping, HttpCache, OAuth, Pagination, ServerConfig, ServerStatus, TlsConfig, Token, Transport};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::io::{Read as _, Write as _};
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::clock::{MockClock, SystemClock};
+
+    /// Runs a mock HTTP server on a thread of its own, which answers every request on a connection of its own.
+    ///
+    /// # Arguments
+    /// - `respond`: Builds the full response (see [`response()`]) to the head of a request (e.g., `GET /events HTTP/1.1\r\n...`).
+    ///
+    /// # Returns
+    /// The address of the server, which stops when the test does.
+    fn serve(respond: impl 'static + Send + Sync + Fn(&str) -> String) -> Url {
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url: Url = Url::parse(&format!("http://{}/events", listener.local_addr().unwrap())).unwrap();
+        let respond: Arc<dyn Send + Sync + Fn(&str) -> String> = Arc::new(respond);
+        thread::spawn(move || {
+            for mut conn in listener.incoming().map_while(Result::ok) {
+                let respond: Arc<dyn Send + Sync + Fn(&str) -> String> = respond.clone();
+                thread::spawn(move || {
+                    // Read until the end of the head; none of our requests have a body
+                    let mut head: Vec<u8> = Vec::new();
+                    let mut buf: [u8; 1024] = [0; 1024];
+                    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+                        match conn.read(&mut buf) {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => head.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    let res: String = respond(&String::from_utf8_lossy(&head));
+                    let _ = conn.write_all(res.as_bytes());
+                });
+            }
+        });
+        url
+    }
+
+    /// Builds an HTTP response that closes the connection.
+    ///
+    /// # Arguments
+    /// - `status`: The status line without the version (e.g., `200 OK`).
+    /// - `headers`: Any headers besides `Content-Length` and `Connection`.
+    /// - `body`: The body of the response.
+    ///
+    /// # Returns
+    /// The raw response.
+    fn response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+        let headers: String = headers.iter().map(|(name, value)| format!("{name}: {value}\r\n")).collect();
+        format!("HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len())
+    }
+
+    #[test]
+    fn adaptive_interval_converges_to_bounds() {
+        let mut server: ServerConfig = ServerConfig::new("adaptive", Url::parse("http://localhost/events").unwrap());
+        server.adaptive = true;
+        server.min_interval = Duration::from_secs(10);
+        server.max_interval = Duration::from_secs(600);
+
+        // A busy server is polled ever faster, until the floor
+        let mut interval: Duration = Duration::from_secs(60);
+        for _ in 0..10 {
+            interval = next_interval(&server, interval, true);
+        }
+        assert_eq!(interval, server.min_interval);
+        assert_eq!(next_interval(&server, interval, true), server.min_interval);
+
+        // A quiet one ever slower, until the ceiling
+        for _ in 0..10 {
+            interval = next_interval(&server, interval, false);
+        }
+        assert_eq!(interval, server.max_interval);
+
+        // Without adaptive mode, it's always the configured interval
+        server.adaptive = false;
+        assert_eq!(next_interval(&server, Duration::from_secs(10), true), server.poll_interval);
+    }
+
+    #[test]
+    fn html_with_ok_status_is_rejected() {
+        let url: Url = serve(|_| response("200 OK", &[("Content-Type", "text/html; charset=utf-8")], "<html><body>Log in</body></html>"));
+        let server: ServerConfig = ServerConfig::new("html", url);
+        let client: Client = build_client(&TlsConfig::default(), None).unwrap();
+        let mut cache: HttpCache = HttpCache::default();
+        match poll_once(&client, &server, &mut cache, &SystemClock) {
+            Err(Error::ResponseContentType { content_type, .. }) => assert_eq!(content_type.as_deref(), Some("text/html; charset=utf-8")),
+            res => panic!("Expected a content type error, got {res:?}"),
+        }
+        assert_eq!(cache, HttpCache::default());
+    }
+
+    #[test]
+    fn not_modified_keeps_cache_and_reports_nothing() {
+        // Serve the events once, and after that only if the client doesn't know them yet
+        let url: Url = serve(|head| {
+            if head.to_ascii_lowercase().contains("if-none-match: \"v1\"") {
+                response("304 Not Modified", &[("ETag", "\"v1\"")], "")
+            } else {
+                let body: &str = r#"[{"id":"a","timestamp":"2024-04-01T12:00:00Z","severity":"info","source":"","title":"A","message":""}]"#;
+                response("200 OK", &[("Content-Type", "application/json"), ("ETag", "\"v1\"")], body)
+            }
+        });
+        let server: ServerConfig = ServerConfig::new("etag", url);
+        let client: Client = build_client(&TlsConfig::default(), None).unwrap();
+
+        // The first poll remembers the ETag...
+        let mut cache: HttpCache = HttpCache::default();
+        let events: Vec<Event> = poll_once(&client, &server, &mut cache, &SystemClock).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(cache.etag.as_deref(), Some("\"v1\""));
+
+        // ...such that the second is told nothing changed
+        let before: HttpCache = cache.clone();
+        let events: Vec<Event> = poll_once(&client, &server, &mut cache, &SystemClock).unwrap();
+        assert!(events.is_empty());
+        assert_eq!(cache, before);
+    }
+
+    #[test]
+    fn follows_two_pages() {
+        let event =
+            |id: &str| -> String { format!(r#"{{"id":"{id}","timestamp":"2024-04-01T12:00:00Z","severity":"info","source":"","title":"{id}","message":""}}"#) };
+        let json: [(&str, &str); 1] = [("Content-Type", "application/json")];
+
+        // Query parameters, where an empty page ends the feed
+        let url: Url = serve(move |head| {
+            let path: &str = head.split(' ').nth(1).unwrap_or("");
+            match path {
+                "/events" => response("200 OK", &json, &format!("[{}]", event("a"))),
+                "/events?page=2" => response("200 OK", &json, &format!("[{}]", event("b"))),
+                _ => response("200 OK", &json, "[]"),
+            }
+        });
+        let mut server: ServerConfig = ServerConfig::new("query", url);
+        server.pagination = Some(Pagination::Query { param: "page".into(), max_pages: 10 });
+        let client: Client = build_client(&TlsConfig::default(), None).unwrap();
+        let events: Vec<Event> = poll_once(&client, &server, &mut HttpCache::default(), &SystemClock).unwrap();
+        assert_eq!(events.iter().map(|event| event.id.as_str()).collect::<Vec<&str>>(), ["a", "b"]);
+
+        // Cursors, where a missing one ends the feed
+        let url: Url = serve(move |head| {
+            let path: &str = head.split(' ').nth(1).unwrap_or("");
+            match path {
+                "/events" => response("200 OK", &json, &format!(r#"{{"events":[{}],"next":"abc"}}"#, event("c"))),
+                "/events?cursor=abc" => response("200 OK", &json, &format!(r#"{{"events":[{}]}}"#, event("d"))),
+                _ => response("404 Not Found", &[], ""),
+            }
+        });
+        let mut server: ServerConfig = ServerConfig::new("cursor", url);
+        server.pagination = Some(Pagination::Cursor { items_field: "events".into(), cursor_field: "next".into(), param: "cursor".into(), max_pages: 10 });
+        let events: Vec<Event> = poll_once(&client, &server, &mut HttpCache::default(), &SystemClock).unwrap();
+        assert_eq!(events.iter().map(|event| event.id.as_str()).collect::<Vec<&str>>(), ["c", "d"]);
+    }
+
+    #[test]
+    fn malformed_events_are_reported_apart() {
+        let body: &str = r#"[
+            {"id":"a","timestamp":"2024-04-01T12:00:00Z","severity":"info","source":"","title":"A","message":""},
+            {"id":"b","timestamp":"yesterday","severity":"info","source":"","title":"B","message":""},
+            {"id":"c","timestamp":"2024-04-01T12:01:00Z","severity":"error","source":"","title":"C","message":""},
+            "not an event"
+        ]"#;
+        let url: Url = serve(move |_| response("200 OK", &[("Content-Type", "application/json")], body));
+        let server: ServerConfig = ServerConfig::new("mixed", url);
+        let client: Client = build_client(&TlsConfig::default(), None).unwrap();
+        let events: Vec<Event> = poll_once(&client, &server, &mut HttpCache::default(), &SystemClock).unwrap();
+
+        // The valid ones come through as-is, and the other two are summarized in one warning
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].id, "a");
+        assert_eq!(events[1].id, "c");
+        assert!(events[2].id.starts_with("malformed-"));
+        assert_eq!(events[2].severity, Severity::Warning);
+        assert_eq!(events[2].source, "mixed");
+        assert_eq!(events[2].title, "2 events failed to parse");
+        assert!(events[2].message.contains("- event 1:"));
+        assert!(events[2].message.contains("- event 3:"));
+    }
+
+    #[test]
+    fn missing_timestamps_get_time_of_receipt() {
+        let body: &str = r#"[{"id":"a","severity":"info","source":"","title":"A","message":""},{"id":"b","timestamp":null,"severity":"info","source":"","title":"B","message":""}]"#;
+        let url: Url = serve(move |_| response("200 OK", &[("Content-Type", "application/json")], body));
+        let server: ServerConfig = ServerConfig::new("untimed", url);
+        let client: Client = build_client(&TlsConfig::default(), None).unwrap();
+        let now: DateTime<Local> = DateTime::parse_from_rfc3339("2024-04-01T12:00:00+02:00").unwrap().with_timezone(&Local);
+        let events: Vec<Event> = poll_once(&client, &server, &mut HttpCache::default(), &MockClock::new(now)).unwrap();
+        assert_eq!(events.len(), 2);
+        for event in events {
+            assert_eq!(event.timestamp, now);
+            assert_eq!(event.timestamp_flag, Some(TimestampFlag::Missing));
+        }
+    }
+
+    /// Serves empty pages slowly, while keeping track of the most polls that it handled at the same time.
+    fn serve_counting() -> (Url, Arc<AtomicUsize>) {
+        let in_flight: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+        let peak: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+        let url: Url = {
+            let peak: Arc<AtomicUsize> = peak.clone();
+            serve(move |_| {
+                let now: usize = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(50));
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                response("200 OK", &[("Content-Type", "application/json")], "[]")
+            })
+        };
+        (url, peak)
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn poll_slots_cap_concurrent_polls() {
+        const SLOTS: usize = 3;
+        let (url, peak): (Url, Arc<AtomicUsize>) = serve_counting();
+
+        // Poll many servers at once through the same slots
+        let client: Client = build_client(&TlsConfig::default(), None).unwrap();
+        let slots: Arc<PollSlots> = Arc::new(PollSlots::new(SLOTS));
+        let handles: Vec<thread::JoinHandle<()>> = (0..12)
+            .map(|i| {
+                let server: ServerConfig = ServerConfig::new(format!("server{i}"), url.join(&format!("/server{i}")).unwrap());
+                let (client, slots): (Client, Arc<PollSlots>) = (client.clone(), slots.clone());
+                thread::spawn(move || {
+                    for _ in 0..3 {
+                        let _slot: PollSlot = slots.acquire();
+                        poll_once(&client, &server, &mut HttpCache::default(), &SystemClock).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // The slots were shared, but never by more than their number
+        let peak: usize = peak.load(Ordering::SeqCst);
+        assert!(peak <= SLOTS && peak > 1, "{peak} polls at the same time");
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn poll_semaphore_caps_concurrent_polls() {
+        const SLOTS: usize = 3;
+        let (url, peak): (Url, Arc<AtomicUsize>) = serve_counting();
+
+        // Poll many servers at once as tasks, like `poll_task()` does
+        let runtime: tokio::runtime::Runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+        let client: reqwest::Client = build_async_client(&TlsConfig::default(), None).unwrap();
+        let slots: Arc<tokio::sync::Semaphore> = Arc::new(tokio::sync::Semaphore::new(SLOTS));
+        let handles: Vec<tokio::task::JoinHandle<()>> = (0..12)
+            .map(|i| {
+                let server: ServerConfig = ServerConfig::new(format!("server{i}"), url.join(&format!("/server{i}")).unwrap());
+                let (client, slots): (reqwest::Client, Arc<tokio::sync::Semaphore>) = (client.clone(), slots.clone());
+                runtime.spawn(async move {
+                    for _ in 0..3 {
+                        let _slot: tokio::sync::SemaphorePermit = slots.acquire().await.unwrap();
+                        poll_once_async(&client, &server, &mut HttpCache::default(), &SystemClock).await.unwrap();
+                    }
+                })
+            })
+            .collect();
+        runtime.block_on(async {
+            for handle in handles {
+                handle.await.unwrap();
+            }
+        });
+
+        let peak: usize = peak.load(Ordering::SeqCst);
+        assert!(peak <= SLOTS && peak > 1, "{peak} polls at the same time");
+    }
+}
+
+
+
+
+
+/***** CONSTANTS *****/
+/// The maximum number of characters of a response body that we show when it's not what we expected.
+const BODY_SNIPPET_LEN: usize = 256;
+/// The `User-Agent` that we send to servers if the user didn't specify one.
+const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// The maximum number of parse errors listed in the event that reports malformed events.
+const MALFORMED_MAX_LINES: usize = 5;
+/// The fields of [`Event`] itself, which are never kept as metadata.
+const EVENT_FIELDS: [&str; 11] = ["id", "timestamp", "severity", "source", "title", "message", "url", "read", "pinned", "timestamp_flag", "metadata"];
+/// The maximum number of unknown fields of an event that we keep as [`Event::metadata`].
+const METADATA_MAX_ENTRIES: usize = 32;
+/// The maximum number of characters of a key or value in [`Event::metadata`], after which it's cut off.
+const METADATA_MAX_LEN: usize = 256;
+/// How long before its expiry we already replace a short-lived token, such that it doesn't expire while we're using it.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(30);
+/// The longest that we wait before polling a server that keeps failing, unless its interval is longer than that.
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+/// How long we wait before reconnecting to an event stream, unless the server told us otherwise.
+const STREAM_RECONNECT_DELAY: Duration = Duration::from_secs(3);
+/// How long an event stream may be quiet (not even sending a comment to keep it alive) before we reconnect.
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(2 * 60);
+/// How many bytes we read from an event stream at most at once.
+const STREAM_CHUNK_SIZE: usize = 8 * 1024;
+/// How long we try to connect to a server's WebSocket before giving up, which matches how long a poll may take.
+const SOCKET_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long reading from a WebSocket blocks before we check whether to ping the server or to stop.
+const SOCKET_READ_TIMEOUT: Duration = Duration::from_secs(1);
+/// How long a WebSocket may be quiet before we ping the server.
+const SOCKET_PING_INTERVAL: Duration = Duration::from_secs(30);
+/// How long the server may take to answer our ping before we consider the WebSocket dead.
+const SOCKET_PONG_TIMEOUT: Duration = Duration::from_secs(10);
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from polling a server.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to parse the root certificate that a server's TLS certificate is verified with.
+    CaCertParse { path: PathBuf, err: reqwest::Error },
+    /// Failed to parse the root certificate that a server's TLS certificate is verified with, for use with WebSockets.
+    CaCertPemParse { path: PathBuf, err: rustls::pki_types::pem::Error },
+    /// Failed to read the root certificate that a server's TLS certificate is verified with.
+    CaCertRead { path: PathBuf, err: std::io::Error },
+    /// Failed to trust the root certificate that a server's TLS certificate is verified with, for use with WebSockets.
+    CaCertTrust { path: PathBuf, err: rustls::Error },
+    /// Failed to build the HTTP client.
+    ClientCreate { err: reqwest::Error },
+    /// Failed to send the request to the server.
+    RequestSend { url: Url, err: reqwest::Error },
+    /// The server responded with a non-success status code.
+    ResponseStatus { url: Url, status: StatusCode },
+    /// The server responded with something that is not JSON.
+    ResponseContentType { url: Url, content_type: Option<String> },
+    /// Failed to download the response body.
+    ResponseDownload { url: Url, err: reqwest::Error },
+    /// Failed to parse the response body as a list of events.
+    ResponseParse { url: Url, err: serde_json::Error },
+    /// The server responded with something that is not an event stream.
+    StreamContentType { url: Url, content_type: Option<String> },
+    /// Failed to read the next message from an event stream.
+    StreamRead { url: Url, err: std::io::Error },
+    /// The file with events to replay cannot be described as a URL.
+    ReplayPath { path: PathBuf },
+    /// Failed to read the file with events to replay.
+    ReplayRead { path: PathBuf, err: std::io::Error },
+    /// Failed to build the runtime on which the servers are polled.
+    #[cfg(feature = "async")]
+    RuntimeCreate { err: std::io::Error },
+    /// Failed to open a WebSocket to the server.
+    SocketConnect { url: Url, err: Box<tungstenite::Error> },
+    /// Failed to read from (or to ping the server over) a WebSocket.
+    SocketRead { url: Url, err: Box<tungstenite::Error> },
+    /// The server did not answer our ping over a WebSocket in time.
+    SocketTimeout { url: Url },
+    /// Failed to set up TLS for WebSockets.
+    SocketTlsCreate { err: rustls::Error },
+    /// Failed to send the request for a short-lived token.
+    TokenExchange { server: String, url: Url, err: reqwest::Error },
+    /// Failed to parse the response of the token endpoint.
+    TokenParse { server: String, url: Url, err: serde_json::Error },
+    /// Failed to get the token to authenticate with.
+    TokenResolve { server: String, err: crate::state::Error },
+    /// The token endpoint responded with a non-success status code.
+    TokenStatus { server: String, url: Url, status: StatusCode },
+}
+impl Error {
+    /// Returns whether this error means that we couldn't authenticate to the server, rather than that we couldn't reach it.
+    ///
+    /// # Returns
+    /// True if we failed to get a token or if the server rejected the one we had, or false otherwise.
+    pub fn is_auth_failure(&self) -> bool {
+        match self {
+            Self::ResponseStatus { status, .. } => *status == StatusCode::UNAUTHORIZED || *status == StatusCode::FORBIDDEN,
+            Self::TokenExchange { .. } | Self::TokenParse { .. } | Self::TokenResolve { .. } | Self::TokenStatus { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Checks whether this error means that we could not reach the server at all, rather than that it answered with something we
+    /// could not use.
+    ///
+    /// # Returns
+    /// True if we failed to send the request or to receive the response, or false otherwise.
+    pub fn is_unreachable(&self) -> bool {
+        matches!(
+            self,
+            Self::ClientCreate { .. }
+                | Self::RequestSend { .. }
+                | Self::ResponseDownload { .. }
+                | Self::StreamRead { .. }
+                | Self::ReplayPath { .. }
+                | Self::ReplayRead { .. }
+                | Self::SocketConnect { .. }
+                | Self::SocketRead { .. }
+                | Self::SocketTimeout { .. }
+                | Self::TokenExchange { .. }
+        )
+    }
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            CaCertParse { path, .. } => write!(f, "Failed to parse root certificate '{}' as PEM", path.display()),
+            CaCertPemParse { path, .. } => write!(f, "Failed to parse root certificate '{}' as PEM", path.display()),
+            CaCertRead { path, .. } => write!(f, "Failed to read root certificate '{}'", path.display()),
+            CaCertTrust { path, .. } => write!(f, "Failed to trust root certificate '{}' for WebSockets", path.display()),
+            ClientCreate { .. } => write!(f, "Failed to create HTTP client"),
+            RequestSend { url, .. } => write!(f, "Failed to send GET-request to '{url}'"),
+            ResponseStatus { url, status } => {
+                write!(f, "Server '{}' responded with non-success status code {} ({})", url, status.as_u16(), status.canonical_reason().unwrap_or("???"))
+            },
+            ResponseContentType { url, content_type } => {
+                write!(f, "Server '{}' responded with non-JSON content type '{}'", url, content_type.as_deref().unwrap_or("<none>"))
+            },
+            ResponseDownload { url, .. } => write!(f, "Failed to download response body from '{url}'"),
+            ResponseParse { url, .. } => write!(f, "Failed to parse response body from '{url}' as a list of events"),
+            StreamContentType { url, content_type } => {
+                write!(f, "Server '{}' responded with content type '{}' instead of an event stream", url, content_type.as_deref().unwrap_or("<none>"))
+            },
+            StreamRead { url, .. } => write!(f, "Failed to read from event stream '{url}'"),
+            ReplayPath { path } => write!(f, "Cannot replay events from '{}' because it cannot be made into a file URL", path.display()),
+            ReplayRead { path, .. } => write!(f, "Failed to read events to replay from '{}'", path.display()),
+            #[cfg(feature = "async")]
+            RuntimeCreate { .. } => write!(f, "Failed to create async runtime for pollers"),
+            SocketConnect { url, .. } => write!(f, "Failed to open WebSocket to '{url}'"),
+            SocketRead { url, .. } => write!(f, "Failed to read from WebSocket '{url}'"),
+            SocketTimeout { url } => write!(f, "Server '{url}' did not answer ping over its WebSocket within {}s", SOCKET_PONG_TIMEOUT.as_secs()),
+            SocketTlsCreate { .. } => write!(f, "Failed to set up TLS for WebSockets"),
+            TokenExchange { server, url, .. } => write!(f, "Failed to request access token for server '{server}' from '{url}'"),
+            TokenParse { server, url, .. } => write!(f, "Failed to parse access token for server '{server}' from '{url}'"),
+            TokenResolve { server, .. } => write!(f, "Failed to get token for server '{server}'"),
+            TokenStatus { server, url, status } => write!(
+                f,
+                "Token endpoint '{}' of server '{}' responded with non-success status code {} ({})",
+                url,
+                server,
+                status.as_u16(),
+                status.canonical_reason().unwrap_or("???")
+            ),
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            CaCertParse { err, .. } => Some(err),
+            CaCertPemParse { err, .. } => Some(err),
+            CaCertRead { err, .. } => Some(err),
+            CaCertTrust { err, .. } => Some(err),
+            ClientCreate { err } => Some(err),
+            RequestSend { err, .. } => Some(err),
+            ResponseStatus { .. } => None,
+            ResponseContentType { .. } => None,
+            ResponseDownload { err, .. } => Some(err),
+            ResponseParse { err, .. } => Some(err),
+            StreamContentType { .. } => None,
+            StreamRead { err, .. } => Some(err),
+            ReplayPath { .. } => None,
+            ReplayRead { err, .. } => Some(err),
+            #[cfg(feature = "async")]
+            RuntimeCreate { err } => Some(err),
+            SocketConnect { err, .. } => Some(err),
+            SocketRead { err, .. } => Some(err),
+            SocketTimeout { .. } => None,
+            SocketTlsCreate { err } => Some(err),
+            TokenExchange { err, .. } => Some(err),
+            TokenParse { err, .. } => Some(err),
+            TokenResolve { err, .. } => Some(err),
+            TokenStatus { .. } => None,
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Checks whether reading a response body failed because the server sent nothing for too long.
+///
+/// # Arguments
+/// - `err`: The [`std::io::Error`] that reading failed with.
+///
+/// # Returns
+/// True if it's a timeout, either of the socket itself or of the HTTP client.
+fn is_timeout(err: &std::io::Error) -> bool {
+    err.kind() == ErrorKind::TimedOut || err.get_ref().and_then(|err| err.downcast_ref::<reqwest::Error>()).is_some_and(reqwest::Error::is_timeout)
+}
+
+/// Runs something that blocks (e.g., processing events, which touches the disk) from within code shared with the asynchronous pollers.
+///
+/// # Arguments
+/// - `f`: The closure to run.
+///
+/// # Returns
+/// Whatever `f` returns.
+#[inline]
+fn run_blocking<R>(f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "async")]
+    return tokio::task::block_in_place(f);
+    #[cfg(not(feature = "async"))]
+    f()
+}
+
+/// Checks whether the given `Content-Type` describes JSON.
+///
+/// # Arguments
+/// - `content_type`: The value of the `Content-Type`-header.
+///
+/// # Returns
+/// True if it's `application/json` or some `+json`-suffixed type, false otherwise.
+fn is_json(content_type: &str) -> bool {
+    let essence: String = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    essence == "application/json" || essence.ends_with("+json")
+}
+
+/// Decides how long to wait before polling a server again.
+///
+/// In adaptive mode, the interval halves after a poll that produced new events and doubles after one that didn't, clamped between
+/// [`ServerConfig::min_interval`] and [`ServerConfig::max_interval`]. Otherwise, it's always [`ServerConfig::poll_interval`].
+///
+/// # Arguments
+/// - `server`: The [`ServerConfig`] that was polled.
+/// - `current`: The interval that we waited before the last poll.
+/// - `active`: Whether the last poll produced any new events.
+///
+/// # Returns
+/// The [`Duration`] to sleep before the next poll.
+fn next_interval(server: &ServerConfig, current: Duration, active: bool) -> Duration {
+    if !server.adaptive {
+        return server.poll_interval;
+    }
+    let next: Duration = if active { current / 2 } else { current.saturating_mul(2) };
+    next.clamp(server.min_interval, server.max_interval.max(server.min_interval))
+}
+
+/// Decides how long to wait before polling a server that failed its last polls.
+///
+/// The interval doubles with every consecutive failure, such that a struggling server isn't hammered, up to [`MAX_BACKOFF`].
+///
+/// # Arguments
+/// - `interval`: The interval that we'd wait if the server was fine.
+/// - `failures`: The number of consecutive failed polls.
+///
+/// # Returns
+/// The [`Duration`] to sleep before the next poll.
+fn backoff(interval: Duration, failures: u32) -> Duration {
+    if failures == 0 {
+        return interval;
+    }
+    interval.saturating_mul(2u32.saturating_pow(failures)).min(MAX_BACKOFF.max(interval))
+}
+
+/// Finds the link to more information about an event in a custom field.
+///
+/// # Arguments
+/// - `server`: The [`ServerConfig`] that reported the event.
+/// - `raw`: The event as the server reported it.
+/// - `field`: The name of the field (or a JSON pointer to it) that holds the link.
+///
+/// # Returns
+/// The link, resolved against the server's [`ServerConfig::url`] if it's relative, or [`None`] if there is no (valid) link.
+fn event_url(server: &ServerConfig, raw: &serde_json::Value, field: &str) -> Option<Url> {
+    let value: &serde_json::Value = if field.starts_with('/') { raw.pointer(field)? } else { raw.get(field)? };
+    match server.url.join(value.as_str()?) {
+        Ok(url) => Some(url),
+        Err(err) => {
+            debug!("Ignoring invalid event link '{value}' from server '{}': {err}", server.name);
+            None
+        },
+    }
+}
+
+/// Rewrites an event in some server-specific shape to the one that [`Event`] expects.
+///
+/// Fields that the mapping points to but that are missing are left out, such that parsing fails if they're required.
+///
+/// # Arguments
+/// - `mapping`: The [`FieldMapping`] that describes the server's shape.
+/// - `raw`: The event as the server reported it.
+///
+/// # Returns
+/// The event in our shape, or a copy of `raw` if it's not an object.
+fn map_event(mapping: &FieldMapping, raw: &serde_json::Value) -> serde_json::Value {
+    let serde_json::Value::Object(fields) = raw else { return raw.clone() };
+    let mut event: serde_json::Map<String, serde_json::Value> = fields.clone();
+    for (ours, theirs) in [
+        ("id", &mapping.id),
+        ("title", &mapping.title),
+        ("message", &mapping.body),
+        ("timestamp", &mapping.timestamp),
+        ("severity", &mapping.severity),
+        ("source", &mapping.source),
+    ] {
+        let Some(theirs) = theirs else { continue };
+        match if theirs.starts_with('/') { raw.pointer(theirs) } else { raw.get(theirs) } {
+            Some(value) => event.insert(ours.into(), value.clone()),
+            None => event.remove(ours),
+        };
+    }
+
+    // Convert the values that we're lenient about
+    if let Some(serde_json::Value::Number(id)) = event.get("id") {
+        event.insert("id".into(), serde_json::Value::String(id.to_string()));
+    }
+    if let Some(timestamp) = event.get("timestamp").and_then(serde_json::Value::as_i64).and_then(|secs| DateTime::from_timestamp(secs, 0)) {
+        event.insert("timestamp".into(), serde_json::Value::String(timestamp.to_rfc3339()));
+    }
+    if let Some(severity) = event.get("severity").and_then(serde_json::Value::as_str).and_then(|severity| mapping.severities.get(severity)) {
+        event.insert("severity".into(), serde_json::json!(severity));
+    }
+    serde_json::Value::Object(event)
+}
+
+/// Collects the fields of an event that we don't know, such that the user can see them anyway.
+///
+/// # Arguments
+/// - `server`: The [`ServerConfig`] that reported the event.
+/// - `raw`: The event as the server reported it.
+///
+/// # Returns
+/// Up to [`METADATA_MAX_ENTRIES`] unknown fields as `key -> value`, with non-string values as JSON and everything cut off at
+/// [`METADATA_MAX_LEN`] characters.
+fn event_metadata(server: &ServerConfig, raw: &serde_json::Value) -> BTreeMap<String, String> {
+    let serde_json::Value::Object(fields) = raw else { return BTreeMap::new() };
+    let cut = |text: &str| -> String {
+        if text.chars().count() > METADATA_MAX_LEN {
+            text.chars().take(METADATA_MAX_LEN).chain(['…']).collect()
+        } else {
+            text.into()
+        }
+    };
+
+    // Find the fields that we already read elsewhere, where pointers mean that we read (from) their top-level field
+    let mut known: HashSet<&str> = EVENT_FIELDS.into_iter().collect();
+    let mapped: Vec<&Option<String>> = match &server.mapping {
+        Some(mapping) => vec![&mapping.id, &mapping.title, &mapping.body, &mapping.timestamp, &mapping.severity, &mapping.source],
+        None => Vec::new(),
+    };
+    for field in mapped.into_iter().chain([&server.event_url_field]).flatten() {
+        known.insert(field.strip_prefix('/').map(|pointer| pointer.split('/').next().unwrap_or(pointer)).unwrap_or(field));
+    }
+
+    // Keep the rest
+    let mut metadata: BTreeMap<String, String> = BTreeMap::new();
+    for (key, value) in fields.iter().filter(|(key, _)| !known.contains(key.as_str())) {
+        if metadata.len() >= METADATA_MAX_ENTRIES {
+            debug!("Event from server '{}' has more than {} unknown fields; ignoring the rest", server.name, METADATA_MAX_ENTRIES);
+            break;
+        }
+        let value: String = match value {
+            serde_json::Value::String(value) => cut(value),
+            value => cut(&value.to_string()),
+        };
+        metadata.insert(cut(key), value);
+    }
+    metadata
+}
+
+/// Returns a copy of the given URL with a query parameter set to some value, replacing any previous value.
+///
+/// # Arguments
+/// - `url`: The [`Url`] to start from.
+/// - `param`: The name of the query parameter.
+/// - `value`: The value to set it to.
+///
+/// # Returns
+/// A new [`Url`] with the parameter set.
+fn with_query(url: &Url, param: &str, value: &str) -> Url {
+    let pairs: Vec<(String, String)> = url.query_pairs().filter(|(key, _)| key != param).map(|(key, value)| (key.into_owned(), value.into_owned())).collect();
+    let mut res: Url = url.clone();
+    res.query_pairs_mut().clear().extend_pairs(pairs).append_pair(param, value);
+    res
+}
+
+/// Builds the headers to send with a request to a server, besides the one that authenticates it.
+///
+/// These are the [`ServerConfig::headers`] that the user configured, and those that ask the server to only send its events if they
+/// changed since the previous poll. The latter take precedence, as does the authentication header over a configured one with the
+/// same name.
+///
+/// # Arguments
+/// - `server`: The [`ServerConfig`] to send the request to.
+/// - `auth`: The name of the header that authenticates the request, if any.
+/// - `cache`: The [`HttpCache`] of the previous poll, if the server may tell us that nothing changed since.
+///
+/// # Returns
+/// A [`HeaderMap`] with the configured headers, and `If-None-Match` and/or `If-Modified-Since` if we know what to put in them.
+fn request_headers(server: &ServerConfig, auth: Option<&HeaderName>, cache: Option<&HttpCache>) -> HeaderMap {
+    let mut headers: HeaderMap = HeaderMap::new();
+    for (name, value) in &server.headers {
+        // These were validated when the config was loaded
+        let (Ok(name), Ok(value)): (Result<HeaderName, _>, Result<HeaderValue, _>) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value))
+        else {
+            warn!("Skipping invalid header '{name}' for server '{}'", server.name);
+            continue;
+        };
+        if auth == Some(&name) {
+            debug!("Not sending configured '{name}'-header to server '{}' because it authenticates with it", server.name);
+            continue;
+        }
+        headers.insert(name, value);
+    }
+    if let Some(etag) = cache.and_then(|cache| cache.etag.as_deref()).and_then(|etag| HeaderValue::from_str(etag).ok()) {
+        headers.insert(IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = cache.and_then(|cache| cache.last_modified.as_deref()).and_then(|value| HeaderValue::from_str(value).ok()) {
+        headers.insert(IF_MODIFIED_SINCE, last_modified);
+    }
+    headers
+}
+
+/// Checks whether a server's response to a request for a page of events is what we expect, before downloading its body.
+///
+/// # Arguments
+/// - `url`: The address of the page.
+/// - `status`: The [`StatusCode`] of the response.
+/// - `headers`: The headers of the response.
+///
+/// # Returns
+/// What the server told us about this version of the events, or [`None`] if it reported that nothing changed.
+///
+/// # Errors
+/// This function errors if the server responded with a non-success status code or with something else than JSON.
+fn check_response(url: &Url, status: StatusCode, headers: &HeaderMap) -> Result<Option<HttpCache>, Error> {
+    if status == StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(Error::ResponseStatus { url: url.clone(), status });
+    }
+
+    // Make sure it's JSON before we try to parse it
+    let content_type: Option<String> = headers.get(CONTENT_TYPE).map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned());
+    if !content_type.as_deref().map(is_json).unwrap_or(false) {
+        return Err(Error::ResponseContentType { url: url.clone(), content_type });
+    }
+
+    // Remember what the server told us about this version of the events
+    let header = |name: HeaderName| -> Option<String> { headers.get(name).and_then(|value| value.to_str().ok()).map(String::from) };
+    Ok(Some(HttpCache { etag: header(ETAG), last_modified: header(LAST_MODIFIED), access_token: None }))
+}
+
+/// Builds the form that exchanges a server's client credentials for a short-lived token.
+///
+/// # Arguments
+/// - `server`: The [`ServerConfig`] to get a token for.
+/// - `oauth`: The server's [`OAuth`] settings.
+///
+/// # Returns
+/// The fields of the form.
+///
+/// # Errors
+/// This function errors if the client secret lives in the OS secret store but we failed to get it from there.
+fn token_form(server: &ServerConfig, oauth: &OAuth) -> Result<Vec<(&'static str, String)>, Error> {
+    let secret: String = match oauth.client_secret.resolve(&server.name) {
+        Ok(secret) => secret,
+        Err(err) => return Err(Error::TokenResolve { server: server.name.clone(), err }),
+    };
+    let mut form: Vec<(&'static str, String)> =
+        vec![("grant_type", "client_credentials".into()), ("client_id", oauth.client_id.clone()), ("client_secret", secret)];
+    if let Some(scope) = &oauth.scope {
+        form.push(("scope", scope.clone()));
+    }
+    Ok(form)
+}
+
+/// Gets a new short-lived token from a server's token endpoint.
+///
+/// # Arguments
+/// - `client`: The [`HttpClient`] to send the request with.
+/// - `server`: The [`ServerConfig`] to get a token for.
+/// - `oauth`: The server's [`OAuth`] settings.
+///
+/// # Returns
+/// The new [`AccessToken`].
+///
+/// # Errors
+/// This function errors if we failed to reach the token endpoint or if it didn't give us a token.
+async fn exchange_token<C: HttpClient>(client: &C, server: &ServerConfig, oauth: &OAuth) -> Result<AccessToken, Error> {
+    debug!("Requesting access token for server '{}' from '{}'...", server.name, oauth.token_endpoint);
+    let url: &Url = &oauth.token_endpoint;
+    let req: Outgoing = Outgoing { url: url.clone(), headers: HeaderMap::new(), form: Some(token_form(server, oauth)?) };
+    let res: C::Response = match client.send(req).await {
+        Ok(res) => res,
+        Err(err) => return Err(Error::TokenExchange { server: server.name.clone(), url: url.clone(), err }),
+    };
+    if !res.status().is_success() {
+        return Err(Error::TokenStatus { server: server.name.clone(), url: url.clone(), status: res.status() });
+    }
+    let body: String = match res.text().await {
+        Ok(body) => body,
+        Err(err) => return Err(Error::TokenExchange { server: server.name.clone(), url: url.clone(), err }),
+    };
+    match serde_json::from_str::<TokenResponse>(&body) {
+        Ok(body) => Ok(body.into_access_token(Instant::now())),
+        Err(err) => Err(Error::TokenParse { server: server.name.clone(), url: url.clone(), err }),
+    }
+}
+
+/// Returns the cached short-lived token of a server if it's not about to expire.
+///
+/// # Arguments
+/// - `cache`: The [`HttpCache`] of the server.
+///
+/// # Returns
+/// The token, or [`None`] if we have to get a new one.
+fn cached_token(cache: &HttpCache) -> Option<String> {
+    let token: &AccessToken = cache.access_token.as_ref()?;
+    match token.expires {
+        Some(expires) if expires <= Instant::now() + TOKEN_REFRESH_MARGIN => None,
+        _ => Some(token.token.clone()),
+    }
+}
+
+/// Turns a short-lived token into the header that authenticates with it.
+///
+/// # Arguments
+/// - `server`: The [`ServerConfig`] that the token is for.
+/// - `token`: The token.
+///
+/// # Returns
+/// The `Authorization`-header, of which the value is marked as sensitive.
+///
+/// # Errors
+/// This function errors if the token doesn't fit in a header.
+fn bearer_header(server: &ServerConfig, token: String) -> Result<(HeaderName, HeaderValue), Error> {
+    match AuthConfig::Bearer(Token::Plain(token)).resolve(&server.name) {
+        Ok(header) => Ok(header),
+        Err(err) => Err(Error::TokenResolve { server: server.name.clone(), err }),
+    }
+}
+
+/// Returns the header to authenticate to a server with.
+///
+/// # Arguments
+/// - `client`: The [`HttpClient`] to get a short-lived token with, if necessary.
+/// - `server`: The [`ServerConfig`] to authenticate to.
+/// - `cache`: The [`HttpCache`] of the server, in which short-lived tokens are kept.
+///
+/// # Returns
+/// A header with a short-lived token if the server has [`ServerConfig::oauth`], or else the one described by its
+/// [`ServerConfig::auth`] or [`ServerConfig::token`] (if any).
+///
+/// # Errors
+/// This function errors if we failed to get the token.
+async fn auth_header<C: HttpClient>(client: &C, server: &ServerConfig, cache: &mut HttpCache) -> Result<Option<(HeaderName, HeaderValue)>, Error> {
+    let oauth: &OAuth = match &server.oauth {
+        Some(oauth) => oauth,
+        None => match server.resolve_auth() {
+            Ok(header) => return Ok(header),
+            Err(err) => return Err(Error::TokenResolve { server: server.name.clone(), err }),
+        },
+    };
+    if let Some(token) = cached_token(cache) {
+        return bearer_header(server, token).map(Some);
+    }
+    let token: AccessToken = exchange_token(client, server, oauth).await?;
+    cache.access_token = Some(token.clone());
+    bearer_header(server, token.token).map(Some)
+}
+
+/// Logs the start of a response body that wasn't JSON, to help figuring out what the server is doing.
+///
+/// # Arguments
+/// - `server`: The [`ServerConfig`] that sent the response.
+/// - `err`: The [`Error::ResponseContentType`] that we got.
+/// - `body`: The body of the response.
+fn log_non_json(server: &ServerConfig, err: &Error, body: &str) {
+    if let Error::ResponseContentType { url, content_type } = err {
+        debug!(
+            "Non-JSON response from '{}' of server '{}' (content type '{}'):\n{}",
+            url,
+            server.name,
+            content_type.as_deref().unwrap_or("<none>"),
+            body.chars().take(BODY_SNIPPET_LEN).collect::<String>()
+        );
+    }
+}
+
+/// Fetches a single page of a server's events API.
+///
+/// # Arguments
+/// - `client`: The [`HttpClient`] to send the request with.
+/// - `server`: The [`ServerConfig`] to poll.
+/// - `url`: The address of the page to fetch.
+/// - `auth`: The header to authenticate with, if any.
+/// - `cache`: The [`HttpCache`] of the previous poll, if the server may tell us that nothing changed since.
+///
+/// # Returns
+/// The parsed body and what the server told us about this version of it, or [`None`] if the server reported that nothing changed.
+///
+/// # Errors
+/// This function errors if we failed to reach the server or if it responded with something else than JSON.
+async fn fetch_page<C: HttpClient>(
+    client: &C,
+    server: &ServerConfig,
+    url: &Url,
+    auth: Option<&(HeaderName, HeaderValue)>,
+    cache: Option<&HttpCache>,
+) -> Result<Option<(serde_json::Value, HttpCache)>, Error> {
+    // Build the request, authenticating if the server wants us to
+    let mut headers: HeaderMap = request_headers(server, auth.map(|(name, _)| name), cache);
+    if let Some((name, value)) = auth {
+        headers.insert(name.clone(), value.clone());
+    }
+
+    // Send it and see if we like the response
+    let res: C::Response = match client.send(Outgoing { url: url.clone(), headers, form: None }).await {
+        Ok(res) => res,
+        Err(err) => return Err(Error::RequestSend { url: url.clone(), err }),
+    };
+    let new_cache: HttpCache = match check_response(url, res.status(), res.headers()) {
+        Ok(Some(new_cache)) => new_cache,
+        Ok(None) => return Ok(None),
+        Err(err) => {
+            if log::log_enabled!(log::Level::Debug) {
+                log_non_json(server, &err, &res.text().await.unwrap_or_default());
+            }
+            return Err(err);
+        },
+    };
+
+    // Parse the body
+    let body: String = match res.text().await {
+        Ok(body) => body,
+        Err(err) => return Err(Error::ResponseDownload { url: url.clone(), err }),
+    };
+    match serde_json::from_str(&body) {
+        Ok(body) => Ok(Some((body, new_cache))),
+        Err(err) => Err(Error::ResponseParse { url: url.clone(), err }),
+    }
+}
+
+/// Builds the headers to send with a request for a server's event stream, besides the one that authenticates it.
+///
+/// # Arguments
+/// - `server`: The [`ServerConfig`] to send the request to.
+/// - `auth`: The name of the header that authenticates the request, if any.
+/// - `last_event_id`: The ID of the last message that we got before losing the connection, if any, such that the server can resume
+///   from there.
+///
+/// # Returns
+/// A [`HeaderMap`] with the configured headers, `Accept` and `Last-Event-ID` (if we have one).
+fn stream_headers(server: &ServerConfig, auth: Option<&HeaderName>, last_event_id: Option<&str>) -> HeaderMap {
+    let mut headers: HeaderMap = request_headers(server, auth, None);
+    headers.insert(ACCEPT, HeaderValue::from_static("text/event-stream"));
+    if let Some(id) = last_event_id.and_then(|id| HeaderValue::from_str(id).ok()) {
+        headers.insert(HeaderName::from_static("last-event-id"), id);
+    }
+    headers
+}
+
+/// Checks whether the response to a request for a server's event stream is one.
+///
+/// # Arguments
+/// - `url`: The address of the event stream.
+/// - `status`: The [`StatusCode`] of the response.
+/// - `headers`: The headers of the response.
+///
+/// # Errors
+/// This function errors if the server responded with a non-success status code or with something else than `text/event-stream`.
+fn check_stream(url: &Url, status: StatusCode, headers: &HeaderMap) -> Result<(), Error> {
+    if !status.is_success() {
+        return Err(Error::ResponseStatus { url: url.clone(), status });
+    }
+    let content_type: Option<String> = headers.get(CONTENT_TYPE).map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned());
+    let essence: String = content_type.as_deref().and_then(|value| value.split(';').next()).unwrap_or("").trim().to_ascii_lowercase();
+    if essence != "text/event-stream" {
+        return Err(Error::StreamContentType { url: url.clone(), content_type });
+    }
+    Ok(())
+}
+
+/// Opens a server's event stream.
+///
+/// # Arguments
+/// - `client`: The [`HttpClient`] to send the request with, which should be one from [`build_stream_client()`] (or its
+///   asynchronous counterpart).
+/// - `server`: The [`ServerConfig`] to connect to.
+/// - `auth`: The header to authenticate with, if any.
+/// - `last_event_id`: The ID of the last message that we got before losing the connection, if any.
+///
+/// # Returns
+/// The response to read the stream from.
+///
+/// # Errors
+/// This function errors if we failed to reach the server or if it responded with something else than an event stream.
+async fn open_stream<C: HttpClient>(
+    client: &C,
+    server: &ServerConfig,
+    auth: Option<&(HeaderName, HeaderValue)>,
+    last_event_id: Option<&str>,
+) -> Result<C::Response, Error> {
+    let mut headers: HeaderMap = stream_headers(server, auth.map(|(name, _)| name), last_event_id);
+    if let Some((name, value)) = auth {
+        headers.insert(name.clone(), value.clone());
+    }
+    let res: C::Response = match client.send(Outgoing { url: server.url.clone(), headers, form: None }).await {
+        Ok(res) => res,
+        Err(err) => return Err(Error::RequestSend { url: server.url.clone(), err }),
+    };
+    check_stream(&server.url, res.status(), res.headers())?;
+    Ok(res)
+}
+
+/// Parses the `data` of a message on a server's event stream.
+///
+/// # Arguments
+/// - `server`: The [`ServerConfig`] that sent the message.
+/// - `data`: The `data` of the message, which is a single event or a list of them.
+/// - `clock`: The [`Clock`] that decides when we received events without a timestamp.
+///
+/// # Returns
+/// The [`Event`]s in the message, like [`poll_once()`] returns them.
+///
+/// # Errors
+/// This function errors if the data is not JSON.
+fn parse_stream_data(server: &ServerConfig, data: &str, clock: &dyn Clock) -> Result<Vec<Event>, Error> {
+    let mut pages: Pages = Pages::new(server, clock);
+    match serde_json::from_str(data) {
+        Ok(serde_json::Value::Array(items)) => pages.raw = items,
+        Ok(item) => pages.raw.push(item),
+        Err(err) => return Err(Error::ResponseParse { url: server.url.clone(), err }),
+    }
+    Ok(pages.into_events())
+}
+
+/// Opens a TCP connection to the host in an address, trying each address it resolves to for at most [`SOCKET_CONNECT_TIMEOUT`].
+///
+/// # Arguments
+/// - `url`: The [`Url`] to connect to.
+///
+/// # Returns
+/// The connected [`TcpStream`].
+///
+/// # Errors
+/// This function errors if the host didn't resolve, or if none of its addresses accepted the connection in time.
+fn connect_tcp(url: &Url) -> Result<TcpStream, std::io::Error> {
+    let addrs: Vec<SocketAddr> = (url.host_str().unwrap_or_default(), url.port_or_known_default().unwrap_or_default()).to_socket_addrs()?.collect();
+    let mut last: std::io::Error = std::io::Error::new(ErrorKind::NotFound, format!("Host '{}' resolved to no addresses", url.host_str().unwrap_or_default()));
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, SOCKET_CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last = err,
+        }
+    }
+    Err(last)
+}
+
+/// Opens a WebSocket to a server.
+///
+/// # Arguments
+/// - `server`: The [`ServerConfig`] to connect to.
+/// - `tls`: The TLS config to connect with if the server's URL is `wss://` (see [`build_socket_tls()`]).
+/// - `auth`: The header to authenticate with, if any.
+///
+/// # Returns
+/// The [`WebSocket`], of which reads time out after [`SOCKET_READ_TIMEOUT`] such that we get the chance to ping the server and to
+/// stop.
+///
+/// # Errors
+/// This function errors if we failed to reach the server or if it refused to upgrade the connection.
+fn open_socket(
+    server: &ServerConfig,
+    tls: &Arc<rustls::ClientConfig>,
+    auth: Option<&(HeaderName, HeaderValue)>,
+) -> Result<WebSocket<MaybeTlsStream<TcpStream>>, Error> {
+    let mut req: Request = match server.url.as_str().into_client_request() {
+        Ok(req) => req,
+        Err(err) => return Err(Error::SocketConnect { url: server.url.clone(), err: Box::new(err) }),
+    };
+    req.headers_mut().extend(request_headers(server, auth.map(|(name, _)| name), None));
+    if let Some((name, value)) = auth {
+        req.headers_mut().insert(name.clone(), value.clone());
+    }
+
+    // Connect, telling apart the server refusing us such that we know when to get a new token
+    let stream: TcpStream = match connect_tcp(&server.url) {
+        Ok(stream) => stream,
+        Err(err) => return Err(Error::SocketConnect { url: server.url.clone(), err: Box::new(tungstenite::Error::Io(err)) }),
+    };
+    let socket: WebSocket<MaybeTlsStream<TcpStream>> = match tungstenite::client_tls_with_config(req, stream, None, Some(Connector::Rustls(tls.clone()))) {
+        Ok((socket, _)) => socket,
+        Err(HandshakeError::Failure(tungstenite::Error::Http(res))) => {
+            return Err(Error::ResponseStatus { url: server.url.clone(), status: res.status() });
+        },
+        Err(HandshakeError::Failure(err)) => return Err(Error::SocketConnect { url: server.url.clone(), err: Box::new(err) }),
+        // Only happens for non-blocking streams, which ours isn't (yet)
+        Err(HandshakeError::Interrupted(_)) => {
+            return Err(Error::SocketConnect { url: server.url.clone(), err: Box::new(tungstenite::Error::Io(ErrorKind::WouldBlock.into())) });
+        },
+    };
+    let res: Result<(), std::io::Error> = match socket.get_ref() {
+        MaybeTlsStream::Plain(stream) => stream.set_read_timeout(Some(SOCKET_READ_TIMEOUT)),
+        MaybeTlsStream::Rustls(stream) => stream.get_ref().set_read_timeout(Some(SOCKET_READ_TIMEOUT)),
+        _ => Ok(()),
+    };
+    if let Err(err) = res {
+        return Err(Error::SocketConnect { url: server.url.clone(), err: Box::new(tungstenite::Error::Io(err)) });
+    }
+    Ok(socket)
+}
+
+/// Closes a WebSocket cleanly, by sending a close frame and briefly waiting for the server to answer it.
+///
+/// # Arguments
+/// - `socket`: The [`WebSocket`] to close.
+/// - `server`: The [`ServerConfig`] on the other end.
+fn close_socket(socket: &mut WebSocket<MaybeTlsStream<TcpStream>>, server: &ServerConfig) {
+    debug!("Closing WebSocket of server '{}'", server.name);
+    if socket.close(None).is_err() {
+        return;
+    }
+    // The server's answer makes reading fail with `ConnectionClosed`
+    let deadline: Instant = Instant::now() + SOCKET_READ_TIMEOUT;
+    while Instant::now() < deadline && socket.read().is_ok() {}
+}
+
+/// Splits a page of a server's events API into the events on it and the address of the next page.
+///
+/// # Arguments
+/// - `server`: The [`ServerConfig`] that sent the page.
+/// - `url`: The address of the page.
+/// - `body`: The parsed body of the page.
+/// - `page`: The number of the page, counting from 1.
+///
+/// # Returns
+/// The (unparsed) events on the page, and the address of the next page if there is one according to [`ServerConfig::pagination`].
+///
+/// # Errors
+/// This function errors if the page does not have the shape that [`ServerConfig::pagination`] describes.
+fn split_page(server: &ServerConfig, url: &Url, mut body: serde_json::Value, page: usize) -> Result<(Vec<serde_json::Value>, Option<Url>), Error> {
+    // Find the events and the cursor, if any
+    let (items, cursor): (serde_json::Value, Option<serde_json::Value>) = match &server.pagination {
+        Some(Pagination::Cursor { items_field, cursor_field, .. }) => (
+            body.get_mut(items_field.as_str()).map(serde_json::Value::take).unwrap_or_default(),
+            body.get_mut(cursor_field.as_str()).map(serde_json::Value::take),
+        ),
+        _ => (body, None),
+    };
+    let items: Vec<serde_json::Value> = match serde_json::from_value(items) {
+        Ok(items) => items,
+        Err(err) => return Err(Error::ResponseParse { url: url.clone(), err }),
+    };
+
+    // Decide where the next page lives
+    let next: Option<Url> = match &server.pagination {
+        Some(Pagination::Query { param, .. }) if !items.is_empty() => Some(with_query(&server.url, param, &(page + 1).to_string())),
+        Some(Pagination::Cursor { param, .. }) => {
+            let cursor: Option<String> = match cursor {
+                Some(serde_json::Value::String(cursor)) if !cursor.is_empty() => Some(cursor),
+                Some(serde_json::Value::Number(cursor)) => Some(cursor.to_string()),
+                _ => None,
+            };
+            cursor.map(|cursor| match Url::parse(&cursor) {
+                Ok(next) if next.has_host() => next,
+                _ => with_query(&server.url, param, &cursor),
+            })
+        },
+        _ => None,
+    };
+    Ok((items, next))
+}
+
+/// Builds an event that tells the user that some of the events of a server could not be parsed.
+///
+/// # Arguments
+/// - `server`: The [`ServerConfig`] that reported the malformed events.
+/// - `failures`: The index of every malformed event in the response, together with the event itself and why it's malformed.
+/// - `now`: The time at which we received the malformed events.
+///
+/// # Returns
+/// A new [`Event`] with [`Severity::Warning`]. Its ID is derived from the malformed events, such that it's only reported again if
+/// they change.
+fn malformed_event(server: &ServerConfig, failures: &[(usize, serde_json::Value, serde_json::Error)], now: DateTime<Local>) -> Event {
+    let mut hasher: DefaultHasher = DefaultHasher::new();
+    for (i, raw, _) in failures {
+        (i, raw.to_string()).hash(&mut hasher);
+    }
+
+    let mut message: String = failures.iter().take(MALFORMED_MAX_LINES).map(|(i, _, err)| format!("- event {i}: {err}")).collect::<Vec<String>>().join("\n");
+    if failures.len() > MALFORMED_MAX_LINES {
+        message.push_str(&format!("\n(and {} more)", failures.len() - MALFORMED_MAX_LINES));
+    }
+    Event {
+        id: format!("malformed-{:016x}", hasher.finish()),
+        timestamp: now,
+        severity: Severity::Warning,
+        source: server.name.clone(),
+        title: format!("{} event{} failed to parse", failures.len(), if failures.len() == 1 { "" } else { "s" }),
+        message,
+        url: None,
+        read: false,
+        pinned: false,
+        timestamp_flag: None,
+        metadata: BTreeMap::new(),
+    }
+}
+
+/// Reads a file of events to replay and decides when to replay each of them.
+///
+/// # Arguments
+/// - `path`: The path of the file, which has an [`Event`] as JSON on every line.
+/// - `speed`: The factor by which replaying is faster than the time between the events' timestamps. `0.0` replays them all at once.
+///
+/// # Returns
+/// The events in the order of the file, each with how long to wait after the previous one before replaying it. Malformed lines are
+/// skipped.
+///
+/// # Errors
+/// This function errors if we failed to read the file.
+fn load_replay(path: &Path, speed: f64) -> Result<Vec<(Duration, Event)>, Error> {
+    let raw: String = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) => return Err(Error::ReplayRead { path: path.into(), err }),
+    };
+
+    let mut schedule: Vec<(Duration, Event)> = Vec::new();
+    let mut prev: Option<DateTime<Local>> = None;
+    for (i, line) in raw.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let event: Event = match serde_json::from_str(line) {
+            Ok(event) => event,
+            Err(err) => {
+                warn!("Skipping malformed event on line {} of '{}': {}", i + 1, path.display(), err);
+                continue;
+            },
+        };
+        let elapsed: Duration = prev.and_then(|prev| (event.timestamp - prev).to_std().ok()).unwrap_or(Duration::ZERO);
+        let delay: Duration = if speed > 0.0 { Duration::try_from_secs_f64(elapsed.as_secs_f64() / speed).unwrap_or(Duration::MAX) } else { Duration::ZERO };
+        prev = Some(event.timestamp);
+        schedule.push((delay, event));
+    }
+    Ok(schedule)
+}
+
+/// Loads the extra root certificate that a [`TlsConfig`] asks for, if any.
+///
+/// # Arguments
+/// - `tls`: The [`TlsConfig`] that may point to a root certificate.
+///
+/// # Returns
+/// The parsed [`Certificate`], or [`None`] if the `tls` doesn't have one.
+///
+/// # Errors
+/// This function errors if we failed to read or parse the certificate.
+fn load_ca_cert(tls: &TlsConfig) -> Result<Option<Certificate>, Error> {
+    let Some(path) = &tls.ca_cert else { return Ok(None) };
+    let raw: Vec<u8> = match fs::read(path) {
+        Ok(raw) => raw,
+        Err(err) => return Err(Error::CaCertRead { path: path.clone(), err }),
+    };
+    match Certificate::from_pem(&raw) {
+        Ok(cert) => Ok(Some(cert)),
+        Err(err) => Err(Error::CaCertParse { path: path.clone(), err }),
+    }
+}
+
+/// Builds the TLS config that WebSockets connect with, which follows the same [`TlsConfig`] as the HTTP clients.
+///
+/// # Arguments
+/// - `tls`: How to verify TLS certificates. See [`ServerConfig::effective_tls()`].
+///
+/// # Returns
+/// A new [`rustls::ClientConfig`] that trusts the usual root certificates and the one in the `tls` (if any), or any certificate at
+/// all if the `tls` says so.
+///
+/// # Errors
+/// This function errors if we failed to load the root certificate in the `tls`, or if rustls failed to initialize.
+fn build_socket_tls(tls: &TlsConfig) -> Result<Arc<rustls::ClientConfig>, Error> {
+    let provider: Arc<CryptoProvider> = Arc::new(rustls::crypto::ring::default_provider());
+    let builder: rustls::ConfigBuilder<rustls::ClientConfig, rustls::WantsVerifier> =
+        match rustls::ClientConfig::builder_with_provider(provider.clone()).with_safe_default_protocol_versions() {
+            Ok(builder) => builder,
+            Err(err) => return Err(Error::SocketTlsCreate { err }),
+        };
+    if tls.danger_accept_invalid {
+        return Ok(Arc::new(builder.dangerous().with_custom_certificate_verifier(Arc::new(AcceptAnyCert { provider })).with_no_client_auth()));
+    }
+
+    // Trust the usual roots, and the user's own if any
+    let mut roots: RootCertStore = RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() };
+    if let Some(path) = &tls.ca_cert {
+        let raw: Vec<u8> = match fs::read(path) {
+            Ok(raw) => raw,
+            Err(err) => return Err(Error::CaCertRead { path: path.clone(), err }),
+        };
+        let cert: CertificateDer<'static> = match CertificateDer::from_pem_slice(&raw) {
+            Ok(cert) => cert,
+            Err(err) => return Err(Error::CaCertPemParse { path: path.clone(), err }),
+        };
+        if let Err(err) = roots.add(cert) {
+            return Err(Error::CaCertTrust { path: path.clone(), err });
+        }
+    }
+    Ok(Arc::new(builder.with_root_certificates(roots).with_no_client_auth()))
+}
+
+/// Builds an HTTP client to poll servers with.
+///
+/// # Arguments
+/// - `tls`: How the client should verify TLS certificates. See [`ServerConfig::effective_tls()`].
+/// - `user_agent`: The `User-Agent` to send, or [`None`] to send `server-events/<version>`.
+///
+/// # Returns
+/// A new [`Client`].
+///
+/// # Errors
+/// This function errors if we failed to load the root certificate in the `tls`, or if the backend failed to initialize the client.
+pub fn build_client(tls: &TlsConfig, user_agent: Option<&str>) -> Result<Client, Error> {
+    let mut builder: ClientBuilder =
+        Client::builder().danger_accept_invalid_certs(tls.danger_accept_invalid).user_agent(user_agent.unwrap_or(DEFAULT_USER_AGENT));
+    if let Some(cert) = load_ca_cert(tls)? {
+        builder = builder.add_root_certificate(cert);
+    }
+    match builder.build() {
+        Ok(client) => Ok(client),
+        Err(err) => Err(Error::ClientCreate { err }),
+    }
+}
+
+/// Builds an HTTP client to read event streams with.
+///
+/// Unlike the one from [`build_client()`], this one never gives up on a request as a whole, as event streams never end by
+/// themselves. Instead, reading from a stream that it opened fails once the stream is quiet for longer than [`STREAM_IDLE_TIMEOUT`].
+///
+/// # Arguments
+/// - `tls`: How the client should verify TLS certificates. See [`ServerConfig::effective_tls()`].
+/// - `user_agent`: The `User-Agent` to send, or [`None`] to send `server-events/<version>`.
+///
+/// # Returns
+/// A new [`Client`].
+///
+/// # Errors
+/// This function errors if we failed to load the root certificate in the `tls`, or if the backend failed to initialize the client.
+#[cfg(not(feature = "async"))]
+fn build_stream_client(tls: &TlsConfig, user_agent: Option<&str>) -> Result<Client, Error> {
+    // The blocking builder only knows about total timeouts, so we configure the idle one on the async builder that it wraps
+    let mut builder: ClientBuilder = ClientBuilder::from(reqwest::Client::builder().read_timeout(STREAM_IDLE_TIMEOUT))
+        .danger_accept_invalid_certs(tls.danger_accept_invalid)
+        .user_agent(user_agent.unwrap_or(DEFAULT_USER_AGENT))
+        .timeout(None)
+        .connect_timeout(STREAM_IDLE_TIMEOUT);
+    if let Some(cert) = load_ca_cert(tls)? {
+        builder = builder.add_root_certificate(cert);
+    }
+    match builder.build() {
+        Ok(client) => Ok(client),
+        Err(err) => Err(Error::ClientCreate { err }),
+    }
+}
+
+/// Builds an asynchronous HTTP client to poll servers with.
+///
+/// See [`build_client()`] for details.
+#[cfg(feature = "async")]
+pub fn build_async_client(tls: &TlsConfig, user_agent: Option<&str>) -> Result<reqwest::Client, Error> {
+    let mut builder: reqwest::ClientBuilder =
+        reqwest::Client::builder().danger_accept_invalid_certs(tls.danger_accept_invalid).user_agent(user_agent.unwrap_or(DEFAULT_USER_AGENT));
+    if let Some(cert) = load_ca_cert(tls)? {
+        builder = builder.add_root_certificate(cert);
+    }
+    match builder.build() {
+        Ok(client) => Ok(client),
+        Err(err) => Err(Error::ClientCreate { err }),
+    }
+}
+
+/// Builds an asynchronous HTTP client to read event streams with.
+///
+/// Reading from a stream that it opened fails once the stream is quiet for longer than [`STREAM_IDLE_TIMEOUT`]. See
+/// [`build_stream_client()`] for details.
+#[cfg(feature = "async")]
+fn build_async_stream_client(tls: &TlsConfig, user_agent: Option<&str>) -> Result<reqwest::Client, Error> {
+    let mut builder: reqwest::ClientBuilder = reqwest::Client::builder()
+        .danger_accept_invalid_certs(tls.danger_accept_invalid)
+        .user_agent(user_agent.unwrap_or(DEFAULT_USER_AGENT))
+        .connect_timeout(STREAM_IDLE_TIMEOUT)
+        .read_timeout(STREAM_IDLE_TIMEOUT);
+    if let Some(cert) = load_ca_cert(tls)? {
+        builder = builder.add_root_certificate(cert);
+    }
+    match builder.build() {
+        Ok(client) => Ok(client),
+        Err(err) => Err(Error::ClientCreate { err }),
+    }
+}
+
+/// Polls the given server once, with any [`HttpClient`].
+///
+/// See [`poll_once()`] for details.
+async fn poll<C: HttpClient>(client: &C, server: &ServerConfig, cache: &mut HttpCache, clock: &dyn Clock) -> Result<Vec<Event>, Error> {
+    debug!("Polling server '{}' at '{}'...", server.name, server.url);
+    let mut auth: Option<(HeaderName, HeaderValue)> = auth_header(client, server, cache).await?;
+
+    // Fetch the first page, which is the only one that the server can tell us is unchanged
+    let first: Option<(serde_json::Value, HttpCache)> = match fetch_page(client, server, &server.url, auth.as_ref(), Some(cache)).await {
+        // Short-lived tokens may be revoked before they expire, so get a new one and try once more
+        Err(Error::ResponseStatus { status, .. }) if status == StatusCode::UNAUTHORIZED && server.oauth.is_some() => {
+            debug!("Server '{}' rejected its access token; requesting a new one", server.name);
+            cache.access_token = None;
+            auth = auth_header(client, server, cache).await?;
+            fetch_page(client, server, &server.url, auth.as_ref(), Some(cache)).await?
+        },
+        res => res?,
+    };
+    let (body, new_cache): (serde_json::Value, HttpCache) = match first {
+        Some(res) => res,
+        None => {
+            trace!("Server '{}' reported no changes since the previous poll", server.name);
+            return Ok(Vec::new());
+        },
+    };
+
+    // Follow the pages, if any
+    let mut pages: Pages = Pages::new(server, clock);
+    let mut next: Option<Url> = pages.push(body)?;
+    while let Some(url) = next {
+        next = match fetch_page(client, server, &url, auth.as_ref(), None).await? {
+            Some((body, _)) => pages.push(body)?,
+            None => None,
+        };
+    }
+    *cache = HttpCache { access_token: cache.access_token.take(), ..new_cache };
+    Ok(pages.into_events())
+}
+
+/// Polls the given server once.
+///
+/// If the server paginates its events (see [`ServerConfig::pagination`]), this follows the pages up to the configured maximum.
+///
+/// # Arguments
+/// - `client`: The HTTP [`Client`] to send the request with.
+/// - `server`: The [`ServerConfig`] to poll.
+/// - `cache`: The [`HttpCache`] of the previous poll, if any, which is updated with what the server tells us this time.
+/// - `clock`: The [`Clock`] that decides when we received events without a timestamp (typically that of the [`AppState`]).
+///
+/// # Returns
+/// The [`Event`]s that the server reported. Their [`Event::source`] is set to the server's name if it was left empty. If the server
+/// reported that nothing changed since the previous poll, this is empty. Events that are malformed are skipped, and reported in a
+/// single additional event instead.
+///
+/// # Errors
+/// This function errors if we failed to reach the server or if it responded with something else than a list of events.
+#[inline]
+pub fn poll_once(client: &Client, server: &ServerConfig, cache: &mut HttpCache, clock: &dyn Clock) -> Result<Vec<Event>, Error> {
+    pollster::block_on(poll(client, server, cache, clock))
+}
+
+/// Polls the given server once, asynchronously.
+///
+/// See [`poll_once()`] for details.
+#[cfg(feature = "async")]
+#[inline]
+pub async fn poll_once_async(client: &reqwest::Client, server: &ServerConfig, cache: &mut HttpCache, clock: &dyn Clock) -> Result<Vec<Event>, Error> {
+    poll(client, server, cache, clock).await
+}
+
+/// Runs the polling loop for a single server on its own thread.
+///
+/// # Arguments
+/// - `worker`: The [`Worker`] that processes the polls.
+/// - `client`: The HTTP [`Client`] to send requests with.
+/// - `slots`: If given, the [`PollSlots`] that limit how many servers are polled at the same time.
+/// - `stopped`: Set when the [`Poller`] is dropped, after which we stop.
+#[cfg(not(feature = "async"))]
+fn poll_loop(mut worker: Worker, client: Client, slots: Option<Arc<PollSlots>>, stopped: Arc<AtomicBool>) {
+    while !stopped.load(Ordering::Relaxed) {
+        let mut cache: HttpCache = worker.cache();
+        let res: Result<Vec<Event>, Error> = {
+            let _slot: Option<PollSlot> = slots.as_deref().map(PollSlots::acquire);
+            poll_once(&client, &worker.server, &mut cache, worker.state.clock())
+        };
+        let Some(sleep) = worker.process(res, cache) else { return };
+        // Parked instead of asleep, such that `Poller::refresh()` and dropping the `Poller` can wake us early
+        thread::park_timeout(sleep);
+    }
+    debug!("Stopping poller for server '{}'", worker.server.name);
+}
+
+/// Replays events from a file on its own thread, as if a server reported them.
+///
+/// # Arguments
+/// - `worker`: The [`Worker`] that processes the events.
+/// - `schedule`: The events to replay, each with how long to wait before doing so (see [`load_replay()`]).
+/// - `stopped`: Set when the [`Poller`] is dropped, after which we stop.
+#[cfg(not(feature = "async"))]
+fn replay_loop(mut worker: Worker, schedule: Vec<(Duration, Event)>, stopped: Arc<AtomicBool>) {
+    for (delay, event) in schedule {
+        // Parked such that dropping the `Poller` can wake us early, but refreshing doesn't skip ahead
+        let deadline: Instant = Instant::now() + delay;
+        while !stopped.load(Ordering::Relaxed) && Instant::now() < deadline {
+            thread::park_timeout(deadline.saturating_duration_since(Instant::now()));
+        }
+        if stopped.load(Ordering::Relaxed) || worker.process(Ok(vec![event]), HttpCache::default()).is_none() {
+            return;
+        }
+    }
+    info!("Replayed all events of '{}'", worker.server.name);
+}
+
+/// Runs the polling loop for a single server as an asynchronous task.
+///
+/// # Arguments
+/// - `worker`: The [`Worker`] that processes the polls.
+/// - `client`: The asynchronous HTTP [`reqwest::Client`] to send requests with.
+/// - `refresh`: A [`Notify`](tokio::sync::Notify) that wakes us early when `Poller::refresh()` is called.
+/// - `slots`: If given, a [`Semaphore`](tokio::sync::Semaphore) that limits how many servers are polled at the same time.
+#[cfg(feature = "async")]
+async fn poll_task(mut worker: Worker, client: reqwest::Client, refresh: Arc<tokio::sync::Notify>, slots: Option<Arc<tokio::sync::Semaphore>>) {
+    loop {
+        let mut cache: HttpCache = worker.cache();
+        let res: Result<Vec<Event>, Error> = {
+            // The semaphore is never closed, so failing to acquire it can't happen
+            let _slot: Option<tokio::sync::SemaphorePermit> = match &slots {
+                Some(slots) => slots.acquire().await.ok(),
+                None => None,
+            };
+            poll_once_async(&client, &worker.server, &mut cache, worker.state.clock()).await
+        };
+        // Processing touches the disk and may notify through blocking backends, so tell the runtime to move other tasks elsewhere
+        let Some(sleep) = tokio::task::block_in_place(|| worker.process(res, cache)) else { return };
+        tokio::select! {
+            _ = tokio::time::sleep(sleep) => {},
+            _ = refresh.notified() => {},
+        }
+    }
+}
+
+/// Replays events from a file as an asynchronous task, as if a server reported them.
+///
+/// See `replay_loop()` for details.
+#[cfg(feature = "async")]
+async fn replay_task(mut worker: Worker, schedule: Vec<(Duration, Event)>) {
+    for (delay, event) in schedule {
+        tokio::time::sleep(delay).await;
+        if tokio::task::block_in_place(|| worker.process(Ok(vec![event]), HttpCache::default())).is_none() {
+            return;
+        }
+    }
+    info!("Replayed all events of '{}'", worker.server.name);
+}
+
+/// Reads a server's event stream until it ends.
+///
+/// Every message is processed as if it were a poll that reported its events. Connecting counts as a poll that reported none, such
+/// that the server shows as reachable while it's quiet. A stream that is quiet for longer than [`STREAM_IDLE_TIMEOUT`] is
+/// reconnected, which doesn't count as a failure.
+///
+/// # Arguments
+/// - `worker`: The [`Worker`] that processes the messages.
+/// - `client`: The [`HttpClient`] to get short-lived tokens with.
+/// - `stream`: The [`HttpClient`] to connect with (see [`build_stream_client()`]).
+/// - `parser`: The [`StreamParser`] that remembers the last message ID and the reconnection delay across connections.
+/// - `cache`: The [`HttpCache`] of the server, in which short-lived tokens are kept.
+/// - `stopped`: Set when the [`Poller`] is dropped, after which we stop.
+///
+/// # Returns
+/// Whether to reconnect, which is not the case if the event loop is gone or we're stopped.
+///
+/// # Errors
+/// This function errors if we failed to connect or if the connection broke.
+async fn read_stream<C: HttpClient>(
+    worker: &mut Worker,
+    client: &C,
+    stream: &C,
+    parser: &mut StreamParser,
+    cache: &mut HttpCache,
+    stopped: &AtomicBool,
+) -> Result<bool, Error> {
+    let server: ServerConfig = worker.server.clone();
+    debug!("Connecting to event stream of server '{}' at '{}'...", server.name, server.url);
+    let mut auth: Option<(HeaderName, HeaderValue)> = auth_header(client, &server, cache).await?;
+    let res: C::Response = match open_stream(stream, &server, auth.as_ref(), parser.last_event_id.as_deref()).await {
+        // Short-lived tokens may be revoked before they expire, so get a new one and try once more
+        Err(Error::ResponseStatus { status, .. }) if status == StatusCode::UNAUTHORIZED && server.oauth.is_some() => {
+            debug!("Server '{}' rejected its access token; requesting a new one", server.name);
+            cache.access_token = None;
+            auth = auth_header(client, &server, cache).await?;
+            open_stream(stream, &server, auth.as_ref(), parser.last_event_id.as_deref()).await?
+        },
+        res => res?,
+    };
+    if run_blocking(|| worker.process(Ok(Vec::new()), cache.clone())).is_none() {
+        return Ok(false);
+    }
+
+    // Read it chunk by chunk, and those line by line, while waking up in time to notify the digest
+    let mut body: <C::Response as HttpResponse>::Body = res.into_body();
+    let mut buffer: Vec<u8> = Vec::new();
+    while !stopped.load(Ordering::Relaxed) {
+        match body.chunk(worker.digest_due()).await {
+            Ok(Some(chunk)) => buffer.extend_from_slice(&chunk),
+            Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                run_blocking(|| worker.flush_digest());
+                continue;
+            },
+            Ok(None) => {
+                debug!("Server '{}' closed its event stream", server.name);
+                return Ok(true);
+            },
+            Err(err) if is_timeout(&err) => {
+                debug!("Event stream of server '{}' was quiet for {}s; reconnecting", server.name, STREAM_IDLE_TIMEOUT.as_secs());
+                return Ok(true);
+            },
+            Err(err) => return Err(Error::StreamRead { url: server.url.clone(), err }),
+        }
+        while let Some(end) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let Some(data) = parser.feed(&String::from_utf8_lossy(&line)) else { continue };
+            match parse_stream_data(&server, &data, worker.state.clock()) {
+                Ok(events) => {
+                    if run_blocking(|| worker.process(Ok(events), cache.clone())).is_none() {
+                        return Ok(false);
+                    }
+                },
+                // A single bad message doesn't spoil the stream
+                Err(err) => warn!("{}", err.trace()),
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Keeps a server's event stream open on its own thread, reconnecting whenever it ends.
+///
+/// # Arguments
+/// - `worker`: The [`Worker`] that processes the messages.
+/// - `client`: The HTTP [`Client`] to get short-lived tokens with.
+/// - `stream`: The HTTP [`Client`] to connect with (see [`build_stream_client()`]).
+/// - `stopped`: Set when the [`Poller`] is dropped, after which we stop.
+#[cfg(not(feature = "async"))]
+fn stream_loop(mut worker: Worker, client: Client, stream: Client, stopped: Arc<AtomicBool>) {
+    let mut parser: StreamParser = StreamParser::default();
+    while !stopped.load(Ordering::Relaxed) {
+        let mut cache: HttpCache = worker.cache();
+        let delay: Duration = match pollster::block_on(read_stream(&mut worker, &client, &stream, &mut parser, &mut cache, &stopped)) {
+            Ok(true) => parser.retry.unwrap_or(STREAM_RECONNECT_DELAY),
+            Ok(false) => break,
+            Err(err) => {
+                if worker.process(Err(err), cache).is_none() {
+                    return;
+                }
+                backoff(parser.retry.unwrap_or(STREAM_RECONNECT_DELAY), worker.failures)
+            },
+        };
+        // Parked instead of asleep, such that `Poller::refresh()` and dropping the `Poller` can wake us early
+        thread::park_timeout(delay);
+    }
+    debug!("Stopping event stream of server '{}'", worker.server.name);
+}
+
+/// Keeps a server's event stream open as an asynchronous task, reconnecting whenever it ends.
+///
+/// # Arguments
+/// - `worker`: The [`Worker`] that processes the messages.
+/// - `client`: The asynchronous HTTP [`reqwest::Client`] to get short-lived tokens with.
+/// - `stream`: The asynchronous HTTP [`reqwest::Client`] to connect with (see `build_async_stream_client()`).
+/// - `refresh`: A [`Notify`](tokio::sync::Notify) that makes us reconnect early when `Poller::refresh()` is called.
+/// - `stopped`: Set when the [`Poller`] is dropped, after which we stop.
+#[cfg(feature = "async")]
+async fn stream_task(mut worker: Worker, client: reqwest::Client, stream: reqwest::Client, refresh: Arc<tokio::sync::Notify>, stopped: Arc<AtomicBool>) {
+    let mut parser: StreamParser = StreamParser::default();
+    while !stopped.load(Ordering::Relaxed) {
+        let mut cache: HttpCache = worker.cache();
+        let delay: Duration = match read_stream(&mut worker, &client, &stream, &mut parser, &mut cache, &stopped).await {
+            Ok(true) => parser.retry.unwrap_or(STREAM_RECONNECT_DELAY),
+            Ok(false) => return,
+            Err(err) => {
+                if tokio::task::block_in_place(|| worker.process(Err(err), cache)).is_none() {
+                    return;
+                }
+                backoff(parser.retry.unwrap_or(STREAM_RECONNECT_DELAY), worker.failures)
+            },
+        };
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {},
+            _ = refresh.notified() => {},
+        }
+    }
+}
+
+/// Reads a server's WebSocket until it closes.
+///
+/// Every text message is processed as if it were a poll that reported its events, and connecting counts as a poll that reported
+/// none. The server is pinged whenever the socket has been quiet for [`SOCKET_PING_INTERVAL`]; tungstenite answers its pings for us.
+///
+/// # Arguments
+/// - `worker`: The [`Worker`] that processes the messages.
+/// - `client`: The HTTP [`Client`] to get short-lived tokens with.
+/// - `tls`: The TLS config to connect with (see [`build_socket_tls()`]).
+/// - `cache`: The [`HttpCache`] of the server, in which short-lived tokens are kept.
+/// - `stopped`: Set when the [`Poller`] is dropped, after which we close the socket.
+///
+/// # Returns
+/// Whether to reconnect, which is not the case if the event loop is gone or we're stopped.
+///
+/// # Errors
+/// This function errors if we failed to connect, if the connection broke or if the server stopped answering our pings.
+fn read_socket(worker: &mut Worker, client: &Client, tls: &Arc<rustls::ClientConfig>, cache: &mut HttpCache, stopped: &AtomicBool) -> Result<bool, Error> {
+    let server: ServerConfig = worker.server.clone();
+    debug!("Connecting to WebSocket of server '{}' at '{}'...", server.name, server.url);
+    let mut auth: Option<(HeaderName, HeaderValue)> = pollster::block_on(auth_header(client, &server, cache))?;
+    let mut socket: WebSocket<MaybeTlsStream<TcpStream>> = match open_socket(&server, tls, auth.as_ref()) {
+        // Short-lived tokens may be revoked before they expire, so get a new one and try once more
+        Err(Error::ResponseStatus { status, .. }) if status == StatusCode::UNAUTHORIZED && server.oauth.is_some() => {
+            debug!("Server '{}' rejected its access token; requesting a new one", server.name);
+            cache.access_token = None;
+            auth = pollster::block_on(auth_header(client, &server, cache))?;
+            open_socket(&server, tls, auth.as_ref())?
+        },
+        res => res?,
+    };
+    if worker.process(Ok(Vec::new()), cache.clone()).is_none() {
+        close_socket(&mut socket, &server);
+        return Ok(false);
+    }
+
+    // Read it message by message
+    let mut last_seen: Instant = Instant::now();
+    let mut pinged: Option<Instant> = None;
+    while !stopped.load(Ordering::Relaxed) {
+        let msg: Message = match socket.read() {
+            Ok(msg) => msg,
+            // Nothing to read, so see if the server is still there
+            Err(tungstenite::Error::Io(err)) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                // This happens every `SOCKET_READ_TIMEOUT`, which is often enough to notify the digest in time
+                worker.flush_digest();
+                match pinged {
+                    Some(pinged) if pinged.elapsed() >= SOCKET_PONG_TIMEOUT => return Err(Error::SocketTimeout { url: server.url.clone() }),
+                    Some(_) => {},
+                    None if last_seen.elapsed() >= SOCKET_PING_INTERVAL => {
+                        trace!("Pinging server '{}' over its WebSocket", server.name);
+                        if let Err(err) = socket.send(Message::Ping(Vec::new())) {
+                            return Err(Error::SocketRead { url: server.url.clone(), err: Box::new(err) });
+                        }
+                        pinged = Some(Instant::now());
+                    },
+                    None => {},
+                }
+                continue;
+            },
+            Err(tungstenite::Error::ConnectionClosed) => {
+                debug!("Server '{}' closed its WebSocket", server.name);
+                return Ok(true);
+            },
+            Err(err) => return Err(Error::SocketRead { url: server.url.clone(), err: Box::new(err) }),
+        };
+        last_seen = Instant::now();
+        pinged = None;
+        match msg {
+            Message::Text(text) => match parse_stream_data(&server, &text, worker.state.clock()) {
+                Ok(events) => {
+                    if worker.process(Ok(events), cache.clone()).is_none() {
+                        close_socket(&mut socket, &server);
+                        return Ok(false);
+                    }
+                },
+                // A single bad message doesn't spoil the socket
+                Err(err) => warn!("{}", err.trace()),
+            },
+            Message::Binary(_) => debug!("Ignoring binary message from server '{}'", server.name),
+            // tungstenite answers pings and finishes the close handshake for us
+            Message::Ping(_) | Message::Pong(_) | Message::Close(_) | Message::Frame(_) => {},
+        }
+    }
+    close_socket(&mut socket, &server);
+    Ok(false)
+}
+
+/// Keeps a WebSocket to a server open, reconnecting whenever it closes.
+///
+/// This blocks, so with the `async` feature, it runs on one of the runtime's blocking threads.
+///
+/// # Arguments
+/// - `worker`: The [`Worker`] that processes the messages.
+/// - `client`: The HTTP [`Client`] to get short-lived tokens with.
+/// - `tls`: The TLS config to connect with (see [`build_socket_tls()`]).
+/// - `refreshes`: Counts the calls to `Poller::refresh()`, which make us reconnect early.
+/// - `stopped`: Set when the [`Poller`] is dropped, after which we close the socket and stop.
+fn socket_loop(mut worker: Worker, client: Client, tls: Arc<rustls::ClientConfig>, refreshes: Arc<AtomicUsize>, stopped: Arc<AtomicBool>) {
+    while !stopped.load(Ordering::Relaxed) {
+        let mut cache: HttpCache = worker.cache();
+        let delay: Duration = match read_socket(&mut worker, &client, &tls, &mut cache, &stopped) {
+            Ok(true) => STREAM_RECONNECT_DELAY,
+            Ok(false) => break,
+            Err(err) => {
+                if worker.process(Err(err), cache).is_none() {
+                    return;
+                }
+                backoff(STREAM_RECONNECT_DELAY, worker.failures)
+            },
+        };
+        // Parked in short steps, such that we notice being stopped or refreshed even when nobody unparks us
+        let deadline: Instant = Instant::now() + delay;
+        let refreshed: usize = refreshes.load(Ordering::Relaxed);
+        while !stopped.load(Ordering::Relaxed) && refreshes.load(Ordering::Relaxed) == refreshed && Instant::now() < deadline {
+            thread::park_timeout(deadline.saturating_duration_since(Instant::now()).min(SOCKET_READ_TIMEOUT));
+        }
+    }
+    debug!("Stopping WebSocket of server '{}'", worker.server.name);
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Describes a request to a server, regardless of the client that sends it.
+#[derive(Debug)]
+struct Outgoing {
+    /// The address to send the request to.
+    url:     Url,
+    /// The headers to send along.
+    headers: HeaderMap,
+    /// If given, the request POSTs this form instead of being a GET.
+    form:    Option<Vec<(&'static str, String)>>,
+}
+
+
+
+/// Sends [`Outgoing`] requests, such that talking to servers is written once for both the blocking and the asynchronous client.
+///
+/// The blocking [`Client`] never suspends, so its futures are simply driven with [`pollster::block_on()`].
+trait HttpClient {
+    /// The response to a request.
+    type Response: HttpResponse;
+
+    /// Sends a request and waits for the head of its response.
+    ///
+    /// # Arguments
+    /// - `req`: The [`Outgoing`] request to send.
+    ///
+    /// # Returns
+    /// The response, of which the body is yet to be read.
+    ///
+    /// # Errors
+    /// This function errors if we failed to reach the server.
+    async fn send(&self, req: Outgoing) -> Result<Self::Response, reqwest::Error>;
+}
+impl HttpClient for Client {
+    type Response = Response;
+
+    async fn send(&self, req: Outgoing) -> Result<Response, reqwest::Error> {
+        let builder: RequestBuilder = match &req.form {
+            Some(form) => self.post(req.url).form(form),
+            None => self.get(req.url),
+        };
+        // Streams need no timeout here, as the client of `build_stream_client()` already has an idle timeout
+        builder.headers(req.headers).send()
+    }
+}
+#[cfg(feature = "async")]
+impl HttpClient for reqwest::Client {
+    type Response = reqwest::Response;
+
+    async fn send(&self, req: Outgoing) -> Result<reqwest::Response, reqwest::Error> {
+        // Streams need no timeout here, as the client of `build_async_stream_client()` already has an idle timeout
+        let builder: reqwest::RequestBuilder = match &req.form {
+            Some(form) => self.post(req.url).form(form),
+            None => self.get(req.url),
+        };
+        builder.headers(req.headers).send().await
+    }
+}
+
+/// The response to an [`Outgoing`] request, of which the body is yet to be read.
+trait HttpResponse {
+    /// The body of the response, when read part by part.
+    type Body: HttpBody;
+
+    /// Returns the status code of the response.
+    fn status(&self) -> StatusCode;
+
+    /// Returns the headers of the response.
+    fn headers(&self) -> &HeaderMap;
+
+    /// Reads the whole body as text.
+    ///
+    /// # Errors
+    /// This function errors if we failed to download the body.
+    async fn text(self) -> Result<String, reqwest::Error>;
+
+    /// Turns the response into its body, to read part by part as the server sends it (e.g., an event stream).
+    fn into_body(self) -> Self::Body;
+}
+impl HttpResponse for Response {
+    type Body = BlockingBody;
+
+    #[inline]
+    fn status(&self) -> StatusCode { Response::status(self) }
+
+    #[inline]
+    fn headers(&self) -> &HeaderMap { Response::headers(self) }
+
+    #[inline]
+    async fn text(self) -> Result<String, reqwest::Error> { Response::text(self) }
+
+    fn into_body(mut self) -> BlockingBody {
+        // Reading blocks until the server sends something, so it gets a thread of its own that we can stop waiting for
+        let (sender, chunks): (SyncSender<BodyChunk>, Receiver<BodyChunk>) = mpsc::sync_channel(1);
+        thread::spawn(move || {
+            let mut chunk: Vec<u8> = vec![0; STREAM_CHUNK_SIZE];
+            loop {
+                let res: BodyChunk = match std::io::Read::read(&mut self, &mut chunk) {
+                    Ok(0) => Ok(None),
+                    Ok(len) => Ok(Some(chunk[..len].to_vec())),
+                    Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                    Err(err) => Err(err),
+                };
+                // Stop once the body ends or nobody's listening anymore
+                let more: bool = matches!(res, Ok(Some(_)));
+                if sender.send(res).is_err() || !more {
+                    return;
+                }
+            }
+        });
+        BlockingBody { chunks }
+    }
+}
+#[cfg(feature = "async")]
+impl HttpResponse for reqwest::Response {
+    type Body = reqwest::Response;
+
+    #[inline]
+    fn status(&self) -> StatusCode { reqwest::Response::status(self) }
+
+    #[inline]
+    fn headers(&self) -> &HeaderMap { reqwest::Response::headers(self) }
+
+    #[inline]
+    async fn text(self) -> Result<String, reqwest::Error> { reqwest::Response::text(self).await }
+
+    #[inline]
+    fn into_body(self) -> reqwest::Response { self }
+}
+
+/// The body of an [`HttpResponse`] that is read part by part, as the server sends it.
+trait HttpBody {
+    /// Reads the next part of the body.
+    ///
+    /// # Arguments
+    /// - `wait`: How long to wait for it at most, or [`None`] to wait until the server sends something.
+    ///
+    /// # Returns
+    /// The bytes that were read, or [`None`] if the body has ended.
+    ///
+    /// # Errors
+    /// This function errors with [`ErrorKind::WouldBlock`] if nothing arrived within `wait`. Otherwise, it errors if we failed to read
+    /// from the connection, which includes it being quiet for too long (see [`is_timeout()`]).
+    async fn chunk(&mut self, wait: Option<Duration>) -> Result<Option<Vec<u8>>, std::io::Error>;
+}
+
+/// One read from the body of a blocking [`Response`]: either the next part, [`None`] if the body ended, or why reading failed.
+type BodyChunk = Result<Option<Vec<u8>>, std::io::Error>;
+
+/// The body of a blocking [`Response`], which is read on a thread of its own such that we can stop waiting for it.
+#[derive(Debug)]
+struct BlockingBody {
+    /// Receives the parts of the body that the thread read.
+    chunks: Receiver<BodyChunk>,
+}
+impl HttpBody for BlockingBody {
+    async fn chunk(&mut self, wait: Option<Duration>) -> Result<Option<Vec<u8>>, std::io::Error> {
+        match wait {
+            Some(wait) => match self.chunks.recv_timeout(wait) {
+                Ok(res) => res,
+                Err(RecvTimeoutError::Timeout) => Err(std::io::Error::from(ErrorKind::WouldBlock)),
+                Err(RecvTimeoutError::Disconnected) => Ok(None),
+            },
+            None => self.chunks.recv().unwrap_or(Ok(None)),
+        }
+    }
+}
+#[cfg(feature = "async")]
+impl HttpBody for reqwest::Response {
+    async fn chunk(&mut self, wait: Option<Duration>) -> Result<Option<Vec<u8>>, std::io::Error> {
+        let next = async { reqwest::Response::chunk(self).await.map(|chunk| chunk.map(|chunk| chunk.to_vec())) };
+        let res: Result<Option<Vec<u8>>, reqwest::Error> = match wait {
+            Some(wait) => match tokio::time::timeout(wait, next).await {
+                Ok(res) => res,
+                Err(_) => return Err(std::io::Error::from(ErrorKind::WouldBlock)),
+            },
+            None => next.await,
+        };
+        match res {
+            Ok(chunk) => Ok(chunk),
+            Err(err) => {
+                let kind: ErrorKind = if err.is_timeout() { ErrorKind::TimedOut } else { ErrorKind::Other };
+                Err(std::io::Error::new(kind, err))
+            },
+        }
+    }
+}
+
+
+
+/// Verifies TLS certificates of WebSockets by accepting any, for servers with [`TlsConfig::danger_accept_invalid`].
+///
+/// The handshake signatures are still checked, such that the connection is at least with whoever owns the certificate.
+#[derive(Debug)]
+struct AcceptAnyCert {
+    /// The cryptography to check the handshake signatures with.
+    provider: Arc<CryptoProvider>,
+}
+impl ServerCertVerifier for AcceptAnyCert {
+    #[inline]
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    #[inline]
+    fn verify_tls12_signature(&self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    #[inline]
+    fn verify_tls13_signature(&self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    #[inline]
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> { self.provider.signature_verification_algorithms.supported_schemes() }
+}
+
+
+
+/// Limits how many servers are polled at the same time, by handing out a limited number of [`PollSlot`]s.
+#[cfg(not(feature = "async"))]
+#[derive(Debug)]
+struct PollSlots {
+    /// The number of slots.
+    max:   usize,
+    /// The number of slots that are handed out.
+    used:  Mutex<usize>,
+    /// Signalled whenever a slot is returned.
+    freed: Condvar,
+}
+#[cfg(not(feature = "async"))]
+impl PollSlots {
+    /// Constructor for the PollSlots.
+    ///
+    /// # Arguments
+    /// - `max`: The number of slots. Should be at least 1.
+    ///
+    /// # Returns
+    /// A new PollSlots with all slots available.
+    #[inline]
+    fn new(max: usize) -> Self { Self { max, used: Mutex::new(0), freed: Condvar::new() } }
+
+    /// Takes a slot, waiting for one to be returned if they're all handed out.
+    ///
+    /// # Returns
+    /// A [`PollSlot`] that returns the slot when it is dropped.
+    fn acquire(&self) -> PollSlot<'_> {
+        let mut used: MutexGuard<usize> = self.used.lock();
+        while *used >= self.max {
+            self.freed.wait(&mut used);
+        }
+        *used += 1;
+        PollSlot { slots: self }
+    }
+}
+
+/// Proves that a poller may poll its server right now. Returns its slot when dropped.
+#[cfg(not(feature = "async"))]
+struct PollSlot<'s> {
+    /// The slots that this one was taken from.
+    slots: &'s PollSlots,
+}
+#[cfg(not(feature = "async"))]
+impl Drop for PollSlot<'_> {
+    fn drop(&mut self) {
+        *self.slots.used.lock() -= 1;
+        self.slots.freed.notify_one();
+    }
+}
+
+
+
+/// Describes a file of events to replay instead of polling the servers, e.g., for demos or to reproduce bugs.
+#[derive(Clone, Debug)]
+pub struct Replay {
+    /// The path of the file, which has an [`Event`] as JSON on every line.
+    pub path:  PathBuf,
+    /// The factor by which replaying is faster than the time between the events' timestamps. `0.0` replays them all at once.
+    pub speed: f64,
+}
+
+
+
+/// Collects the events of a (paginated) response, page by page.
+struct Pages<'s> {
+    /// The server that sends the pages.
+    server: &'s ServerConfig,
+    /// The address of the current page.
+    url:    Url,
+    /// The number of the current page, counting from 1.
+    page:   usize,
+    /// The (unparsed) events on the pages so far.
+    raw:    Vec<serde_json::Value>,
+    /// The clock that decides when we received the events.
+    clock:  &'s dyn Clock,
+}
+impl<'s> Pages<'s> {
+    /// Constructor for the Pages.
+    ///
+    /// # Arguments
+    /// - `server`: The [`ServerConfig`] that sends the pages.
+    /// - `clock`: The [`Clock`] that decides when we received the events, for those without a timestamp (and for malformed ones).
+    ///
+    /// # Returns
+    /// A new Pages that expects the first page next.
+    #[inline]
+    fn new(server: &'s ServerConfig, clock: &'s dyn Clock) -> Self { Self { server, url: server.url.clone(), page: 1, raw: Vec::new(), clock } }
+
+    /// Adds the next page.
+    ///
+    /// # Arguments
+    /// - `body`: The parsed body of the page.
+    ///
+    /// # Returns
+    /// The address of the page after it, or [`None`] if this was the last one (or the last one we're allowed to fetch).
+    ///
+    /// # Errors
+    /// This function errors if the page does not have the shape that [`ServerConfig::pagination`] describes.
+    fn push(&mut self, body: serde_json::Value) -> Result<Option<Url>, Error> {
+        let (items, next): (Vec<serde_json::Value>, Option<Url>) = split_page(self.server, &self.url, body, self.page)?;
+        self.raw.extend(items);
+        let Some(next) = next else { return Ok(None) };
+        let max_pages: usize = self.server.pagination.as_ref().map(Pagination::max_pages).unwrap_or(1);
+        if self.page >= max_pages {
+            warn!("Server '{}' has more than {} page(s) of events; ignoring the rest", self.server.name, max_pages);
+            return Ok(None);
+        }
+        self.page += 1;
+        self.url = next.clone();
+        Ok(Some(next))
+    }
+
+    /// Parses the events on all pages.
+    ///
+    /// Malformed events are skipped, and reported in a single additional event instead.
+    ///
+    /// # Returns
+    /// The [`Event`]s, with their [`Event::source`] set to the server's name if it was left empty.
+    fn into_events(self) -> Vec<Event> {
+        // Parse the events, one by one such that a single malformed one doesn't spoil the others
+        let server: &ServerConfig = self.server;
+        let now: DateTime<Local> = self.clock.now();
+        let mut events: Vec<Event> = Vec::with_capacity(self.raw.len());
+        let mut failures: Vec<(usize, serde_json::Value, serde_json::Error)> = Vec::new();
+        for (i, raw) in self.raw.into_iter().enumerate() {
+            let mut value: serde_json::Value = match &server.mapping {
+                Some(mapping) => map_event(mapping, &raw),
+                None => raw.clone(),
+            };
+
+            // Events without a timestamp get the time we received them, which ingestion may still refuse
+            let missing: bool = value.get("timestamp").is_none_or(serde_json::Value::is_null);
+            if missing {
+                if let Some(fields) = value.as_object_mut() {
+                    fields.insert("timestamp".into(), now.to_rfc3339().into());
+                }
+            }
+            let parsed: Result<Event, serde_json::Error> = Event::deserialize(value);
+            let mut event: Event = match parsed {
+                Ok(event) => event,
+                Err(err) => {
+                    warn!("Skipping malformed event {} from server '{}': {}", i, server.name, err);
+                    failures.push((i, raw, err));
+                    continue;
+                },
+            };
+            if event.source.is_empty() {
+                event.source = server.name.clone();
+            }
+            if missing {
+                event.timestamp_flag = Some(TimestampFlag::Missing);
+            }
+            if let Some(field) = &server.event_url_field {
+                event.url = event_url(server, &raw, field);
+            }
+            if !server.skip_metadata {
+                event.metadata = event_metadata(server, &raw);
+            }
+            events.push(event);
+        }
+        if !failures.is_empty() {
+            events.push(malformed_event(server, &failures, now));
+        }
+        trace!("Server '{}' reported {} event(s) in {} page(s)", server.name, events.len(), self.page);
+        events
+    }
+}
+
+
+
+/// The part of a token endpoint's response that we care about.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    /// The short-lived token itself.
+    access_token: String,
+    /// The number of seconds after which the token expires, if the endpoint tells.
+    #[serde(default)]
+    expires_in:   Option<u64>,
+}
+impl TokenResponse {
+    /// Turns this response into an [`AccessToken`].
+    ///
+    /// # Arguments
+    /// - `now`: The time at which we got the response, from which the expiry is counted.
+    ///
+    /// # Returns
+    /// A new AccessToken.
+    #[inline]
+    fn into_access_token(self, now: Instant) -> AccessToken {
+        AccessToken { token: self.access_token, expires: self.expires_in.map(|secs| now + Duration::from_secs(secs)) }
+    }
+}
+
+
+
+/// Splits the lines of a server's event stream into messages.
+///
+/// Only `data`, `id` and `retry` are used; the kind of a message (`event`) is ignored, and so are comments, which servers send to keep
+/// the connection alive.
+#[derive(Debug, Default)]
+struct StreamParser {
+    /// The `data` of the message so far.
+    data: String,
+    /// Whether the message so far has any `data`, which may be empty.
+    has_data: bool,
+    /// The ID of the last message, which we send back when reconnecting.
+    last_event_id: Option<String>,
+    /// How long the server wants us to wait before reconnecting, if it told us.
+    retry: Option<Duration>,
+}
+impl StreamParser {
+    /// Processes the next line of the stream.
+    ///
+    /// # Arguments
+    /// - `line`: The line, with or without its line ending.
+    ///
+    /// # Returns
+    /// The `data` of a message if this line ended one, or [`None`] otherwise.
+    fn feed(&mut self, line: &str) -> Option<String> {
+        let line: &str = line.strip_suffix('\n').unwrap_or(line);
+        let line: &str = line.strip_suffix('\r').unwrap_or(line);
+
+        // An empty line ends the message
+        if line.is_empty() {
+            if !self.has_data {
+                return None;
+            }
+            self.has_data = false;
+            return Some(std::mem::take(&mut self.data));
+        }
+
+        let (field, value): (&str, &str) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "data" => {
+                if self.has_data {
+                    self.data.push('\n');
+                }
+                self.data.push_str(value);
+                self.has_data = true;
+            },
+            "id" if !value.contains('\0') => self.last_event_id = if value.is_empty() { None } else { Some(value.into()) },
+            "retry" => {
+                if let Ok(millis) = value.parse::<u64>() {
+                    self.retry = Some(Duration::from_millis(millis));
+                }
+            },
+            _ => {},
+        }
+        None
+    }
+}
+
+
+
+/// Processes the polls of a single server, regardless of how they're sent.
+struct Worker {
+    /// The state to ingest the events into.
+    state: AppState,
+    /// Used to notify the main loop of new events.
+    proxy: AppEventLoopProxy,
+    /// Used to tell the user about new events.
+    notifiers: Arc<Notifiers>,
+    /// The server to poll.
+    server: ServerConfig,
+    /// The time we waited before the last poll.
+    interval: Duration,
+    /// The number of polls in a row that failed, which makes us back off.
+    failures: u32,
+    /// Whether we've had a successful poll yet; this survives connection failures on purpose.
+    seeded: bool,
+    /// The events waiting to be notified about in a digest.
+    digest: Vec<Event>,
+    /// Since when we've been collecting the events in the digest.
+    digest_since: Instant,
+}
+impl Worker {
+    /// Constructor for the Worker.
+    ///
+    /// # Arguments
+    /// - `state`: The [`AppState`] to ingest the events into.
+    /// - `proxy`: An [`AppEventLoopProxy`] to notify the main loop of new events.
+    /// - `notifiers`: The [`Notifiers`] to tell the user about new events with.
+    /// - `server`: The [`ServerConfig`] to poll.
+    ///
+    /// # Returns
+    /// A new Worker that has yet to see its first poll.
+    fn new(state: AppState, proxy: AppEventLoopProxy, notifiers: Arc<Notifiers>, server: ServerConfig) -> Self {
+        match server.transport {
+            Transport::Poll => {
+                info!(
+                    "Starting poller for server '{}' (every {}s{})",
+                    server.name,
+                    server.poll_interval.as_secs(),
+                    if server.adaptive { ", adaptive" } else { "" }
+                )
+            },
+            Transport::Sse => info!("Starting event stream for server '{}'", server.name),
+            Transport::WebSocket => info!("Starting WebSocket for server '{}'", server.name),
+        }
+        // Servers that a previous poller already polled (e.g., before the config was reloaded) don't need a new starting point
+        let seeded: bool = state.access(|state| state.last_poll.contains_key(&server.name));
+        Self { state, proxy, notifiers, interval: server.poll_interval, failures: 0, server, seeded, digest: Vec::new(), digest_since: Instant::now() }
+    }
+
+    /// Returns the [`HttpCache`] to send with the next poll.
+    ///
+    /// # Returns
+    /// What the server told us about the events of the last successful poll, if anything.
+    #[inline]
+    fn cache(&self) -> HttpCache { self.state.access(|state| state.http_cache.get(&self.server.name).cloned().unwrap_or_default()) }
+    /// Processes the result of a poll.
+    ///
+    /// This ingests the new events, keeps track of whether the server is reachable and tells both the main loop and the user about
+    /// anything new.
+    ///
+    /// # Arguments
+    /// - `res`: The result of [`poll_once()`] (or its asynchronous counterpart).
+    /// - `cache`: The [`HttpCache`] that was updated by the poll.
+    ///
+    /// # Returns
+    /// How long to wait before the next poll, or [`None`] if the event loop is gone and we should stop polling.
+    fn process(&mut self, res: Result<Vec<Event>, Error>, cache: HttpCache) -> Option<Duration> {
+        let mut new: Vec<Event> = Vec::new();
+        let mut silent: bool = false;
+        let (status, auth_failed): (ServerStatus, bool) = match res {
+            Ok(events) => {
+                let now: DateTime<Local> = self.state.now();
+                if let Ok(Err(err)) = self.state.access_mut(|state| -> Result<(), Infallible> {
+                    new = state.ingest(events, now);
+                    state.http_cache.insert(self.server.name.clone(), cache);
+                    state.last_poll.insert(self.server.name.clone(), now);
+
+                    // Take the events of the first poll as a starting point, unless the user wants to hear about them
+                    if !self.seeded && !state.notify_on_first_poll {
+                        for event in state.events.iter_mut().filter(|event| event.source == self.server.name) {
+                            event.read = true;
+                        }
+                        state.mark_history_dirty();
+                        silent = true;
+                    }
+                    Ok(())
+                }) {
+                    warn!("{}", err.trace());
+                }
+                self.seeded = true;
+                self.failures = 0;
+                (ServerStatus::Ok, false)
+            },
+            // Polling failures are not fatal; we'll just try again next cycle
+            Err(err) => {
+                warn!("{}", err.trace());
+                self.failures = self.failures.saturating_add(1);
+                // Still hold on to any short-lived token we got, such that we don't have to ask for one again
+                if cache.access_token.is_some() {
+                    if let Ok(Err(err)) = self.state.access_mut(|state| -> Result<(), Infallible> {
+                        state.http_cache.entry(self.server.name.clone()).or_default().access_token = cache.access_token;
+                        Ok(())
+                    }) {
+                        warn!("{}", err.trace());
+                    }
+                }
+                (if err.is_unreachable() { ServerStatus::Unreachable } else { ServerStatus::Degraded }, err.is_auth_failure())
+            },
+        };
+
+        // Keep a record of the new events, if the user wants one
+        if let Some(dir) = self.state.access(|state| state.csv_export_path.clone()) {
+            if let Err(err) = crate::export::append_csv(&dir, &new, self.state.now()) {
+                warn!("{}", err.trace());
+            }
+        }
+
+        // Keep track of whether we can authenticate to the server, which the user has to fix rather than wait out
+        if auth_failed != self.state.access(|state| state.auth_failed.contains(&self.server.name)) {
+            if let Ok(Err(err)) = self.state.access_mut(|state| -> Result<(), Infallible> {
+                if auth_failed {
+                    state.auth_failed.insert(self.server.name.clone());
+                } else {
+                    state.auth_failed.remove(&self.server.name);
+                }
+                Ok(())
+            }) {
+                warn!("{}", err.trace());
+            }
+        }
+
+        // Keep track of whether the server is reachable (and for how long it hasn't been, such that the tray icon can wait out hiccups)
+        let (old_status, old_failures): (Option<ServerStatus>, u32) =
+            self.state.access(|state| (state.status.get(&self.server.name).copied(), state.failures.get(&self.server.name).copied().unwrap_or(0)));
+        if old_status != Some(status) || old_failures != self.failures {
+            if let Ok(Err(err)) = self.state.access_mut(|state| -> Result<(), Infallible> {
+                if status == ServerStatus::Ok {
+                    state.offline.remove(&self.server.name);
+                    state.failures.remove(&self.server.name);
+                } else {
+                    state.offline.insert(self.server.name.clone());
+                    state.failures.insert(self.server.name.clone(), self.failures);
+                }
+                state.status.insert(self.server.name.clone(), status);
+                Ok(())
+            }) {
+                warn!("{}", err.trace());
+            }
+        }
+        let threshold: u32 = self.state.access(|state| state.warn_after_failures);
+        let crossed: bool = status == ServerStatus::Unreachable && self.failures == threshold.max(1);
+        if (old_status != Some(status) || crossed) && self.proxy.send_event(AppEvent::ConnectionChanged { server: self.server.name.clone(), status }).is_err() {
+            debug!("Event loop is gone; stopping poller for server '{}'", self.server.name);
+            return None;
+        }
+
+        // Tell the main loop and the user about new events
+        if !new.is_empty() {
+            debug!("Received {} new event(s) from server '{}'{}", new.len(), self.server.name, if silent { " (silently)" } else { "" });
+            if self.proxy.send_event(AppEvent::NewEvents { server: self.server.name.clone(), count: new.len(), silent }).is_err() {
+                debug!("Event loop is gone; stopping poller for server '{}'", self.server.name);
+                return None;
+            }
+            // Automation gets everything, unless the user wants the webhook muted along with the notifications
+            let forward: bool = !silent && (!self.state.access(|state| state.webhook_respects_mute) || !self.state.effective_muted());
+            if forward {
+                for event in &new {
+                    self.notifiers.forward(&self.server.name, event);
+                }
+            }
+            if !silent && !self.state.effective_muted() {
+                let now: DateTime<Local> = self.state.now();
+                for event in &new {
+                    // Don't bother the user about titles that they snoozed
+                    if self.state.access(|state| state.is_snoozed(&event.title, now)) {
+                        trace!("Not notifying about event '{}' from server '{}' because its title is snoozed", event.id, self.server.name);
+                        continue;
+                    }
+                    let min_severity: Severity = self.state.access(|state| state.min_severity);
+                    if min_severity.is_more_severe_than(event.severity) {
+                        trace!(
+                            "Not notifying about event '{}' from server '{}' because it's less severe than {}",
+                            event.id,
+                            self.server.name,
+                            min_severity.variant()
+                        );
+                        continue;
+                    }
+                    if self.server.digest_interval.is_none() || (self.server.digest_bypass_critical && event.severity == Severity::Critical) {
+                        let icon: Option<String> = self.state.access(|state| state.icon(event).map(String::from));
+                        self.notifiers.notify(event, icon.as_deref());
+                    } else {
+                        if self.digest.is_empty() {
+                            self.digest_since = Instant::now();
+                        }
+                        self.digest.push(event.clone());
+                    }
+                }
+            }
+        }
+        self.interval = next_interval(&self.server, self.interval, !new.is_empty());
+
+        // Flush the digest if it's due; otherwise, make sure we wake up in time to do so
+        let mut sleep: Duration = backoff(self.interval, self.failures);
+        if self.failures > 0 {
+            debug!("Backing off from server '{}' for {}s after {} failed poll(s)", self.server.name, sleep.as_secs(), self.failures);
+        }
+        if let Some(due) = self.flush_digest() {
+            sleep = sleep.min(due);
+        }
+        trace!("Polling server '{}' again in {}s", self.server.name, sleep.as_secs());
+        Some(sleep)
+    }
+
+    /// Returns how long until the digest is due.
+    ///
+    /// # Returns
+    /// The time left until we should notify the digest (which may be zero), or [`None`] if no events are waiting in it.
+    fn digest_due(&self) -> Option<Duration> {
+        let digest_interval: Duration = self.server.digest_interval.filter(|_| !self.digest.is_empty())?;
+        Some(digest_interval.saturating_sub(self.digest_since.elapsed()))
+    }
+
+    /// Notifies the user about the events waiting in the digest, if it's due.
+    ///
+    /// Pollers do this after every poll, but event streams and WebSockets call this whenever [`Worker::digest_due()`] passes while
+    /// they wait for a message.
+    ///
+    /// # Returns
+    /// How long until the digest is due if it isn't yet, or [`None`] if no events are waiting in it (anymore).
+    fn flush_digest(&mut self) -> Option<Duration> {
+        let due: Duration = self.digest_due()?;
+        if !due.is_zero() {
+            return Some(due);
+        }
+
+        // Don't bother the user with what happened while they muted us
+        if !self.state.effective_muted() {
+            debug!("Notifying digest of {} event(s) from server '{}'", self.digest.len(), self.server.name);
+            let digest: Event = crate::notify::digest(&self.server.name, &self.digest, self.state.now());
+            let icon: Option<String> = self.state.access(|state| state.icon(&digest).map(String::from));
+            self.notifiers.notify(&digest, icon.as_deref());
+        }
+        self.digest.clear();
+        None
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Manages the background workers that poll the configured servers.
+///
+/// Without the `async` feature, every server is polled by a thread of its own. With it, they are all polled by tasks on a single
+/// [`tokio`] runtime that is owned by the Poller, and which cancels them when it is dropped.
+#[derive(Debug)]
+pub struct Poller {
+    /// The handles of the threads polling each server.
+    #[cfg(not(feature = "async"))]
+    handles:   Vec<JoinHandle<()>>,
+    /// The handles of the threads keeping a WebSocket open, which we wait for when dropped such that they close it cleanly.
+    #[cfg(not(feature = "async"))]
+    sockets:   Vec<JoinHandle<()>>,
+    /// Tells the threads (or, with the `async` feature, the WebSockets and event streams) to stop once they wake up.
+    stopped:   Arc<AtomicBool>,
+    /// Counts the calls to [`Poller::refresh()`], such that WebSockets that are waiting to reconnect notice them.
+    refreshes: Arc<AtomicUsize>,
+    /// The runtime on which the servers are polled, which is only kept such that its tasks are cancelled when we're dropped.
+    #[cfg(feature = "async")]
+    _runtime:  tokio::runtime::Runtime,
+    /// Wakes up all polling and event stream tasks early.
+    #[cfg(feature = "async")]
+    refresh:   Arc<tokio::sync::Notify>,
+}
+impl Poller {
+    /// Spawns a new poller for every server in the given `state`.
+    ///
+    /// # Arguments
+    /// - `state`: The [`AppState`] that lists the servers and in which we ingest events.
+    /// - `proxy`: An [`AppEventLoopProxy`] to notify the main loop of new events.
+    /// - `notifiers`: The [`Notifiers`] to tell the user about new events with.
+    ///
+    /// # Returns
+    /// A new Poller that manages the spawned pollers.
+    ///
+    /// # Errors
+    /// This function errors if we failed to build the HTTP client (or, with the `async` feature, the runtime).
+    #[cfg(not(feature = "async"))]
+    pub fn spawn(state: AppState, proxy: AppEventLoopProxy, notifiers: Notifiers) -> Result<Self, Error> {
+        info!("Spawning pollers...");
+
+        // Build a client to share
+        let user_agent: Option<String> = state.access(|state| state.user_agent.clone());
+        let client: Client = build_client(&TlsConfig::default(), user_agent.as_deref())?;
+
+        // Spawn a thread per server
+        let notifiers: Arc<Notifiers> = Arc::new(notifiers);
+        let servers: Vec<ServerConfig> = state.access(|state| state.servers.clone());
+        let slots: Option<Arc<PollSlots>> = state.access(|state| state.max_concurrent_polls).filter(|max| *max > 0).map(|max| Arc::new(PollSlots::new(max)));
+        let stopped: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let refreshes: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+        let mut handles: Vec<JoinHandle<()>> = Vec::with_capacity(servers.len());
+        let mut sockets: Vec<JoinHandle<()>> = Vec::new();
+        for server in servers {
+            // Servers with their own TLS settings get a client of their own, such that the others are unaffected
+            let tls: TlsConfig = server.effective_tls();
+            if tls.danger_accept_invalid {
+                warn!("INSECURE: Not verifying TLS certificates of server '{}' ({}); only do this on trusted networks!", server.name, server.url);
+            }
+            let client: Client = if tls == TlsConfig::default() {
+                client.clone()
+            } else {
+                match build_client(&tls, user_agent.as_deref()) {
+                    Ok(client) => client,
+                    Err(err) => {
+                        error!("{} (not polling server '{}')", err.trace(), server.name);
+                        continue;
+                    },
+                }
+            };
+
+            let worker: Worker = Worker::new(state.clone(), proxy.clone(), notifiers.clone(), server);
+            let (slots, stopped): (Option<Arc<PollSlots>>, Arc<AtomicBool>) = (slots.clone(), stopped.clone());
+            match worker.server.transport {
+                Transport::Poll => handles.push(thread::spawn(move || poll_loop(worker, client, slots, stopped))),
+                // Streams are long-lived, so they don't take a slot, and they need a client that doesn't give up on them as a whole
+                Transport::Sse => match build_stream_client(&tls, user_agent.as_deref()) {
+                    Ok(stream) => handles.push(thread::spawn(move || stream_loop(worker, client, stream, stopped))),
+                    Err(err) => error!("{} (not polling server '{}')", err.trace(), worker.server.name),
+                },
+                Transport::WebSocket => match build_socket_tls(&tls) {
+                    Ok(tls) => {
+                        let refreshes: Arc<AtomicUsize> = refreshes.clone();
+                        sockets.push(thread::spawn(move || socket_loop(worker, client, tls, refreshes, stopped)))
+                    },
+                    Err(err) => error!("{} (not polling server '{}')", err.trace(), worker.server.name),
+                },
+            }
+        }
+
+        // Done
+        Ok(Self { handles, sockets, stopped, refreshes })
+    }
+
+    /// Spawns a new poller for every server in the given `state`.
+    ///
+    /// # Arguments
+    /// - `state`: The [`AppState`] that lists the servers and in which we ingest events.
+    /// - `proxy`: An [`AppEventLoopProxy`] to notify the main loop of new events.
+    /// - `notifiers`: The [`Notifiers`] to tell the user about new events with.
+    ///
+    /// # Returns
+    /// A new Poller that manages the spawned pollers.
+    ///
+    /// # Errors
+    /// This function errors if we failed to build the HTTP client (or, with the `async` feature, the runtime).
+    #[cfg(feature = "async")]
+    pub fn spawn(state: AppState, proxy: AppEventLoopProxy, notifiers: Notifiers) -> Result<Self, Error> {
+        info!("Spawning pollers...");
+
+        // Build the runtime and a client to share
+        let runtime: tokio::runtime::Runtime = match tokio::runtime::Builder::new_multi_thread().thread_name("poller").enable_all().build() {
+            Ok(runtime) => runtime,
+            Err(err) => return Err(Error::RuntimeCreate { err }),
+        };
+        let user_agent: Option<String> = state.access(|state| state.user_agent.clone());
+        let client: reqwest::Client = build_async_client(&TlsConfig::default(), user_agent.as_deref())?;
+
+        // Spawn a task per server
+        let notifiers: Arc<Notifiers> = Arc::new(notifiers);
+        let refresh: Arc<tokio::sync::Notify> = Arc::new(tokio::sync::Notify::new());
+        let slots: Option<Arc<tokio::sync::Semaphore>> =
+            state.access(|state| state.max_concurrent_polls).filter(|max| *max > 0).map(|max| Arc::new(tokio::sync::Semaphore::new(max)));
+        let stopped: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let refreshes: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+        for server in state.access(|state| state.servers.clone()) {
+            // Servers with their own TLS settings get a client of their own, such that the others are unaffected
+            let tls: TlsConfig = server.effective_tls();
+            if tls.danger_accept_invalid {
+                warn!("INSECURE: Not verifying TLS certificates of server '{}' ({}); only do this on trusted networks!", server.name, server.url);
+            }
+            let client: reqwest::Client = if tls == TlsConfig::default() {
+                client.clone()
+            } else {
+                match build_async_client(&tls, user_agent.as_deref()) {
+                    Ok(client) => client,
+                    Err(err) => {
+                        error!("{} (not polling server '{}')", err.trace(), server.name);
+                        continue;
+                    },
+                }
+            };
+
+            let worker: Worker = Worker::new(state.clone(), proxy.clone(), notifiers.clone(), server);
+            match worker.server.transport {
+                Transport::Poll => runtime.spawn(poll_task(worker, client, refresh.clone(), slots.clone())),
+                // Streams are long-lived, so they don't take a slot, and they need a client that doesn't give up on them as a whole
+                Transport::Sse => match build_async_stream_client(&tls, user_agent.as_deref()) {
+                    Ok(stream) => runtime.spawn(stream_task(worker, client, stream, refresh.clone(), stopped.clone())),
+                    Err(err) => {
+                        error!("{} (not polling server '{}')", err.trace(), worker.server.name);
+                        continue;
+                    },
+                },
+                // WebSockets block, and only need a client for short-lived tokens
+                Transport::WebSocket => {
+                    let (client, tls): (Client, Arc<rustls::ClientConfig>) =
+                        match build_client(&tls, user_agent.as_deref()).and_then(|client| Ok((client, build_socket_tls(&tls)?))) {
+                            Ok(res) => res,
+                            Err(err) => {
+                                error!("{} (not polling server '{}')", err.trace(), worker.server.name);
+                                continue;
+                            },
+                        };
+                    let (refreshes, stopped): (Arc<AtomicUsize>, Arc<AtomicBool>) = (refreshes.clone(), stopped.clone());
+                    runtime.spawn_blocking(move || socket_loop(worker, client, tls, refreshes, stopped))
+                },
+            };
+        }
+
+        // Done
+        Ok(Self { _runtime: runtime, refresh, stopped, refreshes })
+    }
+
+    /// Spawns a single poller that replays events from a file instead of polling the servers in the given `state`.
+    ///
+    /// The events are ingested and notified about as if a server named `replay` reported them.
+    ///
+    /// # Arguments
+    /// - `state`: The [`AppState`] in which we ingest events.
+    /// - `proxy`: An [`AppEventLoopProxy`] to notify the main loop of new events.
+    /// - `notifiers`: The [`Notifiers`] to tell the user about new events with.
+    /// - `replay`: The [`Replay`] that describes which file to replay, and how fast.
+    ///
+    /// # Returns
+    /// A new Poller that manages the replay.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read the file (or, with the `async` feature, to build the runtime).
+    pub fn replay(state: AppState, proxy: AppEventLoopProxy, notifiers: Notifiers, replay: &Replay) -> Result<Self, Error> {
+        info!("Replaying events from '{}' at {}x speed...", replay.path.display(), replay.speed);
+        let schedule: Vec<(Duration, Event)> = load_replay(&replay.path, replay.speed)?;
+        let url: Url = match std::path::absolute(&replay.path).ok().and_then(|path| Url::from_file_path(path).ok()) {
+            Some(url) => url,
+            None => return Err(Error::ReplayPath { path: replay.path.clone() }),
+        };
+        let mut worker: Worker = Worker::new(state, proxy, Arc::new(notifiers), ServerConfig::new("replay", url));
+        // Every replayed event is new, including the first ones
+        worker.seeded = true;
+
+        #[cfg(not(feature = "async"))]
+        {
+            let stopped: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+            let handle: JoinHandle<()> = {
+                let stopped: Arc<AtomicBool> = stopped.clone();
+                thread::spawn(move || replay_loop(worker, schedule, stopped))
+            };
+            Ok(Self { handles: vec![handle], sockets: Vec::new(), stopped, refreshes: Arc::new(AtomicUsize::new(0)) })
+        }
+        #[cfg(feature = "async")]
+        {
+            let runtime: tokio::runtime::Runtime = match tokio::runtime::Builder::new_multi_thread().thread_name("poller").enable_all().build() {
+                Ok(runtime) => runtime,
+                Err(err) => return Err(Error::RuntimeCreate { err }),
+            };
+            runtime.spawn(replay_task(worker, schedule));
+            Ok(Self {
+                _runtime:  runtime,
+                refresh:   Arc::new(tokio::sync::Notify::new()),
+                stopped:   Arc::new(AtomicBool::new(false)),
+                refreshes: Arc::new(AtomicUsize::new(0)),
+            })
+        }
+    }
+
+    /// Makes all pollers poll their server right away, instead of waiting for their next turn.
+    ///
+    /// Event streams and WebSockets that are connected already report events as they happen; those that are waiting to reconnect
+    /// do so right away.
+    pub fn refresh(&self) {
+        info!("Refreshing all servers...");
+        self.refreshes.fetch_add(1, Ordering::Relaxed);
+        #[cfg(not(feature = "async"))]
+        for handle in self.handles.iter().chain(&self.sockets) {
+            handle.thread().unpark();
+        }
+        #[cfg(feature = "async")]
+        self.refresh.notify_waiters();
+    }
+}
+#[cfg(not(feature = "async"))]
+impl Drop for Poller {
+    fn drop(&mut self) {
+        // Threads that are in the middle of a request stop after it; we don't wait for them, as that may take a while
+        debug!("Stopping pollers...");
+        self.stopped.store(true, Ordering::Relaxed);
+        for handle in self.handles.iter().chain(&self.sockets) {
+            handle.thread().unpark();
+        }
+
+        // WebSockets do get waited for, as they notice being stopped quickly and then close cleanly
+        for handle in self.sockets.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
+#[cfg(feature = "async")]
+impl Drop for Poller {
+    fn drop(&mut self) {
+        // Dropping the runtime afterwards waits for the WebSockets, which notice this quickly and then close cleanly
+        debug!("Stopping pollers...");
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
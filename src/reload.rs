@@ -0,0 +1,57 @@
+//  RELOAD.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 09:40:12
+//  Last edited:
+//    16 Oct 2026, 09:40:12
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Notices when the config file is changed by someone else (e.g., the
+//!   user in an editor), such that the changes apply without a restart.
+//!   
+//!   This is done coarsely, by checking every so often whether the file
+//!   was modified since we last wrote or read it.
+//
+
+use std::thread;
+use std::time::Duration;
+
+use log::{debug, info};
+
+use crate::app::{AppEvent, AppEventLoopProxy};
+use crate::state::AppState;
+
+
+/***** CONSTANTS *****/
+/// How often we check whether the config file changed.
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+
+
+
+
+/***** LIBRARY *****/
+/// Starts watching the config file for changes made by someone else, on a thread of its own.
+///
+/// Whenever it changes, an [`AppEvent::ReloadConfig`] is sent to the main loop. Our own writes don't count (see
+/// [`AppState::config_changed()`]). The thread stops once the main loop is gone.
+///
+/// # Arguments
+/// - `state`: The [`AppState`] whose config file to watch.
+/// - `proxy`: An [`AppEventLoopProxy`] to tell the main loop about changes with.
+pub fn watch(state: AppState, proxy: AppEventLoopProxy) {
+    debug!("Watching config file for changes...");
+    thread::spawn(move || loop {
+        thread::sleep(CHECK_INTERVAL);
+        if state.config_changed() {
+            info!("Config file was changed by someone else");
+            if proxy.send_event(AppEvent::ReloadConfig).is_err() {
+                debug!("Event loop is gone; no longer watching the config file");
+                return;
+            }
+        }
+    });
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    11 Apr 2024, 13:14:35
 //  Last edited:
-//    11 Apr 2024, 17:13:35
+//    16 Oct 2026, 12:05:52
 //  Auto updated?
 //    Yes
 //
@@ -12,39 +12,466 @@
 //!   Defines some shared state for the whole app.
 //
 
-use std::fmt::{Display, Formatter, Result as FResult};
-use std::io::ErrorKind;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::convert::Infallible;
+use std::ffi::OsString;
+use std::fmt::{Debug, Display, Formatter, Result as FResult};
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Write as _};
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 use std::{error, fs};
 
-use chrono::{DateTime, Local};
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use chrono::{DateTime, Local, Utc};
 use enum_debug::EnumDebug;
-use log::{debug, info};
+use error_trace::ErrorTrace as _;
+use log::{debug, info, trace, warn};
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serializable::toml::{Error as TomlError, Serializer as Toml};
 use serializable::Serializable;
+use url::Url;
+
+use crate::clock::{Clock, SystemClock};
+use crate::event::{Event, Severity, TimestampFlag};
+use crate::i18n::{Lang, Text};
+use crate::notify::NotifierConfig;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use chrono::FixedOffset;
+
+    use super::*;
+
+    /// Builds an event with the given ID that happened at the given time.
+    fn event(id: &str, timestamp: DateTime<Local>) -> Event {
+        Event {
+            id: id.into(),
+            timestamp,
+            severity: Severity::Info,
+            source: "test".into(),
+            title: id.into(),
+            message: String::new(),
+            url: None,
+            read: false,
+            pinned: false,
+            timestamp_flag: None,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn pinned_events_survive_restart_and_pruning() {
+        let dir: tempfile::TempDir = tempfile::tempdir().unwrap();
+        let now: DateTime<Local> = Local::now();
+
+        // Pin the older of two events, and then make room for only one unpinned event
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        state
+            .access_mut(|state| -> Result<(), Infallible> {
+                state.ingest([event("old", now - chrono::Duration::minutes(1)), event("new", now)], now);
+                state.events.iter_mut().find(|event| event.id == "old").unwrap().pinned = true;
+                state.mark_history_dirty();
+                state.max_events = 1;
+                state.mark_dirty();
+                Ok(())
+            })
+            .unwrap()
+            .unwrap();
+        drop(state);
+
+        // Both should be back after a restart, as the pinned one doesn't count towards the maximum
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        let events: Vec<(String, bool)> = state.access(|state| state.events.iter().map(|event| (event.id.clone(), event.pinned)).collect());
+        assert_eq!(events, vec![("new".into(), false), ("old".into(), true)]);
+
+        // A newer event pushes out the unpinned one only
+        state
+            .access_mut(|state| -> Result<(), Infallible> {
+                let later: DateTime<Local> = now + chrono::Duration::minutes(1);
+                state.ingest([event("newer", later)], later);
+                Ok(())
+            })
+            .unwrap()
+            .unwrap();
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        let ids: Vec<String> = state.access(|state| state.events.iter().map(|event| event.id.clone()).collect());
+        assert_eq!(ids, vec!["newer".to_string(), "old".to_string()]);
+    }
+
+    #[test]
+    fn events_that_would_be_pruned_leave_history_alone() {
+        let dir: tempfile::TempDir = tempfile::tempdir().unwrap();
+        let now: DateTime<Local> = Local::now();
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        state
+            .access_mut(|state| -> Result<(), Infallible> {
+                // Events older than the retention age are neither new nor kept
+                state.retention_age = Some(Duration::from_secs(60 * 60));
+                state.history_dirty = false;
+                assert!(state.ingest([event("ancient", now - chrono::Duration::hours(2))], now).is_empty());
+                assert!(state.events.is_empty());
+                assert!(!state.history_dirty);
+
+                // An event pushed out by a newer one in the same poll isn't new, and isn't restored only to be pruned again later
+                state.max_events = 1;
+                let new: Vec<Event> = state.ingest([event("older", now - chrono::Duration::minutes(1)), event("newer", now)], now);
+                assert_eq!(new.iter().map(|event| event.id.as_str()).collect::<Vec<&str>>(), ["newer"]);
+                assert!(state.history_dirty);
+                state.history_dirty = false;
+                assert!(state.ingest([event("older", now - chrono::Duration::minutes(1))], now).is_empty());
+                assert_eq!(state.events.iter().map(|event| event.id.as_str()).collect::<Vec<&str>>(), ["newer"]);
+                assert!(!state.history_dirty);
+
+                // But a newer one still replaces what's there
+                assert_eq!(state.ingest([event("newest", now)], now).len(), 1);
+                assert!(state.history_dirty);
+                Ok(())
+            })
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn seen_events_are_kept_out_of_the_config_file() {
+        let dir: tempfile::TempDir = tempfile::tempdir().unwrap();
+        let config_path: PathBuf = dir.path().join(CONFIG_FILE_NAME);
+        let now: DateTime<Local> = Local::now();
+
+        // New events are remembered next to the history, without touching the config file
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        let before: Option<SystemTime> = modified(&config_path);
+        state.access_mut(|state| -> Result<(), Infallible> { Ok(drop(state.ingest([event("new", now)], now))) }).unwrap().unwrap();
+        assert_eq!(modified(&config_path), before);
+        drop(state);
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        assert!(state.access(|state| state.seen.contains("test/new")));
+        drop(state);
+
+        // Those that older versions kept in the config file are moved out of it
+        fs::remove_file(dir.path().join(CACHE_DIR_NAME).join(SEEN_FILE_NAME)).unwrap();
+        ConfigFile::default().to_path_pretty(&config_path).unwrap();
+        fs::write(&config_path, format!("seen = [\"test/old\"]\n{}", fs::read_to_string(&config_path).unwrap())).unwrap();
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        assert!(state.access(|state| state.seen.contains("test/old")));
+        assert!(!fs::read_to_string(&config_path).unwrap().contains("test/old"));
+        drop(state);
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        assert!(state.access(|state| state.seen.contains("test/old")));
+    }
+
+    #[test]
+    fn damaged_history_is_moved_aside() {
+        let dir: tempfile::TempDir = tempfile::tempdir().unwrap();
+        let history_path: PathBuf = dir.path().join(CACHE_DIR_NAME).join(HISTORY_FILE_NAME);
+        fs::create_dir_all(history_path.parent().unwrap()).unwrap();
+        fs::write(&history_path, "[{\"id\": \"trunc").unwrap();
+
+        // The app still starts, just without any events
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        assert!(state.access(|state| state.events.is_empty()));
+        assert!(!history_path.exists());
+        assert_eq!(fs::read_to_string(dir.path().join(CACHE_DIR_NAME).join("history.json.bad")).unwrap(), "[{\"id\": \"trunc");
+    }
+
+    #[test]
+    fn failed_transactions_leave_nothing_to_write() {
+        let dir: tempfile::TempDir = tempfile::tempdir().unwrap();
+        let config_path: PathBuf = dir.path().join(CONFIG_FILE_NAME);
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        let before: Option<String> = fs::read_to_string(&config_path).ok();
+
+        // The mute change is undone along with the unknown server, and so is the need to write it
+        assert!(matches!(
+            state.transaction().set_muted(MuteState::Manual).remove_server("nope").commit(),
+            Err(Error::ServerUnknown { name }) if name == "nope"
+        ));
+        assert!(state.access(|state| state.muted == MuteState::Unmuted && !state.dirty));
+
+        // So an unrelated access later doesn't write anything either
+        state.access_mut(|_| -> Result<(), Infallible> { Ok(()) }).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(&config_path).ok(), before);
+    }
+
+    #[test]
+    fn toggle_flips_every_variant() {
+        let deadline: DateTime<Utc> = Utc::now() + chrono::Duration::hours(1);
+        for (mut muted, toggled) in [
+            (MuteState::Unmuted, MuteState::Manual),
+            (MuteState::After(deadline), MuteState::Unmuted),
+            (MuteState::NextBoot, MuteState::Unmuted),
+            (MuteState::Manual, MuteState::Unmuted),
+        ] {
+            muted.toggle();
+            assert_eq!(muted, toggled);
+        }
+    }
+
+    #[test]
+    fn mute_deadlines_round_trip_across_timezones() {
+        let moment: DateTime<Utc> = DateTime::parse_from_rfc3339("2024-04-01T10:00:00Z").unwrap().with_timezone(&Utc);
+
+        // The same moment, as written on machines in various timezones (and, in the last two, by older versions)
+        for raw in [
+            "2024-04-01T10:00:00Z",
+            "2024-04-01T12:00:00+02:00",
+            "2024-04-01T05:00:00-05:00",
+            "2024-04-01T19:30:00+09:30",
+            "2024-04-01T12:00:00.000+02:00",
+            "2024-04-01 12:00:00 +02:00",
+        ] {
+            let muted: MuteState = serde_json::from_value(serde_json::json!({ "after": raw })).unwrap();
+            assert_eq!(muted, MuteState::After(moment), "Parsing '{raw}'");
+
+            // Writing it out again gives an RFC3339 string, which reads back as the same moment
+            let written: serde_json::Value = serde_json::to_value(&muted).unwrap();
+            let written: &str = written["after"].as_str().unwrap();
+            assert!(DateTime::parse_from_rfc3339(written).is_ok(), "Writing '{raw}' gave '{written}'");
+            assert_eq!(serde_json::from_value::<MuteState>(serde_json::json!({ "after": written })).unwrap(), muted);
+        }
+    }
+
+    #[test]
+    fn mute_deadlines_survive_dst_transitions() {
+        let at = |raw: &str| -> DateTime<Local> { DateTime::parse_from_rfc3339(raw).unwrap().with_timezone(&Local) };
+        for (written, before, after) in [
+            // Spring forward in Central Europe, where 02:00 CET becomes 03:00 CEST
+            ("2024-03-31T01:45:00+01:00", "2024-03-31T01:30:00+01:00", "2024-03-31T03:15:00+02:00"),
+            // Fall back, where 03:00 CEST becomes 02:00 CET and the wall clock reads earlier than the deadline once it has passed
+            ("2024-10-27T02:30:00+02:00", "2024-10-27T02:15:00+02:00", "2024-10-27T02:15:00+01:00"),
+        ] {
+            // Written before the transition, read after it
+            let muted: MuteState = serde_json::from_value(serde_json::json!({ "after": written })).unwrap();
+            let muted: MuteState = serde_json::from_value(serde_json::to_value(&muted).unwrap()).unwrap();
+            assert_eq!(muted, MuteState::After(at(written).with_timezone(&Utc)));
+
+            // It should end at the moment it denotes, regardless of what the wall clock says
+            let mut ticked: MuteState = muted.clone();
+            assert!(!ticked.tick(at(before)), "Deadline '{written}' passed at '{before}'");
+            assert_eq!(ticked, muted);
+            assert!(ticked.tick(at(after)), "Deadline '{written}' not passed at '{after}'");
+            assert_eq!(ticked, MuteState::Unmuted);
+        }
+    }
+
+    #[test]
+    fn old_deadline_format_is_migrated_on_load() {
+        let dir: tempfile::TempDir = tempfile::tempdir().unwrap();
+        let config_path: PathBuf = dir.path().join(CONFIG_FILE_NAME);
+        let deadline: DateTime<Utc> = DateTime::parse_from_rfc3339("2030-03-31T01:30:00Z").unwrap().with_timezone(&Utc);
+        let current: String = format!("\"{}\"", deadline.with_timezone(&Local).to_rfc3339());
+
+        // Write a config with the deadline, and then make it look like an older version wrote it on the other side of the world
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        state.transaction().set_muted(MuteState::After(deadline)).commit().unwrap();
+        drop(state);
+        let old: String = format!("\"{}\"", deadline.with_timezone(&FixedOffset::east_opt(13 * 3600).unwrap()).format("%Y-%m-%d %H:%M:%S %:z"));
+        let raw: String = fs::read_to_string(&config_path).unwrap();
+        assert!(raw.contains(&current));
+        fs::write(&config_path, raw.replace(&current, &old)).unwrap();
+
+        // Loading should give the same moment, and rewrite it in the current format
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        assert_eq!(state.access(|state| state.muted.clone()), MuteState::After(deadline));
+        let raw: String = fs::read_to_string(&config_path).unwrap();
+        assert!(raw.contains(&current), "Config not migrated:\n{raw}");
+        assert!(!raw.contains(&old));
+        assert!(!state.config_changed());
+    }
+
+    #[test]
+    fn untrusted_timestamps_follow_policy() {
+        let dir: tempfile::TempDir = tempfile::tempdir().unwrap();
+        let now: DateTime<Local> = Local::now();
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        let mut untimed: Event = event("untimed", now);
+        untimed.timestamp_flag = Some(TimestampFlag::Missing);
+
+        state
+            .access_mut(|state| -> Result<(), Infallible> {
+                // Far-future timestamps are clamped to now, but a little clock skew is fine
+                let far: DateTime<Local> = now + chrono::Duration::days(365);
+                let near: DateTime<Local> = now + chrono::Duration::minutes(1);
+                state.ingest([event("far", far), event("near", near), untimed.clone()], now);
+                let far: &Event = state.events.iter().find(|event| event.id == "far").unwrap();
+                assert_eq!((far.timestamp, far.timestamp_flag), (now, Some(TimestampFlag::Future)));
+                let near: &Event = state.events.iter().find(|event| event.id == "near").unwrap();
+                assert_eq!((near.timestamp, near.timestamp_flag), (now + chrono::Duration::minutes(1), None));
+
+                // Events without a timestamp are kept, unless the user doesn't want them
+                assert!(state.events.iter().any(|event| event.id == "untimed"));
+                state.timestamps.drop_missing = true;
+                untimed.id = "untimed2".into();
+                state.ingest([untimed], now);
+                assert!(!state.events.iter().any(|event| event.id == "untimed2"));
+                Ok(())
+            })
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn concurrent_mute_toggles_leave_last_change_on_disk() {
+        let dir: tempfile::TempDir = tempfile::tempdir().unwrap();
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+
+        // Let a bunch of threads fight over the mute state, toggling it an odd number of times in total
+        let handles: Vec<std::thread::JoinHandle<()>> = (0..15)
+            .map(|_| {
+                let state: AppState = state.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..51 {
+                        state
+                            .access_mut(|state| -> Result<(), Infallible> {
+                                state.muted.toggle();
+                                state.mark_dirty();
+                                Ok(())
+                            })
+                            .unwrap()
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Whatever the last change was, the disk should agree with it
+        let last: MuteState = state.access(|state| state.muted.clone());
+        assert_eq!(last, MuteState::Manual);
+        drop(state);
+        let state: AppState = AppState::new(dir.path().into()).unwrap();
+        assert_eq!(state.access(|state| state.muted.clone()), last);
+    }
+}
+
+
+
+
+
+/***** CONSTANTS *****/
+/// The name of the config file in the config directory.
+pub const CONFIG_FILE_NAME: &str = "server_events.toml";
+/// The name of the cache directory in the config directory.
+pub const CACHE_DIR_NAME: &str = "cache";
+/// The name of the file in the cache directory that keeps the received events across restarts.
+pub const HISTORY_FILE_NAME: &str = "history.json";
+/// The name of the file in the cache directory that keeps the keys of the events that we've seen across restarts.
+pub const SEEN_FILE_NAME: &str = "seen.json";
+/// The service name under which we store tokens in the OS secret store.
+pub const KEYRING_SERVICE: &str = "server-events";
+/// The size (in bytes) after which the mute audit log is rotated.
+const MUTE_AUDIT_MAX_SIZE: u64 = 1024 * 1024;
+/// The number of minutes that [`Activity`] keeps count of.
+pub const ACTIVITY_MINUTES: usize = 60;
+
+
+
 
 
 /***** ERRORS *****/
 /// Defines errors originating from [`AppState`]s.
 #[derive(Debug)]
 pub enum Error {
+    /// The name of the header that a server authenticates with is not a valid header name.
+    AuthHeaderName { server: String, name: String, err: reqwest::header::InvalidHeaderName },
+    /// The credentials of a server don't fit in a header (e.g., because they contain a newline).
+    AuthHeaderValue { server: String, name: HeaderName, err: reqwest::header::InvalidHeaderValue },
     /// Failed to create the config parent directory.
     ConfigDirCreate { path: PathBuf, err: std::io::Error },
+    /// Failed to flush a newly written config file to disk.
+    ConfigFlush { path: PathBuf, err: std::io::Error },
+    /// An imported config file was not valid.
+    ConfigImportInvalid { path: PathBuf, reason: String },
+    /// Failed to parse an imported JSON config file.
+    ConfigImportJson { path: PathBuf, err: serde_json::Error },
+    /// Failed to read an imported config file.
+    ConfigImportRead { path: PathBuf, err: std::io::Error },
+    /// An imported config file had an extension we don't know how to parse.
+    ConfigImportUnknownExt { path: PathBuf },
     /// Failed to load the given config file.
     ConfigLoad { path: PathBuf, err: serializable::Error<TomlError> },
+    /// The config file to reload does not exist (anymore).
+    ConfigMissing { path: PathBuf },
+    /// Failed to move a newly written config file over the old one.
+    ConfigReplace { from: PathBuf, to: PathBuf, err: std::io::Error },
     /// Failed to write a config file.
     ConfigWrite { path: PathBuf, err: serializable::Error<TomlError> },
+    /// Failed to move a damaged file with the event history out of the way.
+    HistoryBackup { from: PathBuf, to: PathBuf, err: std::io::Error },
+    /// Failed to parse the file with the event history.
+    HistoryParse { path: PathBuf, err: serde_json::Error },
+    /// Failed to read the file with the event history.
+    HistoryRead { path: PathBuf, err: std::io::Error },
+    /// Failed to move a newly written event history over the old one.
+    HistoryReplace { from: PathBuf, to: PathBuf, err: std::io::Error },
+    /// Failed to serialize the event history.
+    HistorySerialize { err: serde_json::Error },
+    /// Failed to write (and flush) the file with the event history.
+    HistoryWrite { path: PathBuf, err: std::io::Error },
+    /// Failed to open the OS secret store entry of a server.
+    KeyringOpen { server: String, err: keyring::Error },
+    /// Failed to read a token from the OS secret store.
+    KeyringRead { server: String, err: keyring::Error },
+    /// The OS secret store has no token for a server that claims to have one there.
+    KeyringMissing { server: String },
+    /// Failed to write a token to the OS secret store.
+    KeyringWrite { server: String, err: keyring::Error },
+    /// Failed to rotate the mute audit log.
+    MuteAuditRotate { from: PathBuf, to: PathBuf, err: std::io::Error },
+    /// Failed to write to the mute audit log.
+    MuteAuditWrite { path: PathBuf, err: std::io::Error },
+    /// There already is a server with the given name.
+    ServerExists { name: String },
+    /// There is no server with the given name.
+    ServerUnknown { name: String },
+    /// Failed to read a token from the environment variable it refers to.
+    TokenEnv { server: String, var: String, err: std::env::VarError },
 }
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
         use Error::*;
         match self {
+            AuthHeaderName { server, name, .. } => write!(f, "Invalid name '{name}' for authentication header of server '{server}'"),
+            AuthHeaderValue { server, name, .. } => write!(f, "Invalid value for '{name}'-header of server '{server}'"),
             ConfigDirCreate { path, .. } => write!(f, "Failed to create config directory '{}'", path.display()),
+            ConfigFlush { path, .. } => write!(f, "Failed to flush config file '{}' to disk", path.display()),
+            ConfigImportInvalid { path, reason } => write!(f, "Imported config file '{}' is invalid: {}", path.display(), reason),
+            ConfigImportJson { path, .. } => write!(f, "Failed to parse imported config file '{}' as JSON", path.display()),
+            ConfigImportRead { path, .. } => write!(f, "Failed to read imported config file '{}'", path.display()),
+            ConfigImportUnknownExt { path } => {
+                write!(f, "Imported config file '{}' has an unknown extension (expected '.toml' or '.json')", path.display())
+            },
             ConfigLoad { path, .. } => write!(f, "Failed to load config file '{}'", path.display()),
+            ConfigMissing { path } => write!(f, "Cannot reload config file '{}' because it does not exist", path.display()),
+            ConfigReplace { from, to, .. } => write!(f, "Failed to replace config file '{}' with '{}'", to.display(), from.display()),
             ConfigWrite { path, .. } => write!(f, "Failed to write config file to '{}'", path.display()),
+            HistoryBackup { from, to, .. } => write!(f, "Failed to move damaged event history '{}' to '{}'", from.display(), to.display()),
+            HistoryParse { path, .. } => write!(f, "Failed to parse event history '{}'", path.display()),
+            HistoryRead { path, .. } => write!(f, "Failed to read event history '{}'", path.display()),
+            HistoryReplace { from, to, .. } => write!(f, "Failed to replace event history '{}' with '{}'", to.display(), from.display()),
+            HistorySerialize { .. } => write!(f, "Failed to serialize event history"),
+            HistoryWrite { path, .. } => write!(f, "Failed to write event history to '{}'", path.display()),
+            KeyringOpen { server, .. } => write!(f, "Failed to open OS secret store entry for server '{server}'"),
+            KeyringRead { server, .. } => write!(f, "Failed to read token for server '{server}' from the OS secret store"),
+            KeyringMissing { server } => {
+                write!(f, "No token for server '{server}' found in the OS secret store; run `{} --set-token {server}` to store one", env!("CARGO_PKG_NAME"))
+            },
+            KeyringWrite { server, .. } => write!(f, "Failed to write token for server '{server}' to the OS secret store"),
+            MuteAuditRotate { from, to, .. } => write!(f, "Failed to rotate mute audit log '{}' to '{}'", from.display(), to.display()),
+            MuteAuditWrite { path, .. } => write!(f, "Failed to write to mute audit log '{}'", path.display()),
+            ServerExists { name } => write!(f, "There already is a server named '{name}'"),
+            ServerUnknown { name } => write!(f, "Unknown server '{name}'"),
+            TokenEnv { server, var, .. } => write!(f, "Failed to read token for server '{server}' from environment variable '{var}'"),
         }
     }
 }
@@ -52,13 +479,400 @@ impl error::Error for Error {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         use Error::*;
         match self {
+            AuthHeaderName { err, .. } => Some(err),
+            AuthHeaderValue { err, .. } => Some(err),
             ConfigDirCreate { err, .. } => Some(err),
+            ConfigFlush { err, .. } => Some(err),
+            ConfigImportInvalid { .. } => None,
+            ConfigImportJson { err, .. } => Some(err),
+            ConfigImportRead { err, .. } => Some(err),
+            ConfigImportUnknownExt { .. } => None,
             ConfigLoad { err, .. } => Some(err),
+            ConfigMissing { .. } => None,
+            ConfigReplace { err, .. } => Some(err),
             ConfigWrite { err, .. } => Some(err),
+            HistoryBackup { err, .. } => Some(err),
+            HistoryParse { err, .. } => Some(err),
+            HistoryRead { err, .. } => Some(err),
+            HistoryReplace { err, .. } => Some(err),
+            HistorySerialize { err } => Some(err),
+            HistoryWrite { err, .. } => Some(err),
+            KeyringOpen { err, .. } => Some(err),
+            KeyringRead { err, .. } => Some(err),
+            KeyringMissing { .. } => None,
+            KeyringWrite { err, .. } => Some(err),
+            MuteAuditRotate { err, .. } => Some(err),
+            MuteAuditWrite { err, .. } => Some(err),
+            ServerExists { .. } => None,
+            ServerUnknown { .. } => None,
+            TokenEnv { err, .. } => Some(err),
+        }
+    }
+}
+
+
+
+/// Defines the problems that [`ConfigFile::validate()`] finds.
+#[derive(Debug)]
+pub enum ValidationError {
+    /// The name of the header that a server authenticates with is not a valid header name.
+    AuthHeaderName { server: String, name: String, err: reqwest::header::InvalidHeaderName },
+    /// Two servers have the same name.
+    DuplicateName { name: String },
+    /// A server has an empty name.
+    EmptyName { index: usize },
+    /// The shortest time between polls of an adaptive server is longer than the longest.
+    IntervalOrder { server: String },
+    /// One of the times between polls of a server is zero.
+    IntervalZero { server: String, field: &'static str },
+    /// A token refers to an environment variable that we can't read.
+    TokenEnv { server: String, var: String, err: std::env::VarError },
+    /// The address of a server has a scheme that doesn't match how we get its events.
+    UrlScheme { server: String, url: Url, expected: &'static str },
+}
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use ValidationError::*;
+        match self {
+            AuthHeaderName { server, name, .. } => write!(f, "Invalid name '{name}' for authentication header of server '{server}'"),
+            DuplicateName { name } => write!(f, "Server name '{name}' occurs more than once"),
+            EmptyName { index } => write!(f, "Server {index} has an empty name"),
+            IntervalOrder { server } => write!(f, "Server '{server}' has a 'min_interval' that is longer than its 'max_interval'"),
+            IntervalZero { server, field } => write!(f, "Server '{server}' has a '{field}' of zero"),
+            TokenEnv { server, var, .. } => write!(f, "Cannot read token for server '{server}' from environment variable '{var}'"),
+            UrlScheme { server, url, expected } => write!(f, "URL '{url}' of server '{server}' should start with {expected}"),
+        }
+    }
+}
+impl error::Error for ValidationError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use ValidationError::*;
+        match self {
+            AuthHeaderName { err, .. } => Some(err),
+            DuplicateName { .. } => None,
+            EmptyName { .. } => None,
+            IntervalOrder { .. } => None,
+            IntervalZero { .. } => None,
+            TokenEnv { err, .. } => Some(err),
+            UrlScheme { .. } => None,
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Returns the default value for [`ConfigFile::max_events`].
+#[inline]
+const fn default_max_events() -> usize { 1000 }
+
+/// Returns the default value for [`ConfigFile::max_pinned_events`].
+#[inline]
+const fn default_max_pinned_events() -> usize { 100 }
+
+/// Returns the default value for [`ConfigFile::max_seen`].
+#[inline]
+const fn default_max_seen() -> usize { 10_000 }
+
+/// Returns the default value for [`ConfigFile::warn_after_failures`].
+#[inline]
+const fn default_warn_after_failures() -> u32 { 3 }
+
+/// Returns the default value for [`ConfigFile::notifiers`].
+#[inline]
+fn default_notifiers() -> Vec<NotifierConfig> { vec![NotifierConfig::Desktop] }
+
+/// Returns the default value for [`ConfigFile::max_notifications_per_minute`].
+#[inline]
+const fn default_max_notifications_per_minute() -> usize { 30 }
+
+/// Returns the default value for [`TimestampPolicy::future_tolerance`].
+#[inline]
+const fn default_future_tolerance() -> Duration { Duration::from_secs(5 * 60) }
+
+/// Returns the default value for [`ServerConfig::poll_interval`].
+#[inline]
+const fn default_poll_interval() -> Duration { Duration::from_secs(60) }
+
+/// Returns the default value for [`ServerConfig::min_interval`].
+#[inline]
+const fn default_min_interval() -> Duration { Duration::from_secs(10) }
+
+/// Returns the default value for [`ServerConfig::max_interval`].
+#[inline]
+const fn default_max_interval() -> Duration { Duration::from_secs(600) }
+
+/// Returns the default value for the `max_pages` of a [`Pagination`].
+#[inline]
+const fn default_max_pages() -> usize { 10 }
+
+/// Returns the default value for [`Pagination::Query::param`].
+#[inline]
+fn default_page_param() -> String { "page".into() }
+
+/// Returns the default value for [`Pagination::Cursor::items_field`].
+#[inline]
+fn default_items_field() -> String { "events".into() }
+
+/// Returns the default value for [`Pagination::Cursor::cursor_field`].
+#[inline]
+fn default_cursor_field() -> String { "next".into() }
+
+/// Returns the default value for [`Pagination::Cursor::param`].
+#[inline]
+fn default_cursor_param() -> String { "cursor".into() }
+
+/// (De)serializes a [`Duration`] as a number of seconds.
+mod duration_secs {
+    use std::time::Duration;
+
+    use serde::{Deserialize as _, Deserializer, Serializer};
+
+    /// Serializes a [`Duration`] as a number of seconds.
+    pub fn serialize<S: Serializer>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error> { serializer.serialize_u64(value.as_secs()) }
+
+    /// Deserializes a [`Duration`] from a number of seconds.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> { Ok(Duration::from_secs(u64::deserialize(deserializer)?)) }
+}
+
+/// (De)serializes an optional [`Duration`] as an optional number of seconds.
+mod opt_duration_secs {
+    use std::time::Duration;
+
+    use serde::{Deserialize as _, Deserializer, Serializer};
+
+    /// Serializes an optional [`Duration`] as an optional number of seconds.
+    pub fn serialize<S: Serializer>(value: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_some(&value.as_secs()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserializes an optional [`Duration`] from an optional number of seconds.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_secs))
+    }
+}
+
+/// (De)serializes a UTC [`DateTime`] as an RFC3339 string in local time, such that it's readable to the user.
+///
+/// Deserializing accepts any offset, which makes it independent of the local timezone at the time of writing. For backwards
+/// compatibility, it also accepts anything that chrono's own [`FromStr`](std::str::FromStr) accepts.
+mod rfc3339 {
+    use chrono::{DateTime, FixedOffset, Local, Utc};
+    use serde::de::Error as _;
+    use serde::{Deserialize as _, Deserializer, Serializer};
+
+    /// Serializes a [`DateTime`] as an RFC3339 string with the local offset.
+    pub fn serialize<S: Serializer>(value: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.with_timezone(&Local).to_rfc3339())
+    }
+
+    /// Deserializes a [`DateTime`] from an RFC3339 string with any offset, or any older format written by chrono.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+        let raw: String = String::deserialize(deserializer)?;
+        let time: DateTime<FixedOffset> = match DateTime::parse_from_rfc3339(&raw) {
+            Ok(time) => time,
+            Err(_) => match raw.parse() {
+                Ok(time) => time,
+                Err(err) => return Err(D::Error::custom(format!("Invalid timestamp '{raw}': {err}"))),
+            },
+        };
+        Ok(time.with_timezone(&Utc))
+    }
+
+    /// Deserializes a [`DateTime`] only if it is written exactly like [`serialize()`] would write it now.
+    pub fn deserialize_current<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+        let raw: String = String::deserialize(deserializer)?;
+        match DateTime::parse_from_rfc3339(&raw) {
+            Ok(time) if time.with_timezone(&Local).to_rfc3339() == raw => Ok(time.with_timezone(&Utc)),
+            _ => Err(D::Error::custom(format!("Timestamp '{raw}' is not in the current format"))),
+        }
+    }
+}
+
+/// Deserializes the extra headers of a [`ServerConfig`], rejecting names or values that cannot be sent in a request.
+fn deserialize_headers<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<String, String>, D::Error> {
+    use serde::de::Error as _;
+
+    let headers: BTreeMap<String, String> = BTreeMap::deserialize(deserializer)?;
+    for (name, value) in &headers {
+        if let Err(err) = reqwest::header::HeaderName::from_bytes(name.as_bytes()) {
+            return Err(D::Error::custom(format!("Invalid header name '{name}': {err}")));
+        }
+        if let Err(err) = reqwest::header::HeaderValue::from_str(value) {
+            return Err(D::Error::custom(format!("Invalid value for header '{name}': {err}")));
+        }
+    }
+    Ok(headers)
+}
+
+/// Reads the config file.
+///
+/// # Arguments
+/// - `config_path`: The path of the config file.
+///
+/// # Returns
+/// The [`ConfigFile`], or [`None`] if there is no file at `config_path`.
+///
+/// # Errors
+/// This function errors if the file exists but we failed to read or parse it.
+fn read_config(config_path: &Path) -> Result<Option<ConfigFile>, Error> {
+    debug!("Loading config file from '{}'...", config_path.display());
+    match ConfigFile::from_path(config_path) {
+        Ok(config) => Ok(Some(config)),
+        Err(serializable::Error::FileOpen { err, .. }) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(Error::ConfigLoad { path: config_path.into(), err }),
+    }
+}
+
+/// Reads one of the files with the event history (i.e., [`HISTORY_FILE_NAME`] or [`SEEN_FILE_NAME`]).
+///
+/// # Arguments
+/// - `history_path`: The path of the file.
+///
+/// # Returns
+/// The contents of the file, or [`None`] if there is no file at `history_path` (e.g., because we never received any events).
+///
+/// # Errors
+/// This function errors if the file exists but we failed to read or parse it.
+fn read_history<T: DeserializeOwned>(history_path: &Path) -> Result<Option<T>, Error> {
+    debug!("Loading event history from '{}'...", history_path.display());
+    let raw: String = match fs::read_to_string(history_path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(Error::HistoryRead { path: history_path.into(), err }),
+    };
+    match serde_json::from_str(&raw) {
+        Ok(history) => Ok(Some(history)),
+        Err(err) => Err(Error::HistoryParse { path: history_path.into(), err }),
+    }
+}
+
+/// Reads one of the files with the event history like [`read_history()`], but moves it out of the way if it is damaged.
+///
+/// This way, a corrupt or truncated file costs the user their history but doesn't stop the app from starting. The damaged file is
+/// kept next to the original with a `.bad` extension, such that it may still be inspected.
+///
+/// # Arguments
+/// - `history_path`: The path of the file.
+///
+/// # Returns
+/// The contents of the file, or [`None`] if there is no file at `history_path` or if it was damaged.
+fn recover_history<T: DeserializeOwned>(history_path: &Path) -> Option<T> {
+    let err: Error = match read_history(history_path) {
+        Ok(history) => return history,
+        Err(err) => err,
+    };
+    warn!("{}", err.trace());
+    let mut bad_path: OsString = history_path.as_os_str().to_owned();
+    bad_path.push(".bad");
+    let bad_path: PathBuf = bad_path.into();
+    match fs::rename(history_path, &bad_path) {
+        Ok(()) => warn!("Moved damaged event history '{}' to '{}'; starting without it", history_path.display(), bad_path.display()),
+        Err(err) => warn!("{}", Error::HistoryBackup { from: history_path.into(), to: bad_path, err }.trace()),
+    }
+    None
+}
+
+/// Writes one of the files with the event history (i.e., [`HISTORY_FILE_NAME`] or [`SEEN_FILE_NAME`]).
+///
+/// Like [`MutableAppState::sync()`], the file is replaced atomically.
+///
+/// # Arguments
+/// - `history_path`: The path of the file.
+/// - `history`: The contents to write to it.
+///
+/// # Errors
+/// This function errors if we failed to serialize the `history` or to write it to disk.
+fn write_history(history_path: &Path, history: &impl Serialize) -> Result<(), Error> {
+    let raw: Vec<u8> = match serde_json::to_vec(history) {
+        Ok(raw) => raw,
+        Err(err) => return Err(Error::HistorySerialize { err }),
+    };
+    if let Some(parent) = history_path.parent() {
+        if let Err(err) = fs::create_dir_all(parent) {
+            return Err(Error::ConfigDirCreate { path: parent.into(), err });
+        }
+    }
+
+    // Write it next to the old one first, and then move it over that
+    debug!("Syncing event history back to '{}'...", history_path.display());
+    let mut tmp_path: OsString = history_path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path: PathBuf = tmp_path.into();
+    if let Err(err) = File::create(&tmp_path).and_then(|mut file| {
+        file.write_all(&raw)?;
+        file.sync_all()
+    }) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(Error::HistoryWrite { path: tmp_path, err });
+    }
+    if let Err(err) = fs::rename(&tmp_path, history_path) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(Error::HistoryReplace { from: tmp_path, to: history_path.into(), err });
+    }
+    Ok(())
+}
+
+/// Returns when a file was last modified.
+///
+/// # Arguments
+/// - `path`: The path of the file.
+///
+/// # Returns
+/// The modification time, or [`None`] if the file doesn't exist or the OS doesn't tell.
+fn modified(path: &Path) -> Option<SystemTime> { fs::metadata(path).and_then(|metadata| metadata.modified()).ok() }
+
+/// Checks whether the config file holds any mute deadlines in another form than we'd write them now.
+///
+/// Older versions wrote deadlines with the local offset of the time of writing, or in chrono's own format. These still parse to the
+/// moment that they denote, but should be rewritten such that the file shows them in the current timezone.
+///
+/// # Arguments
+/// - `config_path`: The path of the config file.
+///
+/// # Returns
+/// True if any of the deadlines in the file is a [`StoredDeadline::Old`] one.
+fn stale_deadlines(config_path: &Path) -> bool {
+    let Ok(stored) = StoredDeadlines::from_path(config_path) else { return false };
+    std::iter::once(&stored.muted).chain(stored.snoozed.values()).any(|mute| matches!(mute, StoredMuteState::After(StoredDeadline::Old(_))))
+}
+
+/// Logs a warning for every problem that [`ConfigFile::validate()`] finds, such that the app can carry on with what does work.
+///
+/// # Arguments
+/// - `config`: The [`ConfigFile`] to validate.
+fn warn_invalid(config: &ConfigFile) {
+    if let Err(errs) = config.validate() {
+        for err in errs {
+            warn!("{}", err.trace());
         }
     }
 }
 
+/// Checks whether we can write to a directory, by creating (and removing) a file in it.
+///
+/// The directory is created if it doesn't exist yet, like [`MutableAppState::sync()`] would.
+///
+/// # Arguments
+/// - `dir`: The directory to check.
+///
+/// # Returns
+/// True if we could write a file in `dir`.
+fn writable(dir: &Path) -> bool {
+    let probe: PathBuf = dir.join(".write-test");
+    let res: bool = fs::create_dir_all(dir).and_then(|_| fs::write(&probe, b"")).is_ok();
+    if res {
+        // Not being able to clean up doesn't make the directory any less writable
+        let _ = fs::remove_file(&probe);
+    }
+    res
+}
+
 
 
 
@@ -69,140 +883,1665 @@ impl error::Error for Error {
 pub struct ConfigFile {
     /// The current mute state.
     pub muted: MuteState,
+    /// The remote servers that we keep track of.
+    #[serde(default)]
+    pub servers: Vec<ServerConfig>,
+    /// The layout of the window that was last used.
+    #[serde(default)]
+    pub layout: GuiLayout,
+    /// Whether to keep a log of every change to the mute state.
+    #[serde(default)]
+    pub mute_audit: bool,
+    /// The maximum number of events we keep around.
+    #[serde(default = "default_max_events")]
+    pub max_events: usize,
+    /// The maximum age (in seconds) of events we keep around, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "opt_duration_secs")]
+    pub retention_age: Option<Duration>,
+    /// Whether to write a crash report to the cache directory when the app exits with an error.
+    #[serde(default)]
+    pub crash_reports: bool,
+    /// Whether to notify about the events that a server reports on its first successful poll, instead of silently taking them as a starting point.
+    #[serde(default)]
+    pub notify_on_first_poll: bool,
+    /// Whether a left-click on the tray icon opens a small popover instead of the main window. Not supported on Linux.
+    #[serde(default)]
+    pub tray_popover: bool,
+    /// The backends that tell the user about new events.
+    #[serde(default = "default_notifiers")]
+    pub notifiers: Vec<NotifierConfig>,
+    /// The `User-Agent` to send to servers, if not `server-events/<version>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// If given, new events are also appended to a CSV file per day in this directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csv_export_path: Option<PathBuf>,
+    /// If given, raises the severity of events that keep recurring.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalation: Option<Escalation>,
+    /// The items in the tray icon's menu, in order. If empty, a default menu is used.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub menu: Vec<MenuItemSpec>,
+    /// Event titles that we don't notify about, and until when.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub snoozed: HashMap<String, MuteState>,
+    /// Whether to hold back notifications while the OS is in a do-not-disturb (or focus) mode. Only supported on macOS and Windows.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub follow_os_dnd: bool,
+    /// If given (and not 0), the maximum number of servers that are polled at the same time. Others wait for their turn.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_polls: Option<usize>,
+    /// If given, serves `/healthz` and `/metrics` (in Prometheus' text format) on this address. Should be a local one, as there's no authentication.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_addr: Option<SocketAddr>,
+    /// How the event list was last sorted.
+    #[serde(default)]
+    pub sort: SortKey,
+    /// The icon (e.g., an emoji) to show in front of events of a given severity, unless their server overrides it.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub severity_icons: HashMap<Severity, String>,
+    /// The maximum number of notifications shown per minute, after which further ones are hidden until the rate drops. 0 means no limit.
+    #[serde(default = "default_max_notifications_per_minute")]
+    pub max_notifications_per_minute: usize,
+    /// The language to show the client in. If omitted, it follows the OS' locale, falling back to English.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<Lang>,
+    /// The maximum number of pinned events we keep around. These are kept apart from [`ConfigFile::max_events`] and the retention age.
+    #[serde(default = "default_max_pinned_events")]
+    pub max_pinned_events: usize,
+    /// What to do with events whose timestamp is missing or lies in the future.
+    #[serde(default)]
+    pub timestamps: TimestampPolicy,
+    /// If given, listens for commands (e.g., `mute 1h` or `status`) on a Unix socket at this path. Not supported on Windows.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub control_socket: Option<PathBuf>,
+    /// The last known width of the main window (in physical pixels), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// The last known height of the main window (in physical pixels), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// The last known horizontal position of the main window (in physical pixels), if any. May be negative on multi-monitor setups.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x: Option<i32>,
+    /// The last known vertical position of the main window (in physical pixels), if any. May be negative on multi-monitor setups.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y: Option<i32>,
+    /// The keys of the events that older versions remembered here. These are moved to the [`SEEN_FILE_NAME`] file when loading.
+    #[serde(default, skip_serializing)]
+    pub seen: SeenEvents,
+    /// How many keys of seen events to remember at most. The oldest are forgotten first.
+    #[serde(default = "default_max_seen")]
+    pub max_seen: usize,
+    /// Only events at least this severe are notified about. Less severe ones are still listed.
+    #[serde(default)]
+    pub min_severity: Severity,
+    /// If given, every new event is POSTed to this URL as JSON, together with the name of its server. Unlike notifications, this ignores the mute state unless [`ConfigFile::webhook_respects_mute`] is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<Url>,
+    /// Whether events are no longer sent to the [`ConfigFile::webhook`] while notifications are muted.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub webhook_respects_mute: bool,
+    /// The number of consecutive failed polls after which an unreachable server turns the tray icon into its warning variant, such that a single hiccup doesn't make it flicker.
+    #[serde(default = "default_warn_after_failures")]
+    pub warn_after_failures: u32,
 }
 impl Default for ConfigFile {
-    fn default() -> Self { Self { muted: MuteState::Unmuted } }
+    fn default() -> Self {
+        Self {
+            muted: MuteState::Unmuted,
+            servers: Vec::new(),
+            layout: GuiLayout::default(),
+            mute_audit: false,
+            max_events: default_max_events(),
+            retention_age: None,
+            crash_reports: false,
+            notify_on_first_poll: false,
+            tray_popover: false,
+            notifiers: default_notifiers(),
+            user_agent: None,
+            csv_export_path: None,
+            escalation: None,
+            menu: Vec::new(),
+            snoozed: HashMap::new(),
+            follow_os_dnd: false,
+            max_concurrent_polls: None,
+            metrics_addr: None,
+            sort: SortKey::default(),
+            severity_icons: HashMap::new(),
+            max_notifications_per_minute: default_max_notifications_per_minute(),
+            language: None,
+            max_pinned_events: default_max_pinned_events(),
+            timestamps: TimestampPolicy::default(),
+            control_socket: None,
+            width: None,
+            height: None,
+            x: None,
+            y: None,
+            seen: SeenEvents::default(),
+            max_seen: default_max_seen(),
+            min_severity: Severity::default(),
+            webhook: None,
+            webhook_respects_mute: false,
+            warn_after_failures: default_warn_after_failures(),
+        }
+    }
+}
+impl ConfigFile {
+    /// Loads a ConfigFile from some user-provided file, e.g., one that was dropped onto the window.
+    ///
+    /// Both TOML- and JSON-files are supported, as decided by the file's extension.
+    ///
+    /// # Arguments
+    /// - `path`: The path of the file to import.
+    ///
+    /// # Returns
+    /// A new ConfigFile with the contents of the given file.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read or parse the file, or if its contents are not valid.
+    pub fn import(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path: &Path = path.as_ref();
+        debug!("Importing config file '{}'...", path.display());
+
+        // Parse it according to its extension
+        let ext: Option<String> = path.extension().map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+        let config: Self = match ext.as_deref() {
+            Some("toml") => match Self::from_path(path) {
+                Ok(config) => config,
+                Err(err) => return Err(Error::ConfigLoad { path: path.into(), err }),
+            },
+            Some("json") => {
+                let raw: String = match fs::read_to_string(path) {
+                    Ok(raw) => raw,
+                    Err(err) => return Err(Error::ConfigImportRead { path: path.into(), err }),
+                };
+                match serde_json::from_str(&raw) {
+                    Ok(config) => config,
+                    Err(err) => return Err(Error::ConfigImportJson { path: path.into(), err }),
+                }
+            },
+            _ => return Err(Error::ConfigImportUnknownExt { path: path.into() }),
+        };
+
+        // Validate it
+        if let Err(errs) = config.validate() {
+            let reason: String = errs.iter().map(ToString::to_string).collect::<Vec<String>>().join("; ");
+            return Err(Error::ConfigImportInvalid { path: path.into(), reason });
+        }
+        Ok(config)
+    }
+
+    /// Checks whether this ConfigFile makes sense, beyond what parsing it already checked.
+    ///
+    /// This checks that every server has a unique name, that its URL matches its [`Transport`], that its intervals are positive and
+    /// that any environment variables its tokens refer to can be read.
+    ///
+    /// # Errors
+    /// This function errors with every problem that it found, if any.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errs: Vec<ValidationError> = Vec::new();
+        for (i, server) in self.servers.iter().enumerate() {
+            if server.name.is_empty() {
+                errs.push(ValidationError::EmptyName { index: i });
+            } else if self.servers[..i].iter().any(|other| other.name == server.name) {
+                errs.push(ValidationError::DuplicateName { name: server.name.clone() });
+            }
+            if let Err(server_errs) = server.validate() {
+                errs.extend(server_errs);
+            }
+        }
+        if errs.is_empty() {
+            Ok(())
+        } else {
+            Err(errs)
+        }
+    }
 }
 impl Serializable<Toml<ConfigFile>> for ConfigFile {}
 
 
 
-/// Describes if we're muted and, if so, when we're unmuted again.
-#[derive(Clone, Debug, Deserialize, EnumDebug, Serialize)]
-#[serde(rename_all = "snake_case")]
-pub enum MuteState {
-    /// Not muted.
-    Unmuted,
-    /// Resuming once the given timestamp has been passed.
-    After(DateTime<Local>),
-    /// Resuming next time on load.
-    NextBoot,
-    /// No automatic end specified, waiting for manual intervention.
-    Manual,
+/// Describes a single remote server that we keep track of.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ServerConfig {
+    /// Some human-friendly name for the server.
+    pub name: String,
+    /// The address of the server's events API.
+    pub url: Url,
+    /// The time between two polls of the server's events API, in seconds.
+    #[serde(default = "default_poll_interval", with = "duration_secs")]
+    pub poll_interval: Duration,
+    /// Whether to poll faster while the server is busy and slower while it's quiet, instead of sticking to [`ServerConfig::poll_interval`].
+    #[serde(default)]
+    pub adaptive: bool,
+    /// The shortest time between two polls in adaptive mode, in seconds.
+    #[serde(default = "default_min_interval", with = "duration_secs")]
+    pub min_interval: Duration,
+    /// The longest time between two polls in adaptive mode, in seconds.
+    #[serde(default = "default_max_interval", with = "duration_secs")]
+    pub max_interval: Duration,
+    /// The address of the server's human-friendly dashboard, if it has a separate one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dashboard_url: Option<Url>,
+    /// The token to authenticate to the server's events API with, if any. Shorthand for an [`AuthConfig::Bearer`] in
+    /// [`ServerConfig::auth`], which takes precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<Token>,
+    /// How to authenticate to the server's events API, if it's not with a bearer [`ServerConfig::token`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<AuthConfig>,
+    /// Whether to accept invalid (e.g., self-signed) TLS certificates from this server. This is insecure, so only use it on trusted
+    /// private networks. Same as [`TlsConfig::danger_accept_invalid`] in [`ServerConfig::tls`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub accept_invalid_certs: bool,
+    /// How to verify the server's TLS certificate, if it's not with the usual root certificates (e.g., because it's signed by a
+    /// private CA).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+    /// The field in the server's events that holds a link to more information, if it's not `url`. May be a top-level field name or a
+    /// JSON pointer (e.g., `/links/self`). Relative links are resolved against [`ServerConfig::url`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_url_field: Option<String>,
+    /// If given, collects the server's events over this many seconds and notifies about them in a single summary.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "opt_duration_secs")]
+    pub digest_interval: Option<Duration>,
+    /// Whether critical events skip the digest and are notified about immediately.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub digest_bypass_critical: bool,
+    /// How the server splits its events over multiple pages, if it does.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Pagination>,
+    /// Where the server keeps the fields of its events, if it doesn't use our names.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<FieldMapping>,
+    /// Whether to drop the fields of the server's events that we don't know, instead of keeping them as [`Event::metadata`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_metadata: bool,
+    /// The icon (e.g., an emoji) to show in front of this server's events, regardless of their severity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Extra headers to send with every request to this server (e.g., an API version or tenant ID).
+    ///
+    /// These never override the headers that we set ourselves: if the server has a [`ServerConfig::token`] or [`ServerConfig::auth`],
+    /// a header given here with the same name is dropped, and the headers used for caching are always ours.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty", deserialize_with = "deserialize_headers")]
+    pub headers: BTreeMap<String, String>,
+    /// If given, authenticates with short-lived tokens from an OAuth client-credentials exchange instead of with [`ServerConfig::token`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth: Option<OAuth>,
+    /// How we get the server's events.
+    #[serde(default)]
+    pub transport: Transport,
 }
-impl MuteState {
-    /// Checks whether this refers to some muted state.
+impl ServerConfig {
+    /// Constructor for a ServerConfig that uses the default for all optional settings.
+    ///
+    /// # Arguments
+    /// - `name`: Some human-friendly name for the server.
+    /// - `url`: The address of the server's events API.
+    ///
+    /// # Returns
+    /// A new ServerConfig that is polled every minute without any of the extras.
+    pub fn new(name: impl Into<String>, url: Url) -> Self {
+        Self {
+            name: name.into(),
+            url,
+            poll_interval: default_poll_interval(),
+            adaptive: false,
+            min_interval: default_min_interval(),
+            max_interval: default_max_interval(),
+            dashboard_url: None,
+            token: None,
+            auth: None,
+            accept_invalid_certs: false,
+            tls: None,
+            event_url_field: None,
+            digest_interval: None,
+            digest_bypass_critical: false,
+            pagination: None,
+            mapping: None,
+            skip_metadata: false,
+            icon: None,
+            headers: BTreeMap::new(),
+            oauth: None,
+            transport: Transport::Poll,
+        }
+    }
+
+    /// Returns the address of the server's dashboard.
+    ///
+    /// # Returns
+    /// The [`ServerConfig::dashboard_url`] if it is given, or else the origin of [`ServerConfig::url`].
+    pub fn dashboard(&self) -> Url {
+        if let Some(url) = &self.dashboard_url {
+            return url.clone();
+        }
+        let mut url: Url = self.url.clone();
+        url.set_path("/");
+        url.set_query(None);
+        url.set_fragment(None);
+        url
+    }
+
+    /// Returns how to verify the server's TLS certificate, taking [`ServerConfig::accept_invalid_certs`] into account.
+    ///
+    /// # Returns
+    /// The [`ServerConfig::tls`], or the default [`TlsConfig`] if there is none.
+    pub fn effective_tls(&self) -> TlsConfig {
+        let mut tls: TlsConfig = self.tls.clone().unwrap_or_default();
+        tls.danger_accept_invalid |= self.accept_invalid_certs;
+        tls
+    }
+
+    /// Returns the header to authenticate with, fetching its secret from wherever it lives.
+    ///
+    /// # Returns
+    /// The header's name and value, the latter of which is marked as sensitive; or [`None`] if this server doesn't authenticate (or
+    /// only with [`ServerConfig::oauth`]).
+    ///
+    /// # Errors
+    /// This function errors if we failed to get the secret, or if it doesn't fit in a header.
+    pub fn resolve_auth(&self) -> Result<Option<(HeaderName, HeaderValue)>, Error> {
+        match (&self.auth, &self.token) {
+            (Some(auth), _) => auth.resolve(&self.name).map(Some),
+            (None, Some(token)) => AuthConfig::Bearer(token.clone()).resolve(&self.name).map(Some),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Checks whether this ServerConfig makes sense, beyond what parsing it already checked.
+    ///
+    /// Tokens in the OS secret store are not checked, since that may prompt the user.
+    ///
+    /// # Errors
+    /// This function errors with every problem that it found, if any. Whether the name is unique is left to [`ConfigFile::validate()`].
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errs: Vec<ValidationError> = Vec::new();
+
+        // The URL must fit the transport
+        let (schemes, expected): (&[&str], &'static str) = match self.transport {
+            Transport::Poll | Transport::Sse => (&["http", "https"], "'http://' or 'https://'"),
+            Transport::WebSocket => (&["ws", "wss"], "'ws://' or 'wss://'"),
+        };
+        if !schemes.contains(&self.url.scheme()) {
+            errs.push(ValidationError::UrlScheme { server: self.name.clone(), url: self.url.clone(), expected });
+        }
+
+        // We'd poll in a busy loop with zero intervals
+        if self.poll_interval.is_zero() {
+            errs.push(ValidationError::IntervalZero { server: self.name.clone(), field: "poll_interval" });
+        }
+        if self.adaptive {
+            if self.min_interval.is_zero() {
+                errs.push(ValidationError::IntervalZero { server: self.name.clone(), field: "min_interval" });
+            }
+            if self.min_interval > self.max_interval {
+                errs.push(ValidationError::IntervalOrder { server: self.name.clone() });
+            }
+        }
+
+        // Any environment variables that we get tokens from must be there
+        let mut tokens: Vec<&Token> = self.token.iter().collect();
+        match &self.auth {
+            Some(AuthConfig::Bearer(token)) => tokens.push(token),
+            Some(AuthConfig::Basic { pass, .. }) => tokens.push(pass),
+            Some(AuthConfig::Header { name, value }) => {
+                if let Err(err) = HeaderName::from_bytes(name.as_bytes()) {
+                    errs.push(ValidationError::AuthHeaderName { server: self.name.clone(), name: name.clone(), err });
+                }
+                tokens.push(value);
+            },
+            None => {},
+        }
+        if let Some(oauth) = &self.oauth {
+            tokens.push(&oauth.client_secret);
+        }
+        for token in tokens {
+            if let Token::Env(var) = token {
+                if let Err(err) = std::env::var(var) {
+                    errs.push(ValidationError::TokenEnv { server: self.name.clone(), var: var.clone(), err });
+                }
+            }
+        }
+
+        if errs.is_empty() {
+            Ok(())
+        } else {
+            Err(errs)
+        }
+    }
+}
+
+
+
+/// Describes how to get short-lived tokens for a [`ServerConfig`] through an OAuth client-credentials exchange.
+///
+/// The tokens themselves are only kept in memory, and are refreshed shortly before they expire.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OAuth {
+    /// The address to exchange the client credentials for a token at.
+    pub token_endpoint: Url,
+    /// The ID of this client.
+    pub client_id: String,
+    /// The secret of this client. Like [`ServerConfig::token`], this may be `"keyring"` to get it from the OS secret store.
+    pub client_secret: Token,
+    /// The scope(s) to ask for, if the server wants any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+
+
+/// Describes how to verify the TLS certificate of a [`ServerConfig`].
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TlsConfig {
+    /// A root certificate (PEM) to trust on top of the usual ones, such as that of a private CA.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<PathBuf>,
+    /// Whether to accept any certificate, including invalid ones. This is insecure, so only use it on trusted private networks.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub danger_accept_invalid: bool,
+}
+
+
+
+/// Describes how to authenticate to a [`ServerConfig`]'s events API.
+///
+/// The secrets in here are [`Token`]s, so they may also come from the environment or the OS secret store.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthConfig {
+    /// Sends the token as `Authorization: Bearer <token>`.
+    Bearer(Token),
+    /// Sends the user and password as `Authorization: Basic <credentials>`.
+    Basic {
+        /// The name of the user to authenticate as.
+        user: String,
+        /// The password of the user.
+        pass: Token,
+    },
+    /// Sends the token as-is in a header of choice (e.g., `X-Api-Key`).
+    Header {
+        /// The name of the header.
+        name:  String,
+        /// The value of the header.
+        value: Token,
+    },
+}
+impl AuthConfig {
+    /// Returns the header to authenticate with, fetching its secret from wherever it lives.
+    ///
+    /// # Arguments
+    /// - `server`: The name of the server that the secret belongs to.
+    ///
+    /// # Returns
+    /// The header's name and value, the latter of which is marked as sensitive such that it's never printed.
+    ///
+    /// # Errors
+    /// This function errors if we failed to get the secret, or if it doesn't fit in a header.
+    pub fn resolve(&self, server: &str) -> Result<(HeaderName, HeaderValue), Error> {
+        let (name, value): (HeaderName, String) = match self {
+            Self::Bearer(token) => (AUTHORIZATION, format!("Bearer {}", token.resolve(server)?)),
+            Self::Basic { user, pass } => (AUTHORIZATION, format!("Basic {}", BASE64_STANDARD.encode(format!("{user}:{}", pass.resolve(server)?)))),
+            Self::Header { name, value } => match HeaderName::from_bytes(name.as_bytes()) {
+                Ok(name) => (name, value.resolve(server)?),
+                Err(err) => return Err(Error::AuthHeaderName { server: server.into(), name: name.clone(), err }),
+            },
+        };
+        let mut value: HeaderValue = match HeaderValue::from_str(&value) {
+            Ok(value) => value,
+            Err(err) => return Err(Error::AuthHeaderValue { server: server.into(), name, err }),
+        };
+        value.set_sensitive(true);
+        Ok((name, value))
+    }
+}
+
+
+
+/// Describes how a [`ServerConfig`]'s token is stored.
+///
+/// In the config file, this is either the token itself, `"${VAR}"` to read it from the environment variable `VAR`, or the literal
+/// string `"keyring"`.
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum Token {
+    /// The token is read from the environment variable with this name.
+    Env(String),
+    /// The token lives in the OS secret store, keyed by the server's name.
+    Keyring,
+    /// The token is given as-is in the config file.
+    Plain(String),
+}
+impl Token {
+    /// Returns the actual token, fetching it from the environment or the OS secret store if necessary.
+    ///
+    /// # Arguments
+    /// - `server`: The name of the server that the token belongs to, which is what it's keyed by in the OS secret store.
+    ///
+    /// # Returns
+    /// The token as a string.
+    ///
+    /// # Errors
+    /// This function errors if the token lives in the environment or the OS secret store but we failed to get it from there.
+    pub fn resolve(&self, server: &str) -> Result<String, Error> {
+        match self {
+            Self::Env(var) => match std::env::var(var) {
+                Ok(token) => Ok(token),
+                Err(err) => Err(Error::TokenEnv { server: server.into(), var: var.clone(), err }),
+            },
+            Self::Plain(token) => Ok(token.clone()),
+            Self::Keyring => {
+                let entry: keyring::Entry = match keyring::Entry::new(KEYRING_SERVICE, server) {
+                    Ok(entry) => entry,
+                    Err(err) => return Err(Error::KeyringOpen { server: server.into(), err }),
+                };
+                match entry.get_password() {
+                    Ok(token) => Ok(token),
+                    Err(keyring::Error::NoEntry) => Err(Error::KeyringMissing { server: server.into() }),
+                    Err(err) => Err(Error::KeyringRead { server: server.into(), err }),
+                }
+            },
+        }
+    }
+}
+impl Debug for Token {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        match self {
+            Self::Env(var) => write!(f, "Env({var:?})"),
+            Self::Keyring => write!(f, "Keyring"),
+            Self::Plain(_) => write!(f, "Plain(<redacted>)"),
+        }
+    }
+}
+impl From<String> for Token {
+    #[inline]
+    fn from(value: String) -> Self {
+        if value == "keyring" {
+            Self::Keyring
+        } else if let Some(var) = value.strip_prefix("${").and_then(|value| value.strip_suffix('}')) {
+            Self::Env(var.into())
+        } else {
+            Self::Plain(value)
+        }
+    }
+}
+impl From<Token> for String {
+    #[inline]
+    fn from(value: Token) -> Self {
+        match value {
+            Token::Env(var) => format!("${{{var}}}"),
+            Token::Keyring => "keyring".into(),
+            Token::Plain(token) => token,
+        }
+    }
+}
+
+
+
+/// Describes when recurring events are considered more severe than they claim to be.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Escalation {
+    /// The number of times that an event may occur within the window before it's escalated.
+    pub count: usize,
+    /// The window in which occurrences are counted, in seconds.
+    #[serde(with = "duration_secs")]
+    pub window: Duration,
+    /// The severity that recurring events are raised to. Events that are already more severe are left as-is.
+    pub to_severity: Severity,
+}
+
+
+
+/// Describes what to do with events whose timestamp can't be trusted, as those would mess up sorting and pruning.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TimestampPolicy {
+    /// Whether to drop events that have no timestamp, instead of keeping them with the time they were received.
+    #[serde(default)]
+    pub drop_missing:     bool,
+    /// How far (in seconds) an event's timestamp may lie in the future before it's replaced with the time it was received.
+    #[serde(default = "default_future_tolerance", with = "duration_secs")]
+    pub future_tolerance: Duration,
+}
+impl Default for TimestampPolicy {
+    #[inline]
+    fn default() -> Self { Self { drop_missing: false, future_tolerance: default_future_tolerance() } }
+}
+
+
+
+/// Describes how we get the events of a [`ServerConfig`].
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumDebug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    /// Asks the server for its events every [`ServerConfig::poll_interval`].
+    #[default]
+    Poll,
+    /// Keeps a connection open on which the server pushes its events as
+    /// [Server-Sent Events](https://html.spec.whatwg.org/multipage/server-sent-events.html).
+    ///
+    /// The `data` of every message is a single event or a list of them. [`ServerConfig::pagination`] and HTTP caching don't apply.
+    Sse,
+    /// Keeps a WebSocket open (at a `ws://` or `wss://` [`ServerConfig::url`]) on which the server pushes its events.
+    ///
+    /// Every text message is a single event or a list of them. [`ServerConfig::pagination`] and HTTP caching don't apply.
+    #[serde(rename = "websocket")]
+    WebSocket,
+}
+
+
+
+/// Describes how a [`ServerConfig`] splits its events over multiple pages.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Pagination {
+    /// Pages are selected with a query parameter (e.g., `?page=2`), counting from 1. Every page is a list of events, and the first
+    /// empty one ends the feed.
+    Query {
+        /// The name of the query parameter.
+        #[serde(default = "default_page_param")]
+        param:     String,
+        /// The maximum number of pages to fetch per poll.
+        #[serde(default = "default_max_pages")]
+        max_pages: usize,
+    },
+    /// Every page is an object with the events in one field and a cursor to the next page in another. A missing or `null` cursor
+    /// ends the feed.
+    Cursor {
+        /// The field that holds the list of events.
+        #[serde(default = "default_items_field")]
+        items_field: String,
+        /// The field that holds the cursor. If it's a full URL, it's followed as-is; otherwise, it's passed in `param`.
+        #[serde(default = "default_cursor_field")]
+        cursor_field: String,
+        /// The name of the query parameter to pass the cursor in.
+        #[serde(default = "default_cursor_param")]
+        param: String,
+        /// The maximum number of pages to fetch per poll.
+        #[serde(default = "default_max_pages")]
+        max_pages: usize,
+    },
+}
+impl Pagination {
+    /// Returns the maximum number of pages to fetch per poll.
+    ///
+    /// # Returns
+    /// The `max_pages` of either scheme.
+    #[inline]
+    pub fn max_pages(&self) -> usize {
+        match self {
+            Self::Query { max_pages, .. } => *max_pages,
+            Self::Cursor { max_pages, .. } => *max_pages,
+        }
+    }
+}
+
+
+
+/// Describes in which fields a server's events keep what, for servers that don't use the names of [`Event`].
+///
+/// Every field may be a top-level field name or a JSON pointer (e.g., `/meta/created_at`). Fields that aren't given are read from
+/// the names of [`Event`].
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FieldMapping {
+    /// The field that holds the event's ID. Numbers are accepted too.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The field that holds the event's title.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The field that holds the event's longer description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// The field that holds when the event happened. Besides RFC 3339 strings, Unix timestamps (in seconds) are accepted too.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    /// The field that holds the event's severity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    /// The field that holds the name of the server that emitted the event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Translates the server's severities to ours (e.g., `err = "critical"`). Severities that aren't in here are used as-is.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub severities: HashMap<String, Severity>,
+}
+
+
+
+/// Remembers which events we've seen by their [`Event::key()`], such that they are not new again once we've forgotten the events
+/// themselves (e.g., after a restart or after they were pruned).
+///
+/// Keys are remembered in the order in which they were seen, such that the oldest can be forgotten first.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(from = "VecDeque<String>", into = "VecDeque<String>")]
+pub struct SeenEvents {
+    /// The keys, oldest first.
+    order: VecDeque<String>,
+    /// The same keys, to quickly find them.
+    keys:  HashSet<String>,
+}
+impl From<VecDeque<String>> for SeenEvents {
+    fn from(value: VecDeque<String>) -> Self {
+        let mut this: Self = Self::default();
+        for key in value {
+            this.insert(key, usize::MAX);
+        }
+        this
+    }
+}
+impl From<SeenEvents> for VecDeque<String> {
+    #[inline]
+    fn from(value: SeenEvents) -> Self { value.order }
+}
+impl SeenEvents {
+    /// Checks whether we've seen an event.
+    ///
+    /// # Arguments
+    /// - `key`: The [`Event::key()`] of the event.
+    ///
+    /// # Returns
+    /// True if we've seen it (and haven't forgotten about it since), or false otherwise.
+    #[inline]
+    pub fn contains(&self, key: &str) -> bool { self.keys.contains(key) }
+
+    /// Remembers that we've seen an event.
+    ///
+    /// # Arguments
+    /// - `key`: The [`Event::key()`] of the event.
+    /// - `max`: The maximum number of keys to remember. If there are more, the oldest are forgotten.
+    ///
+    /// # Returns
+    /// True if we hadn't seen the event yet, or false otherwise.
+    pub fn insert(&mut self, key: String, max: usize) -> bool {
+        if self.keys.contains(&key) {
+            return false;
+        }
+        self.keys.insert(key.clone());
+        self.order.push_back(key);
+        while self.order.len() > max {
+            if let Some(key) = self.order.pop_front() {
+                self.keys.remove(&key);
+            }
+        }
+        true
+    }
+
+    /// Returns whether we remember any events.
+    ///
+    /// # Returns
+    /// True if no events have been seen (or all have been forgotten), or false otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.order.is_empty() }
+}
+
+
+
+/// Counts how many events came in per minute over the last [`ACTIVITY_MINUTES`] minutes, to show bursts at a glance.
+#[derive(Clone, Debug, Default)]
+pub struct Activity {
+    /// The minute (since the Unix epoch) that the last of the `buckets` is about.
+    latest:  i64,
+    /// The events that came in per minute, oldest first.
+    buckets: VecDeque<ActivityBucket>,
+}
+impl Activity {
+    /// Returns the minute (since the Unix epoch) that the given time falls in.
+    #[inline]
+    fn minute(time: DateTime<Local>) -> i64 { time.timestamp().div_euclid(60) }
+
+    /// Moves the buckets along with the time, dropping the ones that have become too old.
+    ///
+    /// # Arguments
+    /// - `now`: The current time, whose minute becomes the last bucket.
+    pub fn age(&mut self, now: DateTime<Local>) {
+        // If the clock went backwards, we simply keep counting in the latest bucket
+        let minute: i64 = Self::minute(now);
+        let passed: usize = (minute - self.latest).clamp(0, ACTIVITY_MINUTES as i64) as usize;
+        self.buckets.extend(std::iter::repeat_n(ActivityBucket::default(), passed));
+        self.latest = self.latest.max(minute);
+        while self.buckets.len() > ACTIVITY_MINUTES {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Counts a new event.
+    ///
+    /// # Arguments
+    /// - `now`: The time at which the event came in.
+    /// - `severity`: The [`Severity`] of the event.
+    pub fn record(&mut self, now: DateTime<Local>, severity: Severity) {
+        self.age(now);
+        if let Some(bucket) = self.buckets.back_mut() {
+            bucket.count += 1;
+            bucket.severity = bucket.severity.max(Some(severity));
+        }
+    }
+
+    /// Returns the buckets as they are at the given time.
+    ///
+    /// # Arguments
+    /// - `now`: The current time, whose minute is the last bucket.
+    ///
+    /// # Returns
+    /// Exactly [`ACTIVITY_MINUTES`] [`ActivityBucket`]s, oldest first.
+    pub fn buckets(&self, now: DateTime<Local>) -> Vec<ActivityBucket> {
+        let mut aged: Self = self.clone();
+        aged.age(now);
+        aged.buckets.into()
+    }
+}
+
+/// Describes the events that came in during a single minute.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ActivityBucket {
+    /// The number of events.
+    pub count:    usize,
+    /// The highest [`Severity`] among them, or [`None`] if there were none.
+    pub severity: Option<Severity>,
+}
+
+
+
+/// Describes what a server told us about the version of its events that we last downloaded.
+///
+/// Sent back on the next poll, such that the server can tell us nothing changed instead of sending everything again.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HttpCache {
+    /// The value of the last `ETag`-header, if any.
+    pub etag: Option<String>,
+    /// The value of the last `Last-Modified`-header, if any.
+    pub last_modified: Option<String>,
+    /// The short-lived token that we got for a server with [`ServerConfig::oauth`], if any. This is never written to disk.
+    pub access_token: Option<AccessToken>,
+}
+
+
+
+/// Describes a short-lived token that we got from a [`ServerConfig`]'s [`OAuth`] token endpoint.
+#[derive(Clone, Eq, PartialEq)]
+pub struct AccessToken {
+    /// The token itself.
+    pub token:   String,
+    /// When the token expires, if the endpoint told us.
+    pub expires: Option<Instant>,
+}
+impl Debug for AccessToken {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { f.debug_struct("AccessToken").field("token", &"<redacted>").field("expires", &self.expires).finish() }
+}
+
+
+
+/// Describes how the last poll of a server went.
+#[derive(Clone, Copy, Debug, EnumDebug, Eq, Hash, PartialEq)]
+pub enum ServerStatus {
+    /// The server gave us its events.
+    Ok,
+    /// The server answered, but not with events that we could use (e.g., an error status or credentials it rejected).
+    Degraded,
+    /// We could not reach the server at all.
+    Unreachable,
+}
+impl ServerStatus {
+    /// Describes the status to the user.
+    ///
+    /// # Returns
+    /// A short description like `"unreachable"`.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Ok => "OK",
+            Self::Degraded => "degraded",
+            Self::Unreachable => "unreachable",
+        }
+    }
+}
+
+
+
+/// Describes one of the built-in items that can be put in the tray icon's menu.
+#[derive(Clone, Copy, Debug, Deserialize, EnumDebug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinMenuItem {
+    /// Opens (or focuses) the main window.
+    Open,
+    /// Toggles whether notifications are muted.
+    Mute,
+    /// A submenu for muting notifications for a fixed time.
+    MuteFor,
+    /// A submenu for choosing the least severe events to notify about.
+    MinSeverity,
+    /// A submenu for opening the dashboard of every server. Omitted if there are no servers.
+    Dashboards,
+    /// Polls all servers right away.
+    Refresh,
+    /// Acknowledges the newest unread event, such that it no longer counts towards the badge, without muting anything else.
+    AcknowledgeNewest,
+    /// Closes the app.
+    Exit,
+}
+
+
+
+/// Describes what a custom item in the tray icon's menu does.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MenuAction {
+    /// Opens the given URL in the browser.
+    OpenUrl(Url),
+    /// Polls all servers right away.
+    Refresh,
+}
+
+
+
+/// Describes a single entry in the tray icon's menu.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MenuItemSpec {
+    /// One of the built-in items.
+    Builtin { item: BuiltinMenuItem },
+    /// An item of the user's own.
+    Custom {
+        /// The text of the item.
+        label:  String,
+        /// What happens when the item is clicked.
+        action: MenuAction,
+    },
+    /// A line between items.
+    Separator,
+}
+impl MenuItemSpec {
+    /// Returns the menu that is used if the user didn't configure one.
+    ///
+    /// # Returns
+    /// A list of [`MenuItemSpec`]s with the open, mute, mute for, acknowledge newest, severity, dashboards and exit items.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::Builtin { item: BuiltinMenuItem::Open },
+            Self::Builtin { item: BuiltinMenuItem::Mute },
+            Self::Builtin { item: BuiltinMenuItem::MuteFor },
+            Self::Builtin { item: BuiltinMenuItem::AcknowledgeNewest },
+            Self::Builtin { item: BuiltinMenuItem::MinSeverity },
+            Self::Builtin { item: BuiltinMenuItem::Dashboards },
+            Self::Separator,
+            Self::Builtin { item: BuiltinMenuItem::Exit },
+        ]
+    }
+}
+
+
+
+/// Describes the layouts that the window can be in.
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumDebug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuiLayout {
+    /// The normal, full-sized window.
+    #[default]
+    Full,
+    /// A tiny, always-on-top window that only shows the bare essentials.
+    Mini,
+}
+
+
+
+/// Describes the orders in which the event list can be shown.
+///
+/// This only affects how the list is drawn; the events are always stored newest-first.
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumDebug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    /// The newest events first.
+    #[default]
+    Newest,
+    /// The most severe events first.
+    Severity,
+    /// Alphabetically by the server that emitted them.
+    Server,
+}
+impl SortKey {
+    /// All sort keys, in the order in which they are offered to the user.
+    pub const ALL: [Self; 3] = [Self::Newest, Self::Severity, Self::Server];
+
+    /// Compares two events according to this key.
+    ///
+    /// Events that are equal by this key are ordered newest-first, such that the order is stable between frames.
+    ///
+    /// # Arguments
+    /// - `lhs`: The first [`Event`] to compare.
+    /// - `rhs`: The second [`Event`] to compare.
+    ///
+    /// # Returns
+    /// The [`Ordering`] of `lhs` relative to `rhs`.
+    pub fn compare(&self, lhs: &Event, rhs: &Event) -> Ordering {
+        let primary: Ordering = match self {
+            Self::Newest => Ordering::Equal,
+            Self::Severity => rhs.severity.cmp(&lhs.severity),
+            Self::Server => lhs.source.cmp(&rhs.source),
+        };
+        primary.then_with(|| rhs.timestamp.cmp(&lhs.timestamp))
+    }
+
+    /// Returns a human-friendly name for this key.
+    ///
+    /// # Returns
+    /// The [`Text`] of the label to show in the UI.
+    #[inline]
+    pub fn label(&self) -> Text {
+        match self {
+            Self::Newest => Text::SortNewest,
+            Self::Severity => Text::SortSeverity,
+            Self::Server => Text::SortServer,
+        }
+    }
+}
+
+
+
+/// Describes a single change to the settings, as part of a [`Transaction`].
+#[derive(Clone, Debug, EnumDebug)]
+pub enum Change {
+    /// Adds a new server, which must have a name that isn't in use yet.
+    AddServer(ServerConfig),
+    /// Removes the server with the given name.
+    RemoveServer { name: String },
+    /// Changes how often the server with the given name is polled.
+    SetPollInterval { name: String, interval: Duration },
+    /// Changes the mute state.
+    SetMuted(MuteState),
+    /// Snoozes (or, with [`MuteState::Unmuted`], unsnoozes) events with the given title.
+    Snooze { title: String, snooze: MuteState },
+}
+
+
+
+/// Describes if we're muted and, if so, when we're unmuted again.
+#[derive(Clone, Debug, Deserialize, EnumDebug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MuteState {
+    /// Not muted.
+    Unmuted,
+    /// Resuming once the given timestamp has been passed.
+    ///
+    /// This is kept in UTC, such that it denotes the same moment if the local timezone changes (e.g., by travelling or a DST
+    /// transition) between writing and reading it. The config file still shows it in local time.
+    After(#[serde(with = "rfc3339")] DateTime<Utc>),
+    /// Resuming next time on load.
+    NextBoot,
+    /// No automatic end specified, waiting for manual intervention.
+    Manual,
+}
+impl MuteState {
+    /// Constructor for a MuteState that lasts for the given time.
+    ///
+    /// # Arguments
+    /// - `now`: The current time.
+    /// - `duration`: How long the mute should last.
+    ///
+    /// # Returns
+    /// A [`MuteState::After`] with a deadline `duration` from `now`, or [`MuteState::Manual`] if that's too far away to represent.
+    pub fn lasting(now: DateTime<Local>, duration: Duration) -> Self {
+        match chrono::Duration::from_std(duration).ok().and_then(|duration| now.checked_add_signed(duration)) {
+            Some(deadline) => Self::After(deadline.with_timezone(&Utc)),
+            None => Self::Manual,
+        }
+    }
+
+    /// Constructor for a MuteState that lasts for the given time from now.
+    ///
+    /// Shorthand for [`MuteState::lasting()`] with the wall clock. Where an [`AppState`] is at hand, prefer passing
+    /// [`AppState::now()`] to that instead, such that its clock is respected.
+    ///
+    /// # Arguments
+    /// - `duration`: How long the mute should last.
+    ///
+    /// # Returns
+    /// A [`MuteState::After`] with a deadline `duration` from now, or [`MuteState::Manual`] if that's too far away to represent.
+    #[inline]
+    pub fn for_duration(duration: Duration) -> Self { Self::lasting(Local::now(), duration) }
+
+    /// Unmutes if this is a [`MuteState::After`] whose deadline has passed.
+    ///
+    /// Meant to be called periodically; any change should then be persisted through [`AppState::access_mut()`].
+    ///
+    /// # Arguments
+    /// - `now`: The current time.
+    ///
+    /// # Returns
+    /// Whether this changed to [`MuteState::Unmuted`].
+    pub fn tick(&mut self, now: DateTime<Local>) -> bool {
+        match self {
+            Self::After(deadline) if *deadline <= now.with_timezone(&Utc) => {
+                *self = Self::Unmuted;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Checks whether this refers to some muted state.
+    ///
+    /// # Returns
+    /// True for all variants except [`MuteState::Unmuted`].
+    #[inline]
+    pub fn is_muted(&self) -> bool { !matches!(self, MuteState::Unmuted) }
+
+    /// Checks whether this refers to some unmuted state.
+    ///
+    /// # Returns
+    /// True only for [`MuteState::Unmuted`].
+    #[inline]
+    pub fn is_unmuted(&self) -> bool { matches!(self, MuteState::Unmuted) }
+
+    /// Flips between a muted and an unmuted state.
+    ///
+    /// Any muted variant becomes [`MuteState::Unmuted`], whereas [`MuteState::Unmuted`] becomes [`MuteState::Manual`].
+    #[inline]
+    pub fn toggle(&mut self) { *self = if self.is_muted() { MuteState::Unmuted } else { MuteState::Manual }; }
+}
+
+
+
+/// The mute deadlines in the config file as they are written there, to find those that [`stale_deadlines()`] should migrate.
+///
+/// This is only ever read, but [`Serializable`] wants to be able to write it too.
+#[derive(Debug, Deserialize, Serialize)]
+struct StoredDeadlines {
+    /// See [`ConfigFile::muted`].
+    muted:   StoredMuteState,
+    /// See [`ConfigFile::snoozed`].
+    #[serde(default)]
+    snoozed: HashMap<String, StoredMuteState>,
+}
+impl Serializable<Toml<StoredDeadlines>> for StoredDeadlines {}
+
+/// Mirrors [`MuteState`], but remembers in which format its deadline was written.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum StoredMuteState {
+    /// See [`MuteState::Unmuted`].
+    Unmuted,
+    /// See [`MuteState::After`].
+    After(StoredDeadline),
+    /// See [`MuteState::NextBoot`].
+    NextBoot,
+    /// See [`MuteState::Manual`].
+    Manual,
+}
+
+/// A mute deadline in any of the formats that we (once) wrote to the config file.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum StoredDeadline {
+    /// RFC3339 with the current local offset, as [`rfc3339::serialize()`] writes it now.
+    Current(#[serde(serialize_with = "rfc3339::serialize", deserialize_with = "rfc3339::deserialize_current")] DateTime<Utc>),
+    /// RFC3339 with the local offset at the time of writing, or chrono's own format, as older versions wrote it.
+    Old(#[serde(with = "rfc3339")] DateTime<Utc>),
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines some shared state for the whole app.
+///
+/// Actually just wraps [`MutableAppState`] plus some non-mutable stuff with some locks and junk.
+#[derive(Clone, Debug)]
+pub struct AppState {
+    // Immutable part
+    /// The location of all the config files.
+    config_dir: PathBuf,
+    /// The location of any chache files (e.g., tray icon thumbnail).
+    cache_dir:  PathBuf,
+    /// The clock that we use to decide what time it is.
+    clock:      Arc<dyn Clock>,
+    /// Whether we can write to the `config_dir`. If not, changes are kept in memory only.
+    persistent: bool,
+
+    // Mutable part
+    /// The mutable part of the app state behind a lock.
+    mut_state: Arc<RwLock<MutableAppState>>,
+}
+impl AppState {
+    /// Constructor for the AppState.
+    ///
+    /// # Arguments
+    /// - `config_dir`: The directory where the app's persistent config is stored.
+    ///
+    /// # Returns
+    /// A new AppState that is ready to be used in the app.
+    ///
+    /// # Errors
+    /// This function may error if we failed to load any of the persistent state in the `config_dir`.
+    #[inline]
+    pub fn new(config_dir: PathBuf) -> Result<Self, Error> { Self::with_clock(config_dir, Arc::new(SystemClock)) }
+
+    /// Constructor for the AppState that uses a custom [`Clock`] to decide what time it is.
+    ///
+    /// # Arguments
+    /// - `config_dir`: The directory where the app's persistent config is stored.
+    /// - `clock`: The [`Clock`] that is used for all time-based logic (e.g., resolving [`MuteState::After`]).
+    ///
+    /// # Returns
+    /// A new AppState that is ready to be used in the app.
+    ///
+    /// # Errors
+    /// This function may error if we failed to load any of the persistent state in the `config_dir`.
+    pub fn with_clock(config_dir: PathBuf, clock: Arc<dyn Clock>) -> Result<Self, Error> {
+        info!("Initializing AppState...");
+
+        // Generate additional paths
+        let cache_dir: PathBuf = config_dir.join(CACHE_DIR_NAME);
+
+        // Build the mutable state
+        let mut_state: Arc<RwLock<MutableAppState>> = Arc::new(RwLock::new(MutableAppState::new(&config_dir, &*clock)?));
+        warn_invalid(&mut_state.read().to_config());
+
+        // Rather than failing every change later on, tell the user once if we can't save them
+        let persistent: bool = writable(&config_dir);
+        if !persistent {
+            warn!("Config directory '{}' is not writable; changes to the settings and the received events will not be saved", config_dir.display());
+        }
+
+        // Write back anything that was migrated while loading (the history first, such that moved data is never lost)
+        if persistent {
+            let mut lock: RwLockWriteGuard<MutableAppState> = mut_state.write();
+            if lock.history_dirty {
+                match lock.sync_history(&cache_dir) {
+                    Ok(()) => lock.history_dirty = false,
+                    // Left dirty, such that the next change tries again
+                    Err(err) => warn!("{}", err.trace()),
+                }
+            }
+            if lock.dirty && !lock.history_dirty {
+                let config_path: PathBuf = config_dir.join(CONFIG_FILE_NAME);
+                match lock.sync(&config_path) {
+                    Ok(()) => {
+                        lock.dirty = false;
+                        lock.synced = modified(&config_path);
+                    },
+                    // Left dirty, such that the next change tries again
+                    Err(err) => warn!("{}", err.trace()),
+                }
+            }
+        }
+
+        // OK, build self
+        Ok(Self { config_dir, cache_dir, clock, persistent, mut_state })
+    }
+
+    /// Returns the path of the persistent configuration for the app.
+    ///
+    /// # Returns
+    /// A [`Path`] describing where to find the folder.
+    #[inline]
+    pub fn config_dir(&self) -> &Path { &self.config_dir }
+
+    /// Returns the path of the temporary cache for the app.
+    ///
+    /// # Returns
+    /// A [`Path`] describing where to find the folder.
+    #[inline]
+    pub fn cache_dir(&self) -> &Path { &self.cache_dir }
+
+    /// Returns the [`Clock`] that decides what time it is for the app.
+    ///
+    /// # Returns
+    /// A reference to the [`Clock`] that was given at construction time.
+    #[inline]
+    pub fn clock(&self) -> &dyn Clock { &*self.clock }
+
+    /// Returns the [`Clock`] that decides what time it is for the app, such that it can be shared with something that outlives
+    /// this borrow (e.g., the [`Notifiers`](crate::notify::Notifiers)).
+    ///
+    /// # Returns
+    /// A shared handle to the [`Clock`] that was given at construction time.
+    #[inline]
+    pub fn shared_clock(&self) -> Arc<dyn Clock> { self.clock.clone() }
+
+    /// Returns whether changes to the settings are saved to the config file.
+    ///
+    /// # Returns
+    /// False if the config directory wasn't writable when the app started, in which case changes are kept in memory only.
+    #[inline]
+    pub fn is_persistent(&self) -> bool { self.persistent }
+
+    /// Returns the current time according to the app's [`Clock`].
+    ///
+    /// # Returns
+    /// A [`DateTime`] describing "now".
+    #[inline]
+    pub fn now(&self) -> DateTime<Local> { self.clock.now() }
+
+    /// Checks whether notifications should be held back right now.
+    ///
+    /// This is the case if the user muted us, or if the OS is in a do-not-disturb mode and the user wants us to follow it (see
+    /// [`MutableAppState::follow_os_dnd`]). Unlike [`MutableAppState::muted`], the latter is never stored.
+    ///
+    /// # Returns
+    /// True if we shouldn't notify the user about anything.
+    pub fn effective_muted(&self) -> bool {
+        let (muted, follow_os_dnd): (bool, bool) = self.access(|state| (state.muted.is_muted(), state.follow_os_dnd));
+        muted || (follow_os_dnd && crate::dnd::is_active())
+    }
+
+    /// Provides read-only access to the mutable part of the state.
+    ///
+    /// # Arguments
+    /// - `access_fn`: Some closure that can access the contents of the mutable app state.
+    ///
+    /// # Errors
+    /// This function errors if the given `access_fn` errors.
+    #[inline]
+    pub fn access<R>(&self, access_fn: impl FnOnce(&MutableAppState) -> R) -> R {
+        let lock: RwLockReadGuard<MutableAppState> = self.mut_state.read();
+        access_fn(&*lock)
+    }
+
+    /// Provides write access to the mutable part of the state.
+    ///
+    /// At the end of this function, if the closure did not error and changed anything persistent, then the disk is updated with the
+    /// mutated state. Methods of [`MutableAppState`] keep track of that by themselves, but closures that assign persistent fields
+    /// directly must call [`MutableAppState::mark_dirty()`] (or, for the events, [`MutableAppState::mark_history_dirty()`]). This happens while still holding the lock, such that concurrent writers can never overwrite the config with a
+    /// stale state. If the state is not [persistent](AppState::is_persistent()), the disk is left alone.
+    ///
+    /// # Arguments
+    /// - `access_fn`: Some closure that can access the contents of the mutable app state.
+    ///
+    /// # Errors
+    /// This function errors if the given `access_fn` errors, or then if writing the state back errors.
+    #[inline]
+    pub fn access_mut<R, E>(&self, access_fn: impl FnOnce(&mut MutableAppState) -> Result<R, E>) -> Result<Result<R, Error>, E> {
+        self.access_mut_from("unknown", access_fn)
+    }
+
+    /// Provides write access to the mutable part of the state on behalf of some named action.
+    ///
+    /// Works like [`AppState::access_mut()`], except that the `source` is recorded in the mute audit log if the closure changed the mute state.
+    ///
+    /// # Arguments
+    /// - `source`: Some description of what triggered this access (e.g., `"tray"`).
+    /// - `access_fn`: Some closure that can access the contents of the mutable app state.
+    ///
+    /// # Errors
+    /// This function errors if the given `access_fn` errors, or then if writing the state back errors.
+    pub fn access_mut_from<R, E>(&self, source: &str, access_fn: impl FnOnce(&mut MutableAppState) -> Result<R, E>) -> Result<Result<R, Error>, E> {
+        // Provide mutable access, with its own, unique lock
+        let mut lock: RwLockWriteGuard<MutableAppState> = self.mut_state.write();
+        let old_muted: MuteState = lock.muted.clone();
+        let res: R = access_fn(&mut *lock)?;
+
+        // There's nothing to write if we can't (the user has been told at startup)
+        if !self.persistent {
+            return Ok(Ok(res));
+        }
+
+        // Keep track of mute changes if the user wants to
+        if lock.mute_audit && lock.muted != old_muted {
+            if let Err(err) = self.audit_mute(source, &old_muted, &lock.muted) {
+                warn!("{}", err.trace());
+            }
+        }
+
+        // Don't bother the disk if nothing persistent changed, unless the previous write failed and may now succeed
+        let retry: bool = lock.sync_error.is_some();
+        if !lock.dirty && !lock.history_dirty && !retry {
+            return Ok(Ok(res));
+        }
+
+        // Now sync the mutable app state back before anyone else gets to change it
+        // (and remember how that went, such that the user can be told)
+        if lock.dirty || retry {
+            let config_path: PathBuf = self.config_dir.join(CONFIG_FILE_NAME);
+            if let Err(err) = lock.sync(&config_path) {
+                lock.sync_error = Some(format!("{}", err.trace()));
+                return Ok(Err(err));
+            }
+            lock.dirty = false;
+            lock.synced = modified(&config_path);
+        }
+        if lock.history_dirty || retry {
+            if let Err(err) = lock.sync_history(&self.cache_dir) {
+                lock.sync_error = Some(format!("{}", err.trace()));
+                return Ok(Err(err));
+            }
+            lock.history_dirty = false;
+        }
+        lock.sync_error = None;
+
+        // OK, return the result
+        Ok(Ok(res))
+    }
+
+    /// Checks whether the config file was changed by someone else since we last wrote or read it (e.g., by the user in an editor).
+    ///
+    /// # Returns
+    /// True if the file's modification time differs from the one we left it with.
+    pub fn config_changed(&self) -> bool {
+        // Checked under the lock, such that a write of ours is never halfway done
+        let config_path: PathBuf = self.config_dir.join(CONFIG_FILE_NAME);
+        self.access(|state| modified(&config_path) != state.synced)
+    }
+
+    /// Re-reads the config file and applies the settings in it, such that changes made by hand don't need a restart.
     ///
-    /// # Returns
-    /// True for all variants except [`MuteState::Unmuted`].
-    #[inline]
-    pub fn is_muted(&self) -> bool { !matches!(self, MuteState::Unmuted) }
+    /// Runtime state, such as the received events and the [`ServerStatus`] of every server, is kept, and so are the
+    /// [`MutableAppState::seen`] events. Pollers that are already running don't notice new or changed servers by themselves, and have to
+    /// be respawned for that.
+    ///
+    /// # Errors
+    /// This function errors if the config file doesn't exist or is invalid, in which case the current settings are kept.
+    pub fn reload_config(&self) -> Result<(), Error> {
+        let config_path: PathBuf = self.config_dir.join(CONFIG_FILE_NAME);
+        let mut lock: RwLockWriteGuard<MutableAppState> = self.mut_state.write();
+        // Even if this version is invalid, we've seen it and only need to try again once it changes
+        lock.synced = modified(&config_path);
+        let config: ConfigFile = match read_config(&config_path)? {
+            Some(config) => config,
+            None => return Err(Error::ConfigMissing { path: config_path }),
+        };
+        warn_invalid(&config);
+        lock.replace_config(config);
+        // The disk already has these settings
+        lock.dirty = false;
+        info!("Reloaded config file '{}'", config_path.display());
+        Ok(())
+    }
 
-    /// Checks whether this refers to some unmuted state.
+    /// Starts a [`Transaction`] that applies several [`Change`]s at once.
     ///
     /// # Returns
-    /// True only for [`MuteState::Unmuted`].
+    /// A new, empty [`Transaction`] on this state.
     #[inline]
-    pub fn is_unmuted(&self) -> bool { matches!(self, MuteState::Unmuted) }
-}
+    pub fn transaction(&self) -> Transaction<'_> { Transaction { state: self, source: "unknown", changes: Vec::new() } }
 
+    /// Stores the token of a server in the OS secret store, and updates the config to refer to it.
+    ///
+    /// # Arguments
+    /// - `server`: The name of the [`ServerConfig`] to store the token of.
+    /// - `token`: The token to store.
+    ///
+    /// # Errors
+    /// This function errors if the server is unknown, or if we failed to write to the secret store or the config file.
+    pub fn store_token(&self, server: &str, token: &str) -> Result<(), Error> {
+        if !self.access(|state| state.servers.iter().any(|other| other.name == server)) {
+            return Err(Error::ServerUnknown { name: server.into() });
+        }
 
+        // Write the token to the secret store
+        debug!("Storing token for server '{server}' in the OS secret store...");
+        let entry: keyring::Entry = match keyring::Entry::new(KEYRING_SERVICE, server) {
+            Ok(entry) => entry,
+            Err(err) => return Err(Error::KeyringOpen { server: server.into(), err }),
+        };
+        if let Err(err) = entry.set_password(token) {
+            return Err(Error::KeyringWrite { server: server.into(), err });
+        }
 
+        // Then refer to it from the config
+        match self.access_mut(|state| -> Result<(), Infallible> {
+            for other in &mut state.servers {
+                if other.name == server {
+                    other.token = Some(Token::Keyring);
+                }
+            }
+            state.mark_dirty();
+            Ok(())
+        }) {
+            Ok(res) => res,
+            Err(err) => match err {},
+        }
+    }
 
+    /// Appends a line to the mute audit log, rotating it first if it grew too large.
+    ///
+    /// # Arguments
+    /// - `source`: Some description of what changed the mute state.
+    /// - `old`: The [`MuteState`] before the change.
+    /// - `new`: The [`MuteState`] after the change.
+    ///
+    /// # Errors
+    /// This function errors if we failed to rotate or write the audit log.
+    fn audit_mute(&self, source: &str, old: &MuteState, new: &MuteState) -> Result<(), Error> {
+        let path: PathBuf = self.config_dir.join("mute_audit.log");
 
-/***** LIBRARY *****/
-/// Defines some shared state for the whole app.
-///
-/// Actually just wraps [`MutableAppState`] plus some non-mutable stuff with some locks and junk.
-#[derive(Clone, Debug)]
-pub struct AppState {
-    // Immutable part
-    /// The location of all the config files.
-    config_dir: PathBuf,
-    /// The location of any chache files (e.g., tray icon thumbnail).
-    cache_dir:  PathBuf,
+        // Rotate the log if it's too large
+        if fs::metadata(&path).map(|md| md.len() >= MUTE_AUDIT_MAX_SIZE).unwrap_or(false) {
+            let rotated: PathBuf = self.config_dir.join("mute_audit.log.1");
+            debug!("Rotating mute audit log '{}' to '{}'...", path.display(), rotated.display());
+            if let Err(err) = fs::rename(&path, &rotated) {
+                return Err(Error::MuteAuditRotate { from: path, to: rotated, err });
+            }
+        }
 
-    // Mutable part
-    /// The mutable part of the app state behind a lock.
-    mut_state: Arc<RwLock<MutableAppState>>,
+        // Write the line
+        debug!("Writing mute change {old:?} -> {new:?} by '{source}' to audit log '{}'...", path.display());
+        let mut handle: File = match OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(handle) => handle,
+            Err(err) => return Err(Error::MuteAuditWrite { path, err }),
+        };
+        if let Err(err) = writeln!(handle, "{}\t{}\t{:?}\t{:?}", self.now().to_rfc3339(), source, old, new) {
+            return Err(Error::MuteAuditWrite { path, err });
+        }
+        Ok(())
+    }
 }
-impl AppState {
-    /// Constructor for the AppState.
+
+
+
+/// Batches several [`Change`]s to an [`AppState`], such that they are applied (and written to disk) at once.
+///
+/// Build one with [`AppState::transaction()`], e.g.:
+/// ```ignore
+/// state.transaction().add_server(server).set_poll_interval("prod", Duration::from_secs(30)).commit()?;
+/// ```
+#[derive(Debug)]
+pub struct Transaction<'s> {
+    /// The state to change.
+    state:   &'s AppState,
+    /// What made the changes, for the mute audit log.
+    source:  &'s str,
+    /// The changes to apply, in order.
+    changes: Vec<Change>,
+}
+impl<'s> Transaction<'s> {
+    /// Sets what made the changes, which is recorded in the mute audit log (see [`AppState::access_mut_from()`]).
     ///
     /// # Arguments
-    /// - `config_dir`: The directory where the app's persistent config is stored.
+    /// - `source`: Some description of what triggered the changes (e.g., `"gui"`).
     ///
     /// # Returns
-    /// A new AppState that is ready to be used in the app.
-    ///
-    /// # Errors
-    /// This function may error if we failed to load any of the persistent state in the `config_dir`.
+    /// Self for chaining.
     #[inline]
-    pub fn new(config_dir: PathBuf) -> Result<Self, Error> {
-        info!("Initializing AppState...");
-
-        // Generate additional paths
-        let cache_dir: PathBuf = config_dir.join("cache");
-
-        // Build the mutable state
-        let mut_state: Arc<RwLock<MutableAppState>> = Arc::new(RwLock::new(MutableAppState::new(&config_dir)?));
+    pub fn source(mut self, source: &'s str) -> Self {
+        self.source = source;
+        self
+    }
 
-        // OK, build self
-        Ok(Self { config_dir, cache_dir, mut_state })
+    /// Adds any [`Change`] to the transaction.
+    ///
+    /// # Arguments
+    /// - `change`: The [`Change`] to apply after the ones before it.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn change(mut self, change: Change) -> Self {
+        self.changes.push(change);
+        self
     }
 
-    /// Returns the path of the persistent configuration for the app.
+    /// Adds a new server.
+    ///
+    /// # Arguments
+    /// - `server`: The [`ServerConfig`] to add.
     ///
     /// # Returns
-    /// A [`Path`] describing where to find the folder.
+    /// Self for chaining.
     #[inline]
-    pub fn config_dir(&self) -> &Path { &self.config_dir }
+    pub fn add_server(self, server: ServerConfig) -> Self { self.change(Change::AddServer(server)) }
 
-    /// Returns the path of the temporary cache for the app.
+    /// Removes a server.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the server to remove.
     ///
     /// # Returns
-    /// A [`Path`] describing where to find the folder.
+    /// Self for chaining.
     #[inline]
-    pub fn cache_dir(&self) -> &Path { &self.cache_dir }
+    pub fn remove_server(self, name: impl Into<String>) -> Self { self.change(Change::RemoveServer { name: name.into() }) }
 
-    /// Provides read-only access to the mutable part of the state.
+    /// Changes how often a server is polled.
     ///
     /// # Arguments
-    /// - `access_fn`: Some closure that can access the contents of the mutable app state.
+    /// - `name`: The name of the server to change.
+    /// - `interval`: The new [`ServerConfig::poll_interval`].
     ///
-    /// # Errors
-    /// This function errors if the given `access_fn` errors.
+    /// # Returns
+    /// Self for chaining.
     #[inline]
-    pub fn access<R>(&self, access_fn: impl FnOnce(&MutableAppState) -> R) -> R {
-        let lock: RwLockReadGuard<MutableAppState> = self.mut_state.read();
-        access_fn(&*lock)
-    }
+    pub fn set_poll_interval(self, name: impl Into<String>, interval: Duration) -> Self { self.change(Change::SetPollInterval { name: name.into(), interval }) }
 
-    /// Provides write access to the mutable part of the state.
+    /// Changes the mute state.
+    ///
+    /// # Arguments
+    /// - `muted`: The new [`MuteState`].
     ///
-    /// At the end of this function, if the closure did not error, then the disk is updated with the mutated state.
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn set_muted(self, muted: MuteState) -> Self { self.change(Change::SetMuted(muted)) }
+
+    /// Snoozes events with some title.
     ///
     /// # Arguments
-    /// - `access_fn`: Some closure that can access the contents of the mutable app state.
+    /// - `title`: The title of the events to snooze.
+    /// - `snooze`: Until when to snooze them, or [`MuteState::Unmuted`] to unsnooze them.
     ///
-    /// # Errors
-    /// This function errors if the given `access_fn` errors, or then if writing the state back errors.
+    /// # Returns
+    /// Self for chaining.
     #[inline]
-    pub fn access_mut<R, E>(&self, access_fn: impl FnOnce(&mut MutableAppState) -> Result<R, E>) -> Result<Result<R, Error>, E> {
-        // Provide mutable access, with its own, unique lock
-        let res: R = {
-            let mut lock: RwLockWriteGuard<MutableAppState> = self.mut_state.write();
-            access_fn(&mut *lock)?
-        };
+    pub fn snooze(self, title: impl Into<String>, snooze: MuteState) -> Self { self.change(Change::Snooze { title: title.into(), snooze }) }
 
-        // Now sync the mutable app state back
-        let config_path: PathBuf = self.config_dir.join("server_events.toml");
-        if let Err(err) = self.mut_state.read().sync(&config_path) {
-            return Ok(Err(err));
+    /// Applies all changes at once, and writes the result to disk once.
+    ///
+    /// If any of the changes fails, none of them are applied and nothing is written.
+    ///
+    /// # Errors
+    /// This function errors if any of the changes is invalid (see [`MutableAppState::apply()`]), or if we failed to write the config.
+    pub fn commit(self) -> Result<(), Error> {
+        if self.changes.is_empty() {
+            return Ok(());
+        }
+        debug!("Committing transaction of {} change(s) by '{}'...", self.changes.len(), self.source);
+        let changes: Vec<Change> = self.changes;
+        match self.state.access_mut_from(self.source, |state| state.apply(changes)) {
+            Ok(res) => res,
+            Err(err) => Err(err),
         }
-
-        // OK, return the result
-        Ok(Ok(res))
     }
 }
 
@@ -213,6 +2552,107 @@ impl AppState {
 pub struct MutableAppState {
     /// Whether notifcations are muted or not and, if not, how to unmute ourselves.
     pub muted: MuteState,
+    /// The remote servers that we keep track of.
+    pub servers: Vec<ServerConfig>,
+    /// The layout of the window.
+    pub layout: GuiLayout,
+    /// Whether to keep a log of every change to the mute state.
+    pub mute_audit: bool,
+    /// The maximum number of events we keep around.
+    pub max_events: usize,
+    /// The maximum age of events we keep around, if any.
+    pub retention_age: Option<Duration>,
+    /// Whether to write a crash report when the app exits with an error.
+    pub crash_reports: bool,
+    /// Whether to notify about the events that a server reports on its first successful poll.
+    pub notify_on_first_poll: bool,
+    /// Whether a left-click on the tray icon opens a small popover instead of the main window.
+    pub tray_popover: bool,
+    /// The backends that tell the user about new events.
+    pub notifiers: Vec<NotifierConfig>,
+    /// The `User-Agent` to send to servers, if not `server-events/<version>`.
+    pub user_agent: Option<String>,
+    /// If given, new events are also appended to a CSV file per day in this directory.
+    pub csv_export_path: Option<PathBuf>,
+    /// If given, raises the severity of events that keep recurring.
+    pub escalation: Option<Escalation>,
+    /// The items in the tray icon's menu, in order. If empty, a default menu is used.
+    pub menu: Vec<MenuItemSpec>,
+    /// Event titles that we don't notify about (keyed by title), and until when.
+    pub snoozed: HashMap<String, MuteState>,
+    /// Whether to hold back notifications while the OS is in a do-not-disturb (or focus) mode.
+    pub follow_os_dnd: bool,
+    /// If given, the maximum number of servers that are polled at the same time.
+    pub max_concurrent_polls: Option<usize>,
+    /// If given, the address on which we serve `/healthz` and `/metrics`.
+    pub metrics_addr: Option<SocketAddr>,
+    /// How the event list is sorted.
+    pub sort: SortKey,
+    /// The icon to show in front of events of a given severity.
+    pub severity_icons: HashMap<Severity, String>,
+    /// The maximum number of notifications shown per minute (0 for no limit).
+    pub max_notifications_per_minute: usize,
+    /// The language that the user chose, if any (see [`MutableAppState::lang()`]).
+    pub language: Option<Lang>,
+    /// The maximum number of pinned events we keep around, apart from [`MutableAppState::max_events`].
+    pub max_pinned_events: usize,
+    /// What to do with events whose timestamp is missing or lies in the future.
+    pub timestamps: TimestampPolicy,
+    /// If given, the path of the Unix socket on which we listen for commands.
+    pub control_socket: Option<PathBuf>,
+    /// The width of the main window (in physical pixels), if known.
+    pub width: Option<u32>,
+    /// The height of the main window (in physical pixels), if known.
+    pub height: Option<u32>,
+    /// The horizontal position of the main window (in physical pixels), if known. May be negative on multi-monitor setups.
+    pub x: Option<i32>,
+    /// The vertical position of the main window (in physical pixels), if known. May be negative on multi-monitor setups.
+    pub y: Option<i32>,
+    /// The keys of the most recent events that we've seen, such that they are not new again once forgotten. These are kept next to
+    /// the event history across restarts.
+    pub seen: SeenEvents,
+    /// How many keys of seen events to remember at most.
+    pub max_seen: usize,
+    /// Only events at least this severe are notified about.
+    pub min_severity: Severity,
+    /// If given, every new event is POSTed to this URL (see [`ConfigFile::webhook`]).
+    pub webhook: Option<Url>,
+    /// Whether events are no longer sent to the webhook while notifications are muted.
+    pub webhook_respects_mute: bool,
+    /// The number of consecutive failed polls after which an unreachable server turns the tray icon into its warning variant.
+    pub warn_after_failures: u32,
+
+    /// The events that we received from the servers, newest first. These are kept in the event history across restarts.
+    pub events: VecDeque<Event>,
+    /// The names of the servers that failed their last poll.
+    pub offline: HashSet<String>,
+    /// The names of the servers that failed their last poll because we couldn't authenticate. These are also in [`MutableAppState::offline`].
+    pub auth_failed: HashSet<String>,
+    /// How the last poll of every server that we polled went, by name.
+    pub status: HashMap<String, ServerStatus>,
+    /// The number of consecutive failed polls of every server that has failed one, by name.
+    pub failures: HashMap<String, u32>,
+    /// The keys of the events that the user acknowledged from the tray icon, which no longer count towards its badge. Unlike
+    /// [`MutableAppState::seen`], this is forgotten when the app exits.
+    pub acknowledged: HashSet<String>,
+    /// The [`HttpCache`] of every server that we polled, by name.
+    pub http_cache: HashMap<String, HttpCache>,
+    /// When every server that we polled was last polled successfully, by name.
+    pub last_poll: HashMap<String, DateTime<Local>>,
+    /// Why the last attempt to write the config file or the event history failed, if it did.
+    pub sync_error: Option<String>,
+    /// When recent events occurred, by source and title, for [`MutableAppState::escalation`].
+    pub occurrences: HashMap<(String, String), VecDeque<DateTime<Local>>>,
+    /// The number of new events that every server reported since we started, by name.
+    pub ingested: HashMap<String, u64>,
+    /// How many events came in per minute recently.
+    pub activity: Activity,
+    /// When the config file was modified by the last write (or read) of ours, such that we can tell when someone else changed it.
+    pub synced: Option<SystemTime>,
+    /// Whether anything persistent changed since the last write (or read) of the config file (see [`MutableAppState::mark_dirty()`]).
+    dirty: bool,
+    /// Whether the events changed since the last write (or read) of the event history (see [`MutableAppState::mark_history_dirty()`]).
+    history_dirty: bool,
 }
 impl MutableAppState {
     /// Syncs this MutableAppState back to the disk.
@@ -222,11 +2662,14 @@ impl MutableAppState {
     /// # Arguments
     /// - `config_path`: The path to write the config file to.
     ///
+    /// The file is replaced atomically, by writing a temporary file next to it first. So if we're killed halfway, the old config
+    /// survives.
+    ///
     /// # Errors
     /// This function may error if it failed to write to disk.
     fn sync(&self, config_path: &Path) -> Result<(), Error> {
         // Build a config file
-        let config: ConfigFile = ConfigFile { muted: self.muted.clone() };
+        let config: ConfigFile = self.to_config();
 
         // Check if the target directory exists
         if let Some(parent) = config_path.parent() {
@@ -238,54 +2681,605 @@ impl MutableAppState {
             }
         }
 
-        // Write it to that path
+        // Write it to a temporary file in the same directory (and so on the same filesystem), and make sure that's on disk
         debug!("Syncing MutableAppState back to '{}'...", config_path.display());
-        match config.to_path_pretty(config_path) {
-            Ok(_) => {
-                info!("Synced MutableAppState back to '{}'", config_path.display());
-                Ok(())
-            },
-            Err(err) => Err(Error::ConfigWrite { path: config_path.into(), err }),
+        let mut tmp_path: OsString = config_path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        let tmp_path: PathBuf = tmp_path.into();
+        if let Err(err) = config.to_path_pretty(&tmp_path) {
+            return Err(Error::ConfigWrite { path: tmp_path, err });
+        }
+        // Opened for writing, as Windows doesn't flush read-only handles
+        if let Err(err) = OpenOptions::new().write(true).open(&tmp_path).and_then(|file| file.sync_all()) {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(Error::ConfigFlush { path: tmp_path, err });
+        }
+
+        // Then move it over the old one, which is atomic (and on Windows, replaces an existing file as well)
+        if let Err(err) = fs::rename(&tmp_path, config_path) {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(Error::ConfigReplace { from: tmp_path, to: config_path.into(), err });
+        }
+        // Only the directory knows about the rename, so flush that too where we can
+        #[cfg(unix)]
+        if let Some(parent) = config_path.parent() {
+            let _ = File::open(parent).and_then(|dir| dir.sync_all());
+        }
+        info!("Synced MutableAppState back to '{}'", config_path.display());
+        Ok(())
+    }
+
+    /// Syncs the events of this MutableAppState, and the keys of those we've seen, back to the disk such that they survive a restart.
+    ///
+    /// # Arguments
+    /// - `cache_dir`: The directory to write the [`HISTORY_FILE_NAME`] and [`SEEN_FILE_NAME`] files to.
+    ///
+    /// # Errors
+    /// This function may error if it failed to write to disk.
+    fn sync_history(&self, cache_dir: &Path) -> Result<(), Error> {
+        // The seen keys go first, such that we never keep events that we don't remember seeing
+        write_history(&cache_dir.join(SEEN_FILE_NAME), &self.seen)?;
+        write_history(&cache_dir.join(HISTORY_FILE_NAME), &self.events)?;
+        debug!("Synced {} event(s) back to '{}'", self.events.len(), cache_dir.display());
+        Ok(())
+    }
+
+    /// Returns the persistent part of this MutableAppState as a [`ConfigFile`].
+    ///
+    /// # Returns
+    /// A new [`ConfigFile`] with the current settings.
+    pub fn to_config(&self) -> ConfigFile {
+        ConfigFile {
+            muted: self.muted.clone(),
+            servers: self.servers.clone(),
+            layout: self.layout,
+            mute_audit: self.mute_audit,
+            max_events: self.max_events,
+            retention_age: self.retention_age,
+            crash_reports: self.crash_reports,
+            notify_on_first_poll: self.notify_on_first_poll,
+            tray_popover: self.tray_popover,
+            notifiers: self.notifiers.clone(),
+            user_agent: self.user_agent.clone(),
+            csv_export_path: self.csv_export_path.clone(),
+            escalation: self.escalation.clone(),
+            menu: self.menu.clone(),
+            snoozed: self.snoozed.clone(),
+            follow_os_dnd: self.follow_os_dnd,
+            max_concurrent_polls: self.max_concurrent_polls,
+            metrics_addr: self.metrics_addr,
+            sort: self.sort,
+            severity_icons: self.severity_icons.clone(),
+            max_notifications_per_minute: self.max_notifications_per_minute,
+            language: self.language,
+            max_pinned_events: self.max_pinned_events,
+            timestamps: self.timestamps,
+            control_socket: self.control_socket.clone(),
+            width: self.width,
+            height: self.height,
+            x: self.x,
+            y: self.y,
+            seen: SeenEvents::default(),
+            max_seen: self.max_seen,
+            min_severity: self.min_severity,
+            webhook: self.webhook.clone(),
+            webhook_respects_mute: self.webhook_respects_mute,
+            warn_after_failures: self.warn_after_failures,
+        }
+    }
+
+    /// Marks the persistent part of this MutableAppState as changed, such that [`AppState::access_mut()`] writes it back.
+    ///
+    /// The methods of the MutableAppState do this by themselves; this is for code that assigns persistent fields directly.
+    #[inline]
+    pub fn mark_dirty(&mut self) { self.dirty = true; }
+
+    /// Marks the events of this MutableAppState as changed, such that [`AppState::access_mut()`] writes them back.
+    ///
+    /// Like [`MutableAppState::mark_dirty()`], this is for code that changes [`MutableAppState::events`] directly.
+    #[inline]
+    pub fn mark_history_dirty(&mut self) { self.history_dirty = true; }
+
+    /// Replaces all settings with those in the given [`ConfigFile`].
+    ///
+    /// Runtime state, such as the received events, is left untouched.
+    ///
+    /// # Arguments
+    /// - `config`: The [`ConfigFile`] to take the new settings from.
+    pub fn replace_config(&mut self, config: ConfigFile) {
+        self.dirty = true;
+        self.muted = config.muted;
+        self.servers = config.servers;
+        self.layout = config.layout;
+        self.mute_audit = config.mute_audit;
+        self.max_events = config.max_events;
+        self.retention_age = config.retention_age;
+        self.crash_reports = config.crash_reports;
+        self.notify_on_first_poll = config.notify_on_first_poll;
+        self.tray_popover = config.tray_popover;
+        self.notifiers = config.notifiers;
+        self.user_agent = config.user_agent;
+        self.csv_export_path = config.csv_export_path;
+        self.escalation = config.escalation;
+        self.menu = config.menu;
+        self.snoozed = config.snoozed;
+        self.follow_os_dnd = config.follow_os_dnd;
+        self.max_concurrent_polls = config.max_concurrent_polls;
+        self.metrics_addr = config.metrics_addr;
+        self.sort = config.sort;
+        self.severity_icons = config.severity_icons;
+        self.max_notifications_per_minute = config.max_notifications_per_minute;
+        self.language = config.language;
+        self.max_pinned_events = config.max_pinned_events;
+        self.timestamps = config.timestamps;
+        self.control_socket = config.control_socket;
+        self.width = config.width;
+        self.height = config.height;
+        self.x = config.x;
+        self.y = config.y;
+        // Don't take `seen` from the file, as an imported config would otherwise make old events new again
+        self.max_seen = config.max_seen;
+        self.min_severity = config.min_severity;
+        self.webhook = config.webhook;
+        self.webhook_respects_mute = config.webhook_respects_mute;
+        self.warn_after_failures = config.warn_after_failures;
+    }
+
+    /// Merges the given [`ConfigFile`] into the current settings.
+    ///
+    /// Only the servers that we don't already know (by name) are added; all other settings are left untouched.
+    ///
+    /// # Arguments
+    /// - `config`: The [`ConfigFile`] to merge.
+    ///
+    /// # Returns
+    /// The number of servers that were added.
+    pub fn merge_config(&mut self, config: ConfigFile) -> usize {
+        let mut added: usize = 0;
+        for server in config.servers {
+            if !self.servers.iter().any(|other| other.name == server.name) {
+                self.servers.push(server);
+                added += 1;
+            }
+        }
+        self.dirty |= added > 0;
+        added
+    }
+
+    /// Adds the given events to the list of events we keep track of.
+    ///
+    /// Events that we already know (by ID and source) are ignored, and so are events that [`MutableAppState::prune()`] would remove
+    /// right away. Events that we've seen before but forgot about (see [`MutableAppState::seen`]) are added again, but as read and
+    /// without counting as new. Events without a timestamp or with one
+    /// too far in the future are handled according to [`MutableAppState::timestamps`]. New events that keep recurring (by source and title) are
+    /// escalated according to [`MutableAppState::escalation`]. The list is kept sorted newest-first, and is pruned with
+    /// [`MutableAppState::prune()`] afterwards.
+    ///
+    /// # Arguments
+    /// - `events`: The [`Event`]s to add.
+    /// - `now`: The current time, used to decide which events are too old to keep.
+    ///
+    /// # Returns
+    /// The events that were actually new.
+    pub fn ingest(&mut self, events: impl IntoIterator<Item = Event>, now: DateTime<Local>) -> Vec<Event> {
+        let before: usize = self.events.len();
+        let mut new: Vec<Event> = Vec::new();
+        let mut added: Vec<String> = Vec::new();
+        let mut seen: bool = false;
+        for mut event in events {
+            if self.events.iter().any(|e| e.id == event.id && e.source == event.source) {
+                continue;
+            }
+            if event.timestamp_flag == Some(TimestampFlag::Missing) && self.timestamps.drop_missing {
+                debug!("Dropping event '{}' from '{}' as it has no timestamp", event.id, event.source);
+                continue;
+            }
+            if let Ok(ahead) = (event.timestamp - now).to_std() {
+                if ahead > self.timestamps.future_tolerance {
+                    debug!("Clamping timestamp of event '{}' from '{}' to now as it lies {}s in the future", event.id, event.source, ahead.as_secs());
+                    event.timestamp = now;
+                    event.timestamp_flag = Some(TimestampFlag::Future);
+                }
+            }
+            if self.would_prune(&event, now) {
+                trace!("Ignoring event '{}' from '{}' as it's too old to keep", event.id, event.source);
+                continue;
+            }
+            added.push(event.key());
+            if !self.seen.insert(event.key(), self.max_seen) {
+                debug!("Restoring event '{}' from '{}' as read as we've seen it before", event.id, event.source);
+                event.read = true;
+                self.events.push_front(event);
+                continue;
+            }
+            if let Some(escalation) = &self.escalation {
+                let times: &mut VecDeque<DateTime<Local>> = self.occurrences.entry((event.source.clone(), event.title.clone())).or_default();
+                times.push_back(event.timestamp);
+                times.retain(|time| now.signed_duration_since(*time).to_std().unwrap_or_default() <= escalation.window);
+                if times.len() > escalation.count && escalation.to_severity > event.severity {
+                    debug!(
+                        "Escalating event '{}' from '{}' to {} as it occurred {} times recently",
+                        event.id,
+                        event.source,
+                        escalation.to_severity.variant(),
+                        times.len()
+                    );
+                    event.severity = escalation.to_severity;
+                }
+            }
+            seen = true;
+            *self.ingested.entry(event.source.clone()).or_default() += 1;
+            self.activity.record(now, event.severity);
+            new.push(event.clone());
+            self.events.push_front(event);
+        }
+        if !added.is_empty() {
+            self.events.make_contiguous().sort_by(|lhs, rhs| rhs.timestamp.cmp(&lhs.timestamp));
+        }
+        match self.escalation.as_ref().map(|escalation| escalation.window) {
+            Some(window) => self.occurrences.retain(|_, times| {
+                times.retain(|time| now.signed_duration_since(*time).to_std().unwrap_or_default() <= window);
+                !times.is_empty()
+            }),
+            None => self.occurrences.clear(),
+        }
+
+        // Only events that survive pruning change the history, but any that we hadn't seen yet change the seen keys
+        let dirty: bool = self.history_dirty;
+        self.prune(now);
+        let kept: HashSet<String> = self.events.iter().filter(|event| added.contains(&event.key())).map(Event::key).collect();
+        new.retain(|event| kept.contains(&event.key()));
+        self.history_dirty = dirty || seen || !kept.is_empty() || self.events.len() != before;
+        new
+    }
+
+    /// Checks whether [`MutableAppState::prune()`] would remove a (not pinned) event right after it is added.
+    ///
+    /// # Arguments
+    /// - `event`: The [`Event`] to check.
+    /// - `now`: The current time, used to decide which events are too old to keep.
+    ///
+    /// # Returns
+    /// True if the event is older than the [`MutableAppState::retention_age`], or if the list is full and the event is older than
+    /// any event in it.
+    fn would_prune(&self, event: &Event, now: DateTime<Local>) -> bool {
+        if let Some(age) = self.retention_age.and_then(|age| chrono::Duration::from_std(age).ok()) {
+            if event.timestamp < now - age {
+                return true;
+            }
+        }
+        let mut unpinned: usize = 0;
+        let mut oldest: Option<DateTime<Local>> = None;
+        for other in self.events.iter().filter(|other| !other.pinned) {
+            unpinned += 1;
+            oldest = Some(other.timestamp);
+        }
+        unpinned >= self.max_events && oldest.is_some_and(|oldest| event.timestamp < oldest)
+    }
+
+    /// Removes events that exceed either the [`MutableAppState::max_events`] or the [`MutableAppState::retention_age`].
+    ///
+    /// Pinned events don't count towards either, and are only removed when they exceed [`MutableAppState::max_pinned_events`].
+    ///
+    /// Assumes the events are sorted newest-first.
+    ///
+    /// # Arguments
+    /// - `now`: The current time, used to decide which events are too old to keep.
+    ///
+    /// # Returns
+    /// The number of events that were removed.
+    pub fn prune(&mut self, now: DateTime<Local>) -> usize {
+        let before: usize = self.events.len();
+        let (max_events, max_pinned): (usize, usize) = (self.max_events, self.max_pinned_events);
+        let (mut events, mut pinned): (usize, usize) = (0, 0);
+        self.events.retain(|event| {
+            if event.pinned {
+                pinned += 1;
+                pinned <= max_pinned
+            } else {
+                events += 1;
+                events <= max_events
+            }
+        });
+        if let Some(age) = self.retention_age.and_then(|age| chrono::Duration::from_std(age).ok()) {
+            let cutoff: DateTime<Local> = now - age;
+            self.events.retain(|event| event.pinned || event.timestamp >= cutoff);
+        }
+        self.history_dirty |= self.events.len() < before;
+        before - self.events.len()
+    }
+
+    /// Counts the events of a server that the user hasn't seen yet.
+    ///
+    /// # Arguments
+    /// - `source`: The name of the server to count the events of.
+    ///
+    /// # Returns
+    /// The number of unread events from that server.
+    #[inline]
+    pub fn unread(&self, source: &str) -> usize { self.events.iter().filter(|event| self.is_unread(event) && event.source == source).count() }
+
+    /// Checks whether an event counts towards the badge on the tray icon.
+    ///
+    /// # Arguments
+    /// - `event`: The [`Event`] to check.
+    ///
+    /// # Returns
+    /// True if the event is neither read nor acknowledged (see [`MutableAppState::acknowledged`]).
+    #[inline]
+    pub fn is_unread(&self, event: &Event) -> bool { !event.read && !self.acknowledged.contains(&event.key()) }
+
+    /// Acknowledges the newest event that still counts towards the badge on the tray icon.
+    ///
+    /// Its key is also remembered in [`MutableAppState::seen`], such that it isn't new again if the server sends it once more.
+    ///
+    /// # Returns
+    /// The key of the acknowledged event, or [`None`] if there was nothing left to acknowledge.
+    pub fn acknowledge_newest(&mut self) -> Option<String> {
+        let key: String = self.events.iter().find(|event| self.is_unread(event))?.key();
+        self.history_dirty |= self.seen.insert(key.clone(), self.max_seen);
+        self.acknowledged.insert(key.clone());
+        Some(key)
+    }
+
+    /// Marks all events as read.
+    ///
+    /// # Returns
+    /// The number of events that were unread before.
+    pub fn mark_read(&mut self) -> usize {
+        let mut marked: usize = 0;
+        for event in self.events.iter_mut().filter(|event| !event.read) {
+            event.read = true;
+            marked += 1;
+        }
+        self.history_dirty |= marked > 0;
+        marked
+    }
+
+    /// Applies the given [`Change`]s to the settings, in order.
+    ///
+    /// This is all or nothing: if any of the changes is invalid, the settings are restored to what they were before (including whether
+    /// they still have to be written to disk).
+    ///
+    /// # Arguments
+    /// - `changes`: The [`Change`]s to apply.
+    ///
+    /// # Errors
+    /// This function errors if a change refers to an unknown server, or adds one with a name that is already in use.
+    pub fn apply(&mut self, changes: impl IntoIterator<Item = Change>) -> Result<(), Error> {
+        let (backup, dirty): (ConfigFile, bool) = (self.to_config(), self.dirty);
+        self.dirty = true;
+        for change in changes {
+            let res: Result<(), Error> = match change {
+                Change::AddServer(server) => {
+                    if self.servers.iter().any(|other| other.name == server.name) {
+                        Err(Error::ServerExists { name: server.name })
+                    } else {
+                        self.servers.push(server);
+                        Ok(())
+                    }
+                },
+                Change::RemoveServer { name } => match self.servers.iter().position(|server| server.name == name) {
+                    Some(i) => {
+                        self.servers.remove(i);
+                        Ok(())
+                    },
+                    None => Err(Error::ServerUnknown { name }),
+                },
+                Change::SetPollInterval { name, interval } => match self.servers.iter_mut().find(|server| server.name == name) {
+                    Some(server) => {
+                        server.poll_interval = interval;
+                        Ok(())
+                    },
+                    None => Err(Error::ServerUnknown { name }),
+                },
+                Change::SetMuted(muted) => {
+                    self.muted = muted;
+                    Ok(())
+                },
+                Change::Snooze { title, snooze } => {
+                    if snooze.is_unmuted() {
+                        self.snoozed.remove(&title);
+                    } else {
+                        self.snoozed.insert(title, snooze);
+                    }
+                    Ok(())
+                },
+            };
+            if let Err(err) = res {
+                self.replace_config(backup);
+                self.dirty = dirty;
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the language to show the client in.
+    ///
+    /// # Returns
+    /// The [`Lang`] that the user chose, or else the one that matches the OS' locale (see [`Lang::resolve()`]).
+    #[inline]
+    pub fn lang(&self) -> Lang { Lang::resolve(self.language) }
+
+    /// Finds the icon to show in front of the given event.
+    ///
+    /// The [`ServerConfig::icon`] of the event's source takes precedence over the [`MutableAppState::severity_icons`].
+    ///
+    /// # Arguments
+    /// - `event`: The [`Event`] to find the icon of.
+    ///
+    /// # Returns
+    /// The icon, or [`None`] if the user didn't configure any for this event.
+    pub fn icon(&self, event: &Event) -> Option<&str> {
+        self.servers
+            .iter()
+            .find(|server| server.name == event.source)
+            .and_then(|server| server.icon.as_deref())
+            .or_else(|| self.severity_icons.get(&event.severity).map(String::as_str))
+    }
+
+    /// Checks whether events with the given title are snoozed.
+    ///
+    /// # Arguments
+    /// - `title`: The title of the events.
+    /// - `now`: The current time, to decide whether a timed snooze has passed.
+    ///
+    /// # Returns
+    /// True if the user doesn't want to be notified about such events right now.
+    pub fn is_snoozed(&self, title: &str, now: DateTime<Local>) -> bool {
+        match self.snoozed.get(title) {
+            Some(MuteState::After(deadline)) => *deadline > now.with_timezone(&Utc),
+            Some(snooze) => snooze.is_muted(),
+            None => false,
         }
     }
+
+    /// Forgets about snoozed titles whose snooze has passed.
+    ///
+    /// # Arguments
+    /// - `now`: The current time.
+    ///
+    /// # Returns
+    /// The number of titles that were unsnoozed.
+    pub fn resolve_snoozes(&mut self, now: DateTime<Local>) -> usize {
+        let before: usize = self.snoozed.len();
+        self.snoozed.retain(|_, snooze| {
+            snooze.tick(now);
+            snooze.is_muted()
+        });
+        self.dirty |= self.snoozed.len() < before;
+        before - self.snoozed.len()
+    }
 }
 impl MutableAppState {
     /// Constructor for the MutableAppState.
     ///
     /// # Arguments
     /// - `config_dir`: The directory where the app's persistent config is stored.
+    /// - `clock`: The [`Clock`] used to resolve timed mute states.
     ///
     /// # Returns
     /// A new MutableAppState that is ready to be used in the app.
     ///
     /// # Errors
     /// This function may error if we failed to load any of the persistent state in the `config_dir`.
-    pub fn new(config_dir: &PathBuf) -> Result<Self, Error> {
+    pub fn new(config_dir: &PathBuf, clock: &dyn Clock) -> Result<Self, Error> {
         info!("Initializing MutableAppState...");
 
         // Attempt to load the config file
-        let config_path: PathBuf = config_dir.join("server_events.toml");
-        debug!("Loading config file from '{}'...", config_path.display());
-        let mut config: ConfigFile = match ConfigFile::from_path(&config_path) {
-            Ok(config) => config,
-            Err(serializable::Error::FileOpen { path, err }) => {
-                if err.kind() == ErrorKind::NotFound {
-                    // Use a default thing instead (we'll catch it on the next resync)
-                    debug!("Config file '{}' not found; using default", config_path.display());
-                    ConfigFile::default()
-                } else {
-                    return Err(Error::ConfigLoad { path: config_path, err: serializable::Error::FileOpen { path, err } });
-                }
+        let config_path: PathBuf = config_dir.join(CONFIG_FILE_NAME);
+        let mut config: ConfigFile = match read_config(&config_path)? {
+            Some(config) => config,
+            None => {
+                // Use a default thing instead (we'll catch it on the next resync)
+                debug!("Config file '{}' not found; using default", config_path.display());
+                ConfigFile::default()
             },
-            Err(err) => return Err(Error::ConfigLoad { path: config_path, err }),
         };
 
-        // Resolve the muted state in case it was supposed to last until the last exit
-        if matches!(config.muted, MuteState::NextBoot) {
-            config.muted = MuteState::Unmuted;
+        // Resolve the muted state in case it was supposed to last until the last exit or has expired in the meantime
+        let now: DateTime<Utc> = clock.now().with_timezone(&Utc);
+        match config.muted {
+            MuteState::NextBoot => config.muted = MuteState::Unmuted,
+            MuteState::After(deadline) if deadline <= now => {
+                debug!("Mute deadline {deadline} has passed; unmuting");
+                config.muted = MuteState::Unmuted;
+            },
+            _ => {},
+        }
+        config.snoozed.retain(|title, snooze| match snooze {
+            MuteState::Unmuted | MuteState::NextBoot => false,
+            MuteState::After(deadline) if *deadline <= now => {
+                debug!("Snooze deadline {deadline} of '{title}' has passed; unsnoozing");
+                false
+            },
+            _ => true,
+        });
+
+        // Deadlines written by older versions already denote the right moment, but are rewritten in the current format
+        // (which `AppState::with_clock()` does right away)
+        let migrate: bool = stale_deadlines(&config_path);
+        if migrate {
+            info!("Migrating mute deadlines in config file '{}' to the current format", config_path.display());
         }
 
         // OK, build self
-        Ok(Self { muted: config.muted })
+        let mut state: Self = Self {
+            muted: config.muted,
+            servers: config.servers,
+            layout: config.layout,
+            mute_audit: config.mute_audit,
+            max_events: config.max_events,
+            retention_age: config.retention_age,
+            crash_reports: config.crash_reports,
+            notify_on_first_poll: config.notify_on_first_poll,
+            tray_popover: config.tray_popover,
+            notifiers: config.notifiers,
+            user_agent: config.user_agent,
+            csv_export_path: config.csv_export_path,
+            escalation: config.escalation,
+            menu: config.menu,
+            snoozed: config.snoozed,
+            follow_os_dnd: config.follow_os_dnd,
+            max_concurrent_polls: config.max_concurrent_polls,
+            metrics_addr: config.metrics_addr,
+            sort: config.sort,
+            severity_icons: config.severity_icons,
+            max_notifications_per_minute: config.max_notifications_per_minute,
+            language: config.language,
+            max_pinned_events: config.max_pinned_events,
+            timestamps: config.timestamps,
+            control_socket: config.control_socket,
+            width: config.width,
+            height: config.height,
+            x: config.x,
+            y: config.y,
+            seen: config.seen,
+            max_seen: config.max_seen,
+            min_severity: config.min_severity,
+            webhook: config.webhook,
+            webhook_respects_mute: config.webhook_respects_mute,
+            warn_after_failures: config.warn_after_failures,
+
+            events: VecDeque::new(),
+            offline: HashSet::new(),
+            auth_failed: HashSet::new(),
+            status: HashMap::new(),
+            failures: HashMap::new(),
+            acknowledged: HashSet::new(),
+            http_cache: HashMap::new(),
+            last_poll: HashMap::new(),
+            sync_error: None,
+            occurrences: HashMap::new(),
+            ingested: HashMap::new(),
+            activity: Activity::default(),
+            synced: modified(&config_path),
+            dirty: migrate,
+            history_dirty: false,
+        };
+
+        // Remember what we've seen, which older versions kept in the config file instead
+        let cache_dir: PathBuf = config_dir.join(CACHE_DIR_NAME);
+        match recover_history::<SeenEvents>(&cache_dir.join(SEEN_FILE_NAME)) {
+            Some(seen) => state.seen = seen,
+            None if !state.seen.is_empty() => {
+                info!("Moving seen events from config file '{}' to '{}'", config_path.display(), cache_dir.join(SEEN_FILE_NAME).display());
+                state.dirty = true;
+                state.history_dirty = true;
+            },
+            None => {},
+        }
+
+        // Pick up the events where we left them, minus those that have grown too old in the meantime
+        let history_path: PathBuf = cache_dir.join(HISTORY_FILE_NAME);
+        if let Some(mut events) = recover_history::<VecDeque<Event>>(&history_path) {
+            events.make_contiguous().sort_by(|lhs, rhs| rhs.timestamp.cmp(&lhs.timestamp));
+            state.events = events;
+            let dirty: bool = state.history_dirty;
+            state.prune(clock.now());
+            state.history_dirty = dirty;
+        }
+        Ok(state)
     }
 }
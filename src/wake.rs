@@ -0,0 +1,63 @@
+//  WAKE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 09:06:57
+//  Last edited:
+//    16 Oct 2026, 09:06:57
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Notices when the machine wakes up from sleep, such that everything
+//!   that went stale in the meantime can be refreshed right away.
+//!   
+//!   This is done coarsely, by checking every so often whether much more
+//!   time has passed than we slept for.
+//
+
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use log::{debug, info};
+
+use crate::app::{AppEvent, AppEventLoopProxy};
+
+
+/***** CONSTANTS *****/
+/// How often we check whether the machine slept.
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// How much more time than [`CHECK_INTERVAL`] must have passed before we think the machine slept.
+const SLEEP_THRESHOLD: Duration = Duration::from_secs(30);
+
+
+
+
+
+/***** LIBRARY *****/
+/// Starts watching for the machine waking up from sleep, on a thread of its own.
+///
+/// Whenever it does, an [`AppEvent::Woke`] is sent to the main loop. The thread stops once the main loop is gone.
+///
+/// Both the wall clock and the monotonic clock are checked, since the latter doesn't advance during sleep on every OS.
+///
+/// # Arguments
+/// - `proxy`: An [`AppEventLoopProxy`] to tell the main loop about waking up with.
+pub fn watch(proxy: AppEventLoopProxy) {
+    debug!("Watching for the machine waking up from sleep...");
+    thread::spawn(move || loop {
+        let (before, before_wall): (Instant, SystemTime) = (Instant::now(), SystemTime::now());
+        thread::sleep(CHECK_INTERVAL);
+
+        // The wall clock may go backwards (e.g., when it's synced), which we simply don't count as sleeping
+        let elapsed: Duration = before.elapsed().max(before_wall.elapsed().unwrap_or_default());
+        if elapsed > CHECK_INTERVAL + SLEEP_THRESHOLD {
+            let slept: Duration = elapsed - CHECK_INTERVAL;
+            info!("Machine seems to have been asleep for {}s", slept.as_secs());
+            if proxy.send_event(AppEvent::Woke { slept }).is_err() {
+                debug!("Event loop is gone; no longer watching for the machine waking up");
+                return;
+            }
+        }
+    });
+}